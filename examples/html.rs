@@ -1,6 +1,9 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use mdtrans::{transform_markdown_string, MarkdownTransformer};
+use mdtrans::{
+    AlertKind, ImageTags, LinkKind, ListItemInfo, MarkdownTransformer, Pipeline,
+    StatefulTransformer,
+};
 
 extern crate mdtrans;
 
@@ -24,12 +27,12 @@ impl MarkdownTransformer for Transformer {
         format!("<div class=\"quote\">{text}</div>")
     }
 
-    fn transform_image(
-        &mut self,
-        alt: String,
-        url: String,
-        add_tags: std::collections::HashMap<String, String>,
-    ) -> String {
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let kind = kind.label().to_lowercase();
+        format!("<div class=\"alert alert-{kind}\">{body}</div>")
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
         let mut metadata = " ".to_string();
         metadata += add_tags
             .into_iter()
@@ -43,6 +46,10 @@ impl MarkdownTransformer for Transformer {
         format!("<img src=\"{url}\" alt=\"{alt}\"{metadata}>")
     }
 
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        format!("<figure>{image}<figcaption>{caption}</figcaption></figure>")
+    }
+
     fn transform_bold(&mut self, text: String) -> String {
         format!("<strong>{text}</strong>")
     }
@@ -51,8 +58,12 @@ impl MarkdownTransformer for Transformer {
         format!("<em>{text}</em>")
     }
 
-    fn transform_link(&mut self, text: String, url: String) -> String {
-        format!("<a href=\"{url}\">{text}</a>")
+    fn transform_link(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        if kind == LinkKind::External {
+            format!("<a href=\"{url}\" rel=\"noopener\" target=\"_blank\">{text}</a>")
+        } else {
+            format!("<a href=\"{url}\">{text}</a>")
+        }
     }
 
     fn transform_header(&mut self, level: usize, text: String) -> String {
@@ -63,7 +74,12 @@ impl MarkdownTransformer for Transformer {
         format!("<code>{}</code>", self.sanitize_html(text))
     }
 
-    fn transform_codeblock(&mut self, lang: Option<String>, text: String) -> String {
+    fn transform_codeblock(
+        &mut self,
+        lang: Option<String>,
+        _flags: Vec<String>,
+        text: String,
+    ) -> String {
         let mut buffer = "<pre".to_string();
         if let Some(l) = lang {
             buffer += format!(" class=\"lang-{}\"", l.to_lowercase()).as_str();
@@ -72,17 +88,23 @@ impl MarkdownTransformer for Transformer {
         buffer
     }
 
-    fn peek_refurl(&mut self, slug: String, url: String) {
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
         self.refs.insert(slug, url);
     }
 
-    fn transform_reflink(&mut self, text: String, slug: String) -> String {
+    fn transform_reflink(&mut self, text: String, slug: String, title: Option<String>) -> String {
         let url = self.refs.get(&slug);
         assert!(url.is_some(), "Link reference {slug} not found");
-        self.transform_link(text, url.unwrap().clone())
+        let url = url.unwrap().clone();
+        let kind = LinkKind::classify(&url, None);
+        let link = self.transform_link(text, url, kind);
+        match title {
+            Some(title) => link.replacen("<a ", format!("<a title=\"{title}\" ").as_str(), 1),
+            None => link,
+        }
     }
 
-    fn transform_refurl(&mut self, _slug: String, _url: String) -> String {
+    fn transform_refurl(&mut self, _slug: String, _url: String, _title: Option<String>) -> String {
         "".to_string()
     }
 
@@ -93,7 +115,7 @@ impl MarkdownTransformer for Transformer {
         buffer
     }
 
-    fn transform_list_element(&mut self, element: String) -> String {
+    fn transform_list_element(&mut self, element: String, _info: ListItemInfo) -> String {
         format!("<li>{}</li>", self.sanitize_html(element))
     }
 
@@ -106,6 +128,23 @@ impl MarkdownTransformer for Transformer {
     }
 }
 
+// Reference links are only valid in the file defining them
+impl StatefulTransformer for Transformer {
+    type Snapshot = HashMap<String, String>;
+
+    fn reset(&mut self) {
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.refs.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.refs = snapshot;
+    }
+}
+
 fn create_page(post: String) -> String {
     format!(
         "
@@ -122,7 +161,7 @@ fn create_page(post: String) -> String {
 }
 
 fn main() {
-    let mut transformer = Transformer::default();
+    let mut pipeline = Pipeline::new().transformer(Transformer::default());
 
     for file in std::fs::read_dir("./examples/data").unwrap() {
         let tstart = std::time::Instant::now();
@@ -140,7 +179,7 @@ fn main() {
         let new_fname = fname.replace(".md", ".html");
         println!("{} -> {}", fname, new_fname);
         let post = std::fs::read_to_string(&post_file).unwrap();
-        let res = transform_markdown_string(post, &mut transformer).unwrap();
+        let res = pipeline.run_isolated(&post).unwrap();
         std::fs::write(PathBuf::from(new_fname), create_page(res)).unwrap();
         println!("Done in {:?}", tstart.elapsed());
     }
@@ -0,0 +1,89 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use mdtrans::{Document, MarkdownParser, MarkdownTransformer, Pipeline, Rule};
+use pest::Parser;
+
+extern crate mdtrans;
+
+// Allocator keeping track of the heap in use, and of its peak since the last `measure`
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+pub struct Transformer;
+
+impl MarkdownTransformer for Transformer {
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<strong>{text}</strong>")
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        format!("<p>{text}</p>")
+    }
+}
+
+// Peak of the heap used by `f`, and the heap still used by what it returns, in kB
+fn measure<F: FnOnce() -> R, R>(name: &str, f: F) -> R {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let res = f();
+    println!(
+        "{name:<10} peak {:>7} kB, kept {:>7} kB",
+        (PEAK.load(Ordering::Relaxed) - base) / 1024,
+        (CURRENT.load(Ordering::Relaxed) - base) / 1024,
+    );
+    res
+}
+
+const CHUNK: &str = "# Section title
+
+Some **bold** text, *italic* and `code` with a [link](https://example.com/page) in a paragraph.
+
+- first item
+- second item with **bold**
+  - nested item
+
+> A quote
+> on two lines
+
+```rust
+fn main() {}
+```
+
+";
+
+// Usage: cargo run --release --example peak_memory [number of sections]
+fn main() {
+    let sections = std::env::args()
+        .nth(1)
+        .map_or(2000, |n| n.parse().expect("Number of sections"));
+    let input = CHUNK.repeat(sections);
+    println!("input      {} kB", input.len() / 1024);
+
+    let pairs = measure("pest pairs", || {
+        MarkdownParser::parse(Rule::file, &input).unwrap()
+    });
+    drop(pairs);
+    let document = measure("document", || Document::parse(&input).unwrap());
+    drop(document);
+    let mut pipeline = Pipeline::new().transformer(Transformer);
+    measure("transform", || pipeline.run(&input).unwrap());
+}
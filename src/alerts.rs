@@ -0,0 +1,35 @@
+/// Kind of a GitHub alert, a quote starting with `[!KIND]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AlertKind {
+    /// Kind from the label between `[!` and `]`, case insensitive
+    pub fn from_label(label: &str) -> Option<AlertKind> {
+        [
+            AlertKind::Note,
+            AlertKind::Tip,
+            AlertKind::Important,
+            AlertKind::Warning,
+            AlertKind::Caution,
+        ]
+        .into_iter()
+        .find(|kind| kind.label().eq_ignore_ascii_case(label))
+    }
+
+    /// Label of the alert, as written in the markdown source (`NOTE`, `WARNING`, ...)
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertKind::Note => "NOTE",
+            AlertKind::Tip => "TIP",
+            AlertKind::Important => "IMPORTANT",
+            AlertKind::Warning => "WARNING",
+            AlertKind::Caution => "CAUTION",
+        }
+    }
+}
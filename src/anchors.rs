@@ -0,0 +1,292 @@
+use crate::{
+    context::CurrentSpan, prelude::*, Document, ElementContext, Errcode, LinkKind,
+    MarkdownTransformer, Span, TransformOptions,
+};
+
+/// Anchor of a heading, as generated by most markdown renderers: lowercase, spaces
+/// replaced by dashes and punctuation removed
+pub fn slugify(text: &str) -> String {
+    slugify_with(text, SlugCharset::Unicode)
+}
+
+/// Characters kept in the anchors of the headings, see `TransformOptions::slug_charset`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SlugCharset {
+    /// Keep the letters and digits of every script (`café` stays `café`)
+    #[default]
+    Unicode,
+    /// Transliterate the accented Latin letters to ASCII (`café` becomes `cafe`),
+    /// dropping the other non-ASCII characters
+    Ascii,
+}
+
+/// How the anchors of headings with the same text are told apart, see
+/// `TransformOptions::slug_dedup`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SlugDedup {
+    /// Suffix `-1`, `-2`, ... on the repeated anchors
+    #[default]
+    Counter,
+    /// Suffix made of 6 hexadecimal digits of a hash of the anchor and its occurrence,
+    /// on the repeated anchors
+    Hash,
+    /// Fail with `Errcode::DuplicateSlug`
+    Error,
+}
+
+fn slugify_with(text: &str, charset: SlugCharset) -> String {
+    let mut slug = String::new();
+    for c in text.trim().to_lowercase().chars() {
+        match c {
+            ' ' => slug.push('-'),
+            c if c.is_ascii_alphanumeric() || c == '-' || c == '_' => slug.push(c),
+            c if charset == SlugCharset::Ascii => slug += transliterate(c),
+            c if c.is_alphanumeric() => slug.push(c),
+            _ => {}
+        }
+    }
+    slug
+}
+
+// ASCII spelling of the lowercase Latin letters with diacritics, nothing for other characters
+fn transliterate(c: char) -> &'static str {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => "",
+    }
+}
+
+/// Link whose target could not be found in the checked document set
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// Path of the document containing the link
+    pub file: String,
+    pub url: String,
+    pub span: Span,
+}
+
+// Gives distinct anchors to headings with the same text, as set by `TransformOptions::slug_dedup`
+#[derive(Default)]
+pub(crate) struct AnchorGenerator {
+    occurrences: HashMap<String, usize>,
+    generated: HashSet<String>,
+    charset: SlugCharset,
+    dedup: SlugDedup,
+}
+
+impl AnchorGenerator {
+    pub(crate) fn new(options: &TransformOptions) -> AnchorGenerator {
+        AnchorGenerator {
+            charset: options.slug_charset,
+            dedup: options.slug_dedup,
+            ..AnchorGenerator::default()
+        }
+    }
+
+    // Anchor of the heading, the duplicated one when `SlugDedup::Error` is set
+    pub(crate) fn anchor(&mut self, text: &str) -> Result<String, String> {
+        let slug = slugify_with(text, self.charset);
+        let occurrence = self.occurrences.entry(slug.clone()).or_insert(0);
+        let mut n = *occurrence;
+        *occurrence += 1;
+        if n == 0 && !self.generated.contains(&slug) {
+            self.generated.insert(slug.clone());
+            return Ok(slug);
+        }
+        if self.dedup == SlugDedup::Error {
+            return Err(slug);
+        }
+        // NOTE    A suffixed anchor can be the anchor of another heading text ("A-1" after
+        //         two "A"), the suffix is bumped until the anchor is free
+        let anchor = loop {
+            n = n.max(1);
+            let candidate = match self.dedup {
+                SlugDedup::Hash => {
+                    // NOTE    FNV-1a, stable across platforms and compiler versions
+                    let mut hash: u64 = 0xcbf29ce484222325;
+                    for byte in format!("{slug}-{n}").bytes() {
+                        hash ^= byte as u64;
+                        hash = hash.wrapping_mul(0x100000001b3);
+                    }
+                    format!("{slug}-{:06x}", hash & 0xffffff)
+                }
+                _ => format!("{slug}-{n}"),
+            };
+            if !self.generated.contains(&candidate) {
+                break candidate;
+            }
+            n += 1;
+        };
+        self.occurrences.insert(slug, n + 1);
+        self.generated.insert(anchor.clone());
+        Ok(anchor)
+    }
+}
+
+// Anchors and checkable links of a document, gathered during the peek pass
+#[derive(Default)]
+struct LinkCollector {
+    span: CurrentSpan,
+    anchors: HashSet<String>,
+    generator: AnchorGenerator,
+    links: Vec<(String, Span)>,
+    duplicate: Option<(String, Span)>,
+}
+
+impl LinkCollector {
+    fn new(options: &TransformOptions) -> LinkCollector {
+        LinkCollector {
+            generator: AnchorGenerator::new(options),
+            ..LinkCollector::default()
+        }
+    }
+
+    fn add_link(&mut self, url: String) {
+        let span = self.span.get();
+        self.links.push((url, span));
+    }
+}
+
+impl MarkdownTransformer for LinkCollector {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.span.set(ctx);
+    }
+
+    fn peek_header(&mut self, _level: usize, text: String) {
+        match self.generator.anchor(&text) {
+            Ok(anchor) => {
+                self.anchors.insert(anchor);
+            }
+            Err(slug) => {
+                let span = self.span.get();
+                self.duplicate.get_or_insert((slug, span));
+            }
+        }
+    }
+
+    fn peek_link(&mut self, _text: String, url: String, kind: LinkKind) {
+        if kind != LinkKind::External {
+            self.add_link(url);
+        }
+    }
+
+    fn peek_refurl(&mut self, _slug: String, url: String, _title: Option<String>) {
+        if LinkKind::classify(&url, None) != LinkKind::External {
+            self.add_link(url);
+        }
+    }
+}
+
+/// Checks that the `#fragment` links and relative links of a set of documents
+/// point to existing documents and headings
+///
+/// Documents are identified by their path, relative links are resolved from the
+/// directory of the document containing them. Absolute paths are not checked.
+#[derive(Default)]
+pub struct LinkChecker {
+    documents: Vec<(String, LinkCollector)>,
+    options: TransformOptions,
+}
+
+impl LinkChecker {
+    /// Options of the documents added afterwards, setting how the anchors of their
+    /// headings are generated
+    pub fn options(mut self, options: TransformOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn add_document<S: ToString>(&mut self, path: S, input: &str) -> Result<(), Errcode> {
+        let mut collector = LinkCollector::new(&self.options);
+        Document::parse_with_mode(input, self.options.parse_mode)?
+            .transform_with_options(&mut collector, &self.options);
+        if let Some((slug, span)) = collector.duplicate.take() {
+            return Err(Errcode::DuplicateSlug { slug, span });
+        }
+        self.documents.push((path.to_string(), collector));
+        Ok(())
+    }
+
+    pub fn check(&self) -> Vec<BrokenLink> {
+        let mut broken = vec![];
+        for (file, collector) in self.documents.iter() {
+            for (url, span) in collector.links.iter() {
+                if !self.resolves(file, collector, url) {
+                    broken.push(BrokenLink {
+                        file: file.clone(),
+                        url: url.clone(),
+                        span: *span,
+                    });
+                }
+            }
+        }
+        broken
+    }
+
+    fn resolves(&self, file: &str, collector: &LinkCollector, url: &str) -> bool {
+        let url = url.split('?').next().unwrap_or(url);
+        let (path, fragment) = match url.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (url, None),
+        };
+        let target = if path.is_empty() {
+            collector
+        } else if path.starts_with('/') {
+            return true;
+        } else {
+            let path = resolve_path(file, path);
+            match self
+                .documents
+                .iter()
+                .find(|(f, _)| normalize_path(f) == path)
+            {
+                Some((_, target)) => target,
+                None => return false,
+            }
+        };
+        fragment.is_none_or(|fragment| target.anchors.contains(fragment))
+    }
+}
+
+// Path of `link` relative to the directory of `file`
+fn resolve_path(file: &str, link: &str) -> String {
+    let dir = file.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    normalize_path(&format!("{dir}/{link}"))
+}
+
+// Removes the `.` and `..` components of a path
+fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
@@ -0,0 +1,71 @@
+use crate::{
+    prelude::*, transform::TransformFramework, Document, Errcode, StatefulTransformer,
+    TransformOptions,
+};
+
+/// Transform several documents with the same transformer, returning the outputs in order
+///
+/// Each document is isolated from the others: the transformer state is restored after each
+/// one, and the footnotes are numbered per document. Use `TransformOptions::share_batch_state`
+/// to share the transformer state between the documents of the batch.
+/// The transformer is restored to its original state once the batch is done in both cases.
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run_batch` instead")]
+pub fn transform_markdown_batch<I, T>(
+    inputs: I,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<Vec<String>, Errcode>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+    T: StatefulTransformer,
+{
+    transform_batch(inputs, transformer, options)
+}
+
+pub(crate) fn transform_batch<I, T>(
+    inputs: I,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<Vec<String>, Errcode>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+    T: StatefulTransformer,
+{
+    let documents = inputs
+        .into_iter()
+        .map(|input| Document::parse_with_mode(input.as_ref(), options.parse_mode))
+        .collect::<Result<Vec<Document>, Errcode>>()?;
+
+    if !options.share_batch_state {
+        return Ok(documents
+            .iter()
+            .map(|doc| doc.transform_isolated(transformer, options))
+            .collect());
+    }
+
+    let snapshot = transformer.snapshot();
+    // NOTE    Footnotes and citations are tied to the document containing them,
+    //         even when the state is shared
+    let peeked = documents
+        .iter()
+        .map(|doc| {
+            let mut parser = TransformFramework::new(transformer, options);
+            parser.peek_document(doc.root());
+            (parser.footnotes, parser.bibliography)
+        })
+        .collect::<Vec<_>>();
+    let outputs = documents
+        .iter()
+        .zip(peeked)
+        .map(|(doc, (footnotes, bibliography))| {
+            let mut parser = TransformFramework::new(transformer, options);
+            parser.footnotes = footnotes;
+            parser.bibliography = bibliography;
+            parser.transform_document(doc.root())
+        })
+        .collect();
+    transformer.restore(snapshot);
+    Ok(outputs)
+}
@@ -0,0 +1,174 @@
+use crate::{prelude::*, Document, ElementKind, Errcode, Node, Span};
+
+/// Changelog written in the [Keep a Changelog](https://keepachangelog.com) format: a `##`
+/// heading per release, a `###` heading per kind of change and a list of the changes
+///
+/// ```
+/// # use mdtrans::Changelog;
+/// let input = concat!(
+///     "## [Unreleased]\n\n",
+///     "### Fixed\n- Crash on empty input\n\n",
+///     "## [1.2.0] - 2024-03-01\n\n",
+///     "### Added\n- `extract_section`\n- Tab groups\n\n",
+///     "[1.2.0]: https://github.com/a/b/compare/v1.1.0...v1.2.0\n",
+/// );
+/// let changelog = Changelog::parse(input).unwrap();
+/// assert_eq!(changelog.releases.len(), 2);
+/// let release = changelog.release("1.2.0").unwrap();
+/// assert_eq!(release.date.as_deref(), Some("2024-03-01"));
+/// assert_eq!(release.url.as_deref(), Some("https://github.com/a/b/compare/v1.1.0...v1.2.0"));
+/// assert_eq!(release.changes("Added"), ["`extract_section`", "Tab groups"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Changelog {
+    /// Releases in the order of the document, usually the latest first
+    pub releases: Vec<Release>,
+}
+
+/// Release of a `Changelog`, from its `## [version] - date` heading
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    /// Version, without its brackets (`1.2.0`, `Unreleased`)
+    pub version: String,
+    pub date: Option<String>,
+    /// Marked `[YANKED]`, pulled because of a serious bug or security issue
+    pub yanked: bool,
+    /// URL of the version given by the reference definition `[version]: url`
+    pub url: Option<String>,
+    pub groups: Vec<ChangeGroup>,
+    /// Location of the heading of the release
+    pub span: Span,
+}
+
+/// Changes of a `Release` of the same kind, under a `### Added` heading
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeGroup {
+    /// Kind of the changes (`Added`, `Changed`, `Deprecated`, `Removed`, `Fixed`, `Security`)
+    pub name: String,
+    /// Markdown of the list elements, without their bullets
+    pub entries: Vec<String>,
+}
+
+impl Changelog {
+    pub fn parse(input: &str) -> Result<Changelog, Errcode> {
+        let source = blank_brackets(input);
+        let document = Document::parse(&source)?;
+        let mut releases: Vec<Release> = vec![];
+        let mut urls = HashMap::new();
+        for block in document.root().children() {
+            match block.kind() {
+                ElementKind::Header(2) => releases.push(Release::from_heading(input, block)),
+                ElementKind::Header(3) => {
+                    let Some(release) = releases.last_mut() else {
+                        continue;
+                    };
+                    release.groups.push(ChangeGroup {
+                        name: heading_text(input, block).to_string(),
+                        entries: vec![],
+                    });
+                }
+                ElementKind::List | ElementKind::OrderedList => {
+                    let Some(group) = releases.last_mut().and_then(|r| r.groups.last_mut()) else {
+                        continue;
+                    };
+                    group
+                        .entries
+                        .extend(block.children().iter().map(entry_text));
+                }
+                ElementKind::Paragraph => {
+                    urls.extend(reference_definitions(source_of(input, block)))
+                }
+                _ => {}
+            }
+        }
+        for release in releases.iter_mut() {
+            release.url = urls.get(release.version.to_lowercase().as_str()).cloned();
+        }
+        Ok(Changelog { releases })
+    }
+
+    /// Release of the version, without its brackets
+    pub fn release(&self, version: &str) -> Option<&Release> {
+        self.releases.iter().find(|r| r.version == version)
+    }
+}
+
+impl Release {
+    fn from_heading(input: &str, heading: &Node) -> Release {
+        let mut text = heading_text(input, heading);
+        let yanked = text.ends_with("[YANKED]");
+        text = text.trim_end_matches("[YANKED]").trim_end();
+        let (version, date) = match text.split_once(" - ") {
+            Some((version, date)) => (version, Some(date.trim().to_string())),
+            None => (text, None),
+        };
+        Release {
+            version: version.trim().trim_matches(['[', ']']).to_string(),
+            date,
+            yanked,
+            url: None,
+            groups: vec![],
+            span: heading.span(),
+        }
+    }
+
+    /// Entries of the group of changes `name`, empty if the release has no such group
+    pub fn changes(&self, name: &str) -> Vec<&str> {
+        self.groups
+            .iter()
+            .filter(|group| group.name.eq_ignore_ascii_case(name))
+            .flat_map(|group| group.entries.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+// NOTE    The grammar has no shortcut reference links, so the `[1.2.0]` of the release
+//         headings would not parse, nor the `[1.2.0]: url` definitions without the
+//         `reflinks` feature. Their brackets are blanked before parsing, keeping the spans
+//         of the nodes on the original input, where the headings and definitions are read.
+fn blank_brackets(input: &str) -> String {
+    input
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.starts_with("##") {
+                line.replace(['[', ']'], " ")
+            } else if let Some((label, url)) =
+                line.strip_prefix('[').and_then(|l| l.split_once("]:"))
+            {
+                format!(" {label}  {url}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+fn source_of<'i>(input: &'i str, node: &Node) -> &'i str {
+    let span = node.span();
+    &input[span.start..span.end]
+}
+
+fn heading_text<'i>(input: &'i str, heading: &Node) -> &'i str {
+    source_of(input, heading).trim_start_matches('#').trim()
+}
+
+// NOTE    Text of the element after its bullet, continuation lines included
+fn entry_text(element: &Node) -> String {
+    let text = element.as_str().trim_start();
+    let text = text
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, text)| text);
+    text.trim().to_string()
+}
+
+// Lines `[label]: url` of a paragraph, by lowercase label
+fn reference_definitions(paragraph: &str) -> Vec<(String, String)> {
+    paragraph
+        .lines()
+        .filter_map(|line| {
+            let (label, url) = line.trim().strip_prefix('[')?.split_once("]:")?;
+            let url = url.split_whitespace().next()?;
+            Some((label.to_lowercase(), url.to_string()))
+        })
+        .collect()
+}
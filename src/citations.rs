@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+// Keys cited in the document in order of first citation, gathered during the peek pass
+#[derive(Clone, Default)]
+pub(crate) struct Bibliography {
+    keys: Vec<String>,
+    pub(crate) section_emitted: bool,
+}
+
+impl Bibliography {
+    pub fn cite(&mut self, key: &str) {
+        if !self.keys.iter().any(|k| k == key) {
+            self.keys.push(key.to_string());
+        }
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.keys.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
@@ -0,0 +1,342 @@
+use core::ops::RangeInclusive;
+
+use crate::{
+    context::CurrentSpan, prelude::*, Document, ElementContext, Errcode, MarkdownTransformer, Span,
+    TransformOptions,
+};
+
+/// Code block extracted from a markdown document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub lang: Option<String>,
+    pub flags: Vec<String>,
+    pub content: String,
+    pub span: Span,
+}
+
+#[derive(Default)]
+struct CodeBlockCollector<'f> {
+    language_filter: Option<&'f str>,
+    span: CurrentSpan,
+    blocks: Vec<CodeBlock>,
+}
+
+impl MarkdownTransformer for CodeBlockCollector<'_> {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.span.set(ctx);
+    }
+
+    fn peek_codeblock(&mut self, language: Option<String>, flags: Vec<String>, text: String) {
+        if self
+            .language_filter
+            .is_some_and(|filter| language.as_deref() != Some(filter))
+        {
+            return;
+        }
+        self.blocks.push(CodeBlock {
+            lang: language,
+            flags,
+            content: text,
+            span: self.span.get(),
+        });
+    }
+}
+
+/// Extract the code blocks of a document in order, only the ones written in
+/// `language_filter` if it is set
+pub fn extract_code_blocks(
+    input: &str,
+    language_filter: Option<&str>,
+) -> Result<Vec<CodeBlock>, Errcode> {
+    let mut collector = CodeBlockCollector {
+        language_filter,
+        ..Default::default()
+    };
+    Document::parse(input)?.transform_with_options(&mut collector, &TransformOptions::default());
+    Ok(collector.blocks)
+}
+
+/// Diagram description language of a code block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagramKind {
+    /// ```` ```mermaid ````
+    Mermaid,
+    /// ```` ```dot ````
+    Graphviz,
+}
+
+impl DiagramKind {
+    pub fn from_language(language: &str) -> Option<DiagramKind> {
+        match language {
+            "mermaid" => Some(DiagramKind::Mermaid),
+            "dot" => Some(DiagramKind::Graphviz),
+            _ => None,
+        }
+    }
+
+    /// Language of the code block the diagram was written in
+    pub fn language(&self) -> &'static str {
+        match self {
+            DiagramKind::Mermaid => "mermaid",
+            DiagramKind::Graphviz => "dot",
+        }
+    }
+}
+
+/// Lines of a code block to highlight and whether to number them, from the `{1,3-5}` and
+/// `linenos` flags of its fence (```` ```rust {1,3-5} linenos ````)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodeblockLines {
+    /// Ranges of the lines to highlight, counting from 1
+    pub highlighted: Vec<RangeInclusive<usize>>,
+    pub numbered: bool,
+}
+
+impl CodeblockLines {
+    /// Lines given by the flags of a code block, the invalid ranges being ignored
+    pub fn from_flags(flags: &[String]) -> CodeblockLines {
+        let mut lines = CodeblockLines::default();
+        for flag in flags {
+            if let Some(ranges) = flag.strip_prefix('{').and_then(|f| f.strip_suffix('}')) {
+                lines
+                    .highlighted
+                    .extend(ranges.split(',').filter_map(parse_range));
+            } else if flag == "linenos" {
+                lines.numbered = true;
+            } else if let Some(value) = flag.strip_prefix("linenos=") {
+                lines.numbered = value != "false";
+            }
+        }
+        lines
+    }
+
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted.iter().any(|range| range.contains(&line))
+    }
+
+    /// Whether there is no line to highlight nor to number
+    pub fn is_empty(&self) -> bool {
+        self.highlighted.is_empty() && !self.numbered
+    }
+}
+
+fn parse_range(range: &str) -> Option<RangeInclusive<usize>> {
+    let range = range.trim();
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+    (start > 0 && start <= end).then_some(start..=end)
+}
+
+/// Color theme of the rendering of a code block, see `TransformOptions::code_themes`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CodeTheme {
+    Light,
+    Dark,
+}
+
+impl CodeTheme {
+    pub fn from_name(name: &str) -> Option<CodeTheme> {
+        match name {
+            "light" => Some(CodeTheme::Light),
+            "dark" => Some(CodeTheme::Dark),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodeTheme::Light => "light",
+            CodeTheme::Dark => "dark",
+        }
+    }
+
+    /// Themes a code block is rendered in, the one of its `theme=` flag or both of them
+    pub(crate) fn of_block(flags: &[String]) -> Vec<CodeTheme> {
+        match flags
+            .iter()
+            .find_map(|flag| flag.strip_prefix("theme="))
+            .and_then(CodeTheme::from_name)
+        {
+            Some(theme) => vec![theme],
+            None => vec![CodeTheme::Light, CodeTheme::Dark],
+        }
+    }
+}
+
+/// Information on a code block, given to the `*_codeblock_with_info` hooks when it has lines
+/// to highlight or to number, or once `TransformOptions::code_themes` is enabled
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodeblockInfo {
+    /// Words following the language on the opening fence, including the ones of the `lines`
+    pub flags: Vec<String>,
+    pub lines: CodeblockLines,
+    /// Themes to render the code block in, empty unless `TransformOptions::code_themes`
+    /// is enabled
+    pub themes: Vec<CodeTheme>,
+}
+
+/// Lines of `content` between the `ANCHOR: name` and `ANCHOR_END: name` markers, written
+/// in a comment of any language (`// ANCHOR: setup`) like the anchors of mdBook
+///
+/// The markers of the other regions found inside are removed, and a region without an end
+/// goes until the end of the content. Returns `None` if no region is named `name`.
+pub fn extract_anchor(content: &str, name: &str) -> Option<String> {
+    let mut lines = content.lines();
+    lines
+        .by_ref()
+        .find(|line| anchor_marker(line, "ANCHOR:") == Some(name))?;
+    let region = lines
+        .take_while(|line| anchor_marker(line, "ANCHOR_END:") != Some(name))
+        .filter(|line| {
+            anchor_marker(line, "ANCHOR:").is_none() && anchor_marker(line, "ANCHOR_END:").is_none()
+        })
+        .collect::<Vec<&str>>();
+    Some(region.join("\n"))
+}
+
+// Name of the region following the marker in the line, if any
+fn anchor_marker<'l>(line: &'l str, marker: &str) -> Option<&'l str> {
+    let (_, rest) = line.split_once(marker)?;
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| !name.is_empty())
+}
+
+// Lines of an included file, all of them or the ones of a range of line numbers starting
+// from 1, written `10-20`, `10-`, `-20` or `10`
+pub(crate) fn select_lines(content: &str, range: Option<&str>) -> Result<String, String> {
+    let lines = content.lines().collect::<Vec<&str>>();
+    let Some(range) = range else {
+        return Ok(lines.join("\n"));
+    };
+    let line_number = |n: &str| {
+        n.parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("invalid line range \"{range}\""))
+    };
+    let (start, end) = match range.split_once('-') {
+        Some(("", "")) => (1, lines.len()),
+        Some(("", end)) => (1, line_number(end)?),
+        Some((start, "")) => (line_number(start)?, lines.len()),
+        Some((start, end)) => (line_number(start)?, line_number(end)?),
+        None => (line_number(range)?, line_number(range)?),
+    };
+    if start > end || end > lines.len() {
+        return Err(format!(
+            "lines {range} are out of the {} lines of the file",
+            lines.len()
+        ));
+    }
+    Ok(lines[start - 1..end].join("\n"))
+}
+
+/// Filters applied to the code of a code block before displaying it
+#[derive(Clone, Debug, Default)]
+pub struct CodeblockFilter {
+    hide_rustdoc_lines: bool,
+}
+
+impl CodeblockFilter {
+    /// Hide the lines starting with `# ` like rustdoc does, `##` being an escaped `#`
+    pub fn hide_rustdoc_lines(mut self, hide: bool) -> Self {
+        self.hide_rustdoc_lines = hide;
+        self
+    }
+
+    pub fn apply(&self, code: &str) -> String {
+        if !self.hide_rustdoc_lines {
+            return code.to_string();
+        }
+        code.lines()
+            .filter_map(|line| {
+                let indent = line.len() - line.trim_start().len();
+                let content = &line[indent..];
+                if content == "#" || content.starts_with("# ") {
+                    None
+                } else if let Some(escaped) = content.strip_prefix("##") {
+                    Some(format!("{}#{escaped}", &line[..indent]))
+                } else {
+                    Some(line.to_string())
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// Rust code as compiled by rustdoc, with the hidden `# ` lines shown
+fn rustdoc_source(code: &str) -> String {
+    code.lines()
+        .map(|line| {
+            let indent = line.len() - line.trim_start().len();
+            let content = &line[indent..];
+            if content == "#" {
+                String::new()
+            } else if let Some(shown) = content.strip_prefix("# ") {
+                format!("{}{shown}", &line[..indent])
+            } else if let Some(escaped) = content.strip_prefix("##") {
+                format!("{}#{escaped}", &line[..indent])
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Generate a Rust test harness from the ```` ```rust ```` code blocks of a document,
+/// one `#[test]` function per block, named after `name` and the line of the block
+///
+/// The `ignore` and `should_panic` flags map to the test attributes, `no_run` blocks are
+/// only compiled and `compile_fail` blocks are skipped. The output is meant to be written
+/// from a build script and included in an integration test with `include!`.
+pub fn rust_test_harness(input: &str, name: &str) -> Result<String, Errcode> {
+    let prefix = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let mut harness = format!("// Generated from the code blocks of {name}\n");
+    for block in extract_code_blocks(input, Some("rust"))? {
+        let has_flag = |flag: &str| block.flags.iter().any(|f| f == flag);
+        if has_flag("compile_fail") {
+            continue;
+        }
+        harness += "\n";
+        if has_flag("no_run") {
+            harness += "#[allow(dead_code)]\n";
+        } else {
+            harness += "#[test]\n";
+            if has_flag("ignore") {
+                harness += "#[ignore]\n";
+            }
+            if has_flag("should_panic") {
+                harness += "#[should_panic]\n";
+            }
+        }
+        let mut code = rustdoc_source(&block.content);
+        if code.contains("fn main()") {
+            code += "\nmain();";
+        }
+        let body = code
+            .lines()
+            .map(|line| match line {
+                "" => String::new(),
+                line => format!("    {line}"),
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let line = block.span.line;
+        harness +=
+            format!("fn {prefix}_line_{line}() {{\n    // {name}:{line}\n{body}\n}}\n").as_str();
+    }
+    Ok(harness)
+}
@@ -0,0 +1,198 @@
+use pulldown_cmark::{html::push_html, Options, Parser};
+
+use crate::{prelude::*, transformers::EmailHtmlRenderer, Errcode, Pipeline};
+
+const VOID_TAGS: [&str; 5] = ["img", "hr", "br", "input", "meta"];
+
+/// Blocks of a document rendered differently by pulldown-cmark and by mdtrans,
+/// as HTML stripped of its styling and layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DialectDiff {
+    /// Blocks as rendered by pulldown-cmark
+    pub pulldown_cmark: Vec<String>,
+    /// Blocks as rendered by mdtrans, in place of the ones of pulldown-cmark
+    pub mdtrans: Vec<String>,
+}
+
+/// Written like a diff, `-` for pulldown-cmark and `+` for mdtrans
+impl core::fmt::Display for DialectDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for block in self.pulldown_cmark.iter() {
+            writeln!(f, "- {block}")?;
+        }
+        for block in self.mdtrans.iter() {
+            writeln!(f, "+ {block}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Render `input` with pulldown-cmark (with tables, strikethrough and task lists) and with
+/// the `EmailHtmlRenderer`, listing the blocks whose meaning differs between the two
+///
+/// Meant to find the gaps between the markdown dialects when migrating documents from
+/// another crate: the attributes, whitespace and layout elements are ignored, only the
+/// structure, the text, and the targets of links and images are compared.
+pub fn compare_with_pulldown_cmark(input: &str) -> Result<Vec<DialectDiff>, Errcode> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut reference = String::new();
+    push_html(&mut reference, Parser::new_ext(input, options));
+    let rendered = Pipeline::new()
+        .transformer(EmailHtmlRenderer::default())
+        .run(input)?;
+    Ok(diff_blocks(
+        &semantic_blocks(&reference),
+        &semantic_blocks(&rendered),
+    ))
+}
+
+// Top-level elements of the HTML, keeping only the semantic tags and the attributes
+// defining the targets, with the whitespace collapsed
+fn semantic_blocks(html: &str) -> Vec<String> {
+    let mut blocks = vec![];
+    let mut current = String::new();
+    // Open elements, with whether they are kept
+    let mut open: Vec<(String, bool)> = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment
+                .split_once("-->")
+                .map(|(_, after)| after)
+                .unwrap_or("");
+            continue;
+        }
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = unescape(&rest[..end]);
+            rest = &rest[end..];
+            if !text.trim().is_empty() {
+                current += &text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                if open.is_empty() {
+                    blocks.push(core::mem::take(&mut current));
+                }
+            }
+            continue;
+        }
+        let end = rest.find('>').unwrap_or(rest.len() - 1);
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim().to_lowercase();
+            while let Some((open_name, kept)) = open.pop() {
+                if kept {
+                    current += &format!("</{open_name}>");
+                }
+                if open_name == name {
+                    break;
+                }
+            }
+        } else {
+            let name = tag
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_lowercase();
+            let parent = open.last().map(|(parent, _)| parent.as_str());
+            let kept = is_semantic(&name)
+                && !(name == "code" && parent == Some("pre"))
+                && !(name == "p" && matches!(parent, Some("li" | "blockquote")));
+            if kept {
+                current += &format!("<{name}{}>", kept_attributes(&name, tag));
+            }
+            if !VOID_TAGS.contains(&name.as_str()) && !tag.ends_with('/') {
+                open.push((name, kept));
+            }
+        }
+        if open.is_empty() && !current.is_empty() {
+            blocks.push(core::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+// Elements carrying meaning, the other ones are layout only (`<div>`, `<thead>`, `<br>`)
+const SEMANTIC_TAGS: &str =
+    "h1 h2 h3 h4 h5 h6 p em strong del a img code pre blockquote ul ol li table tr th td hr sup";
+
+fn is_semantic(name: &str) -> bool {
+    SEMANTIC_TAGS.split(' ').any(|tag| tag == name)
+}
+
+fn kept_attributes(name: &str, tag: &str) -> String {
+    let keys: &[&str] = match name {
+        "a" => &["href"],
+        "img" => &["src", "alt"],
+        "ol" => &["start"],
+        _ => &[],
+    };
+    keys.iter()
+        .filter_map(|key| {
+            let value = attribute(tag, key)?;
+            // NOTE    Lists start at 1 unless told otherwise
+            (*key != "start" || value != "1").then(|| format!(" {key}=\"{value}\""))
+        })
+        .collect()
+}
+
+fn attribute<'t>(tag: &'t str, key: &str) -> Option<&'t str> {
+    let (_, value) = tag.split_once(&format!(" {key}=\""))?;
+    value.split_once('"').map(|(value, _)| value)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// Runs of blocks differing between the two renderings, around their longest common sequence
+fn diff_blocks(reference: &[String], rendered: &[String]) -> Vec<DialectDiff> {
+    let (n, m) = (reference.len(), rendered.len());
+    // Length of the longest common sequence of the blocks following `i` and `j`
+    let mut common = vec![vec![0_usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if reference[i] == rendered[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diffs = vec![];
+    let mut hunk = DialectDiff {
+        pulldown_cmark: vec![],
+        mdtrans: vec![],
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && reference[i] == rendered[j] {
+            if !hunk.pulldown_cmark.is_empty() || !hunk.mdtrans.is_empty() {
+                diffs.push(hunk.clone());
+                hunk.pulldown_cmark.clear();
+                hunk.mdtrans.clear();
+            }
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && common[i + 1][j] >= common[i][j + 1]) {
+            hunk.pulldown_cmark.push(reference[i].clone());
+            i += 1;
+        } else {
+            hunk.mdtrans.push(rendered[j].clone());
+            j += 1;
+        }
+    }
+    if !hunk.pulldown_cmark.is_empty() || !hunk.mdtrans.is_empty() {
+        diffs.push(hunk);
+    }
+    diffs
+}
@@ -0,0 +1,178 @@
+use crate::{prelude::*, ElementKind, ElementNumber, Rule};
+
+/// Location of an element in the markdown source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the element
+    pub start: usize,
+    /// Byte offset right after the end of the element
+    pub end: usize,
+    /// Line of the start of the element, starting from 1
+    pub line: usize,
+    /// Column of the start of the element, starting from 1
+    pub column: usize,
+}
+
+impl<'i> From<pest::Span<'i>> for Span {
+    fn from(span: pest::Span<'i>) -> Self {
+        let (line, column) = span.start_pos().line_col();
+        Span {
+            start: span.start(),
+            end: span.end(),
+            line,
+            column,
+        }
+    }
+}
+
+/// Deterministic identifier of an element, derived from its kind, its content
+/// and its occurrence index among identical elements
+///
+/// An element keeps its ID as long as it and its identical predecessors are unchanged,
+/// which makes it usable as a cache key across builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElementId(pub u64);
+
+impl core::fmt::Display for ElementId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl ElementId {
+    // FNV-1a, stable across platforms and compiler versions
+    fn hash(kind: ElementKind, content: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let level = match kind {
+            ElementKind::Header(level) => level as u8,
+            _ => 0,
+        };
+        let kind = kind.name().bytes().chain([level, 0]);
+        for byte in kind.chain(content.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+}
+
+// Keeps track of identical elements to give them distinct IDs
+#[derive(Default, Clone)]
+pub(crate) struct IdGenerator {
+    occurrences: HashMap<u64, u64>,
+}
+
+impl IdGenerator {
+    pub fn reset(&mut self) {
+        self.occurrences.clear();
+    }
+
+    pub fn next(&mut self, rule: Rule, content: &str) -> ElementId {
+        let hash = ElementId::hash(rule.into(), content);
+        let index = self.occurrences.entry(hash).or_insert(0);
+        *index += 1;
+        ElementId(hash ^ index.wrapping_mul(0x9e3779b97f4a7c15))
+    }
+}
+
+/// Information about the element whose hook is about to be called
+pub struct ElementContext<'i> {
+    rule: Rule,
+    span: Span,
+    source: &'i str,
+    id: ElementId,
+    ordinal: usize,
+    number: Option<ElementNumber>,
+    anchor: Option<String>,
+}
+
+impl<'i> ElementContext<'i> {
+    pub(crate) fn new(
+        rule: Rule,
+        span: Span,
+        source: &'i str,
+        id: ElementId,
+        ordinal: usize,
+    ) -> ElementContext<'i> {
+        ElementContext {
+            rule,
+            span,
+            source,
+            id,
+            ordinal,
+            number: None,
+            anchor: None,
+        }
+    }
+
+    pub(crate) fn with_number(mut self, number: Option<ElementNumber>) -> Self {
+        self.number = number;
+        self
+    }
+
+    pub(crate) fn with_anchor(mut self, anchor: Option<String>) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Deterministic identifier of the element, identical in the peek and transform passes
+    pub fn id(&self) -> ElementId {
+        self.id
+    }
+
+    /// Position of the element in source order, identical in the peek and transform passes
+    ///
+    /// An element has a greater ordinal than the ones starting before it and than the
+    /// elements containing it, ordinals are not contiguous.
+    pub fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    /// Number of the figure, table or listing, the same in both passes and for every
+    /// transformer, `None` for the other elements
+    pub fn number(&self) -> Option<ElementNumber> {
+        self.number
+    }
+
+    /// Anchor of a heading, unique in the document, the one of the outline and of the
+    /// cross-references, `None` for the other elements
+    pub fn anchor(&self) -> Option<&str> {
+        self.anchor.as_deref()
+    }
+
+    // Grammar rule that matched the element, `kind` being the one exposed
+    pub(crate) fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Kind of the element
+    pub fn kind(&self) -> ElementKind {
+        ElementKind::from(self.rule)
+    }
+
+    /// Exact markdown source of the element
+    pub fn source(&self) -> &'i str {
+        self.source
+    }
+
+    /// Location of the element in the markdown source
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// Span of the element whose hooks are called, for the transformers collecting the location
+/// of what they find, kept from `MarkdownTransformer::element_context`
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct CurrentSpan(Option<Span>);
+
+impl CurrentSpan {
+    pub(crate) fn set(&mut self, ctx: &ElementContext) {
+        self.0 = Some(ctx.span());
+    }
+
+    pub(crate) fn get(&self) -> Span {
+        // NOTE    The element context is always given before the hooks
+        self.0.expect("Element context missing")
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    anchors::AnchorGenerator, numbering::Numbering, prelude::*, Node, Rule, TransformOptions,
+};
+
+/// Kind of element a cross-reference `[#slug]` points to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrossrefKind {
+    /// Heading, referenced by its anchor, resolved to its text
+    Header,
+    /// Image with `caption` and `id` tags, referenced by its `id`, resolved to its number
+    Figure,
+}
+
+// Targets of the cross-references of a document, by slug
+#[derive(Default)]
+pub(crate) struct Crossrefs {
+    targets: HashMap<String, (CrossrefKind, String)>,
+    // Anchors of the headings, by ordinal
+    anchors: HashMap<usize, String>,
+}
+
+impl Crossrefs {
+    pub(crate) fn new(root: &Node, numbering: &Numbering, options: &TransformOptions) -> Crossrefs {
+        let mut crossrefs = Crossrefs::default();
+        crossrefs.visit(root, numbering, &mut AnchorGenerator::new(options));
+        crossrefs
+    }
+
+    pub(crate) fn resolve(&self, slug: &str) -> Option<(CrossrefKind, String)> {
+        self.targets.get(slug).cloned()
+    }
+
+    pub(crate) fn anchor(&self, node: &Node) -> Option<String> {
+        self.anchors.get(&node.ordinal()).cloned()
+    }
+
+    fn visit(&mut self, node: &Node, numbering: &Numbering, anchors: &mut AnchorGenerator) {
+        match node.rule() {
+            Rule::h1 | Rule::h2 | Rule::h3 | Rule::h4 | Rule::h5 | Rule::h6 => {
+                let title = plain_text(node);
+                if let Ok(slug) = anchors.anchor(&title) {
+                    self.anchors.insert(node.ordinal(), slug.clone());
+                    self.targets
+                        .entry(slug)
+                        .or_insert((CrossrefKind::Header, title.trim().to_string()));
+                }
+            }
+            Rule::image => {
+                if let (Some(id), Some(number)) = (image_id(node), numbering.get(node)) {
+                    self.targets
+                        .insert(id, (CrossrefKind::Figure, number.to_string()));
+                }
+            }
+            _ => {}
+        }
+        for child in node.children() {
+            self.visit(child, numbering, anchors);
+        }
+    }
+}
+
+// Text of an element without its formatting, nor the targets of its links
+fn plain_text(node: &Node) -> String {
+    match node.rule() {
+        Rule::url | Rule::slug => String::new(),
+        _ if node.children().is_empty() => node.as_str().to_string(),
+        _ => node.children().iter().map(plain_text).collect(),
+    }
+}
+
+#[cfg(feature = "image-tags")]
+fn image_id(image: &Node) -> Option<String> {
+    image
+        .children()
+        .iter()
+        .filter(|child| child.rule() == Rule::image_tags)
+        .flat_map(|tags| tags.children())
+        .find(|tag| tag.children().first().map(|key| key.as_str()) == Some("id"))
+        .and_then(|tag| tag.children().get(1))
+        .map(|id| id.as_str().trim_matches('"').to_string())
+}
+
+#[cfg(not(feature = "image-tags"))]
+fn image_id(_image: &Node) -> Option<String> {
+    None
+}
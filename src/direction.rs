@@ -0,0 +1,75 @@
+use crate::prelude::*;
+
+/// Writing direction of a block of text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left to right, also used for the text without any letter
+    #[default]
+    Ltr,
+    /// Right to left (Hebrew, Arabic, Syriac, Thaana, ...)
+    Rtl,
+}
+
+impl TextDirection {
+    /// Direction of the first letter of `text` (the first strong character of the Unicode
+    /// bidirectional algorithm), the punctuation, digits and markdown syntax being skipped
+    pub fn detect(text: &str) -> TextDirection {
+        text.chars()
+            .find_map(|c| {
+                if is_rtl(c) {
+                    Some(TextDirection::Rtl)
+                } else if c.is_alphabetic() {
+                    Some(TextDirection::Ltr)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    /// Value of the HTML `dir` attribute
+    pub fn html(&self) -> &'static str {
+        match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+        }
+    }
+}
+
+// Letters of the right-to-left scripts, the digits and combining marks of their blocks
+// don't set a direction
+fn is_rtl(c: char) -> bool {
+    match c {
+        '\u{0591}'..='\u{05C7}' | '\u{0610}'..='\u{061A}' | '\u{064B}'..='\u{065F}' => false,
+        '\u{0660}'..='\u{0669}' | '\u{06F0}'..='\u{06F9}' => false,
+        '\u{0590}'..='\u{08FF}' => true,
+        '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}' => c.is_alphabetic(),
+        '\u{10800}'..='\u{10FFF}' | '\u{1E800}'..='\u{1EFFF}' => c.is_alphabetic(),
+        _ => false,
+    }
+}
+
+/// Information on a paragraph, given to the `*_paragraph_with_info` hooks once
+/// `TransformOptions::text_direction` is enabled, or for the paragraphs given a language
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParagraphInfo {
+    /// Direction of the paragraph from its first letter, always `Ltr` unless
+    /// `TransformOptions::text_direction` is enabled
+    pub direction: TextDirection,
+    /// Language given to the paragraph with a `{lang=fr}` attribute
+    pub lang: Option<String>,
+}
+
+impl ParagraphInfo {
+    pub(crate) fn new(lang: Option<String>) -> ParagraphInfo {
+        ParagraphInfo {
+            direction: TextDirection::default(),
+            lang,
+        }
+    }
+
+    pub(crate) fn detect_direction(mut self, source: &str) -> ParagraphInfo {
+        self.direction = TextDirection::detect(source);
+        self
+    }
+}
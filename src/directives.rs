@@ -0,0 +1,31 @@
+/// Instruction given to the framework by a `<!-- mdtrans: name -->` comment, once
+/// `TransformOptions::directives` is enabled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Directive {
+    /// Leave the following blocks out of the output, up to a `skip-end` directive or the
+    /// end of the document
+    SkipStart,
+    /// End of the blocks left out since a `skip-start` directive
+    SkipEnd,
+    /// Pass the markdown source of the next block as is to `transform_raw_block`
+    Raw,
+}
+
+impl Directive {
+    /// Directive of the text of a comment (`mdtrans: skip-start`)
+    pub fn from_comment(text: &str) -> Option<Directive> {
+        let name = text.trim().strip_prefix("mdtrans:")?.trim();
+        [Directive::SkipStart, Directive::SkipEnd, Directive::Raw]
+            .into_iter()
+            .find(|directive| directive.name() == name)
+    }
+
+    /// Name of the directive, as written after `mdtrans:`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Directive::SkipStart => "skip-start",
+            Directive::SkipEnd => "skip-end",
+            Directive::Raw => "raw",
+        }
+    }
+}
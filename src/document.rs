@@ -0,0 +1,643 @@
+use alloc::sync::Arc;
+use pest::{
+    error::{Error, ErrorVariant, InputLocation},
+    iterators::Pair,
+    Parser,
+};
+
+use crate::{
+    context::IdGenerator, crossref::Crossrefs, inline::InlineAdapter, numbering::Numbering,
+    prelude::*, transform::TransformFramework, visitor::walk_node, ElementKind, Errcode,
+    InlineTransformer, MarkdownParser, MarkdownTransformer, MarkdownVisitor, ParseMode, ParseState,
+    Rule, Span, StatefulTransformer, TransformOptions, Warning,
+};
+
+// Markdown source of the nodes, borrowed from the input transformed by the free functions or
+// shared by the nodes of a `Document`
+// NOTE    `Arc<String>` is a thin pointer, keeping the source as small as a `&str` in each node
+#[derive(Clone)]
+enum Source<'s> {
+    Borrowed(&'s str),
+    Shared(Arc<String>),
+}
+
+impl Source<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            Source::Borrowed(source) => source,
+            Source::Shared(source) => source,
+        }
+    }
+}
+
+/// Element of a parsed document, with its location and its children
+///
+/// The nodes of a `Document` share its source, the ones built to transform a markdown string
+/// directly borrow it.
+// NOTE    The positions are stored on 32 bits to keep the nodes small, there being one node
+//         for every few bytes of the source, which is why the inputs are limited to 4 GiB
+#[derive(Clone)]
+pub struct Node<'s> {
+    rule: Rule,
+    start: u32,
+    end: u32,
+    line: u32,
+    column: u32,
+    ordinal: u32,
+    source: Source<'s>,
+    children: Box<[Node<'s>]>,
+}
+
+impl<'s> Node<'s> {
+    fn new(
+        rule: Rule,
+        span: Span,
+        ordinal: usize,
+        source: Source<'s>,
+        children: Vec<Node<'s>>,
+    ) -> Node<'s> {
+        Node {
+            rule,
+            start: span.start as u32,
+            end: span.end as u32,
+            line: span.line as u32,
+            column: span.column as u32,
+            ordinal: ordinal as u32,
+            source,
+            children: children.into_boxed_slice(),
+        }
+    }
+
+    // Grammar rule that matched the element, `kind` being the one exposed
+    pub(crate) fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Kind of the element
+    pub fn kind(&self) -> ElementKind {
+        ElementKind::from(self.rule)
+    }
+
+    /// Location of the element in the markdown source
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.start as usize,
+            end: self.end as usize,
+            line: self.line as usize,
+            column: self.column as usize,
+        }
+    }
+
+    /// Position of the element in source order, greater than the ones of the elements
+    /// starting before it and of the elements containing it
+    ///
+    /// Ordinals are not contiguous, the nodes of plain text being numbered as well.
+    pub fn ordinal(&self) -> usize {
+        self.ordinal as usize
+    }
+
+    /// Exact markdown source of the element
+    pub fn as_str(&self) -> &str {
+        &self.source.as_str()[self.start as usize..self.end as usize]
+    }
+
+    /// Elements matched inside this one, in source order
+    pub fn children(&self) -> &[Node<'s>] {
+        &self.children
+    }
+}
+
+impl core::fmt::Debug for Node<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Node")
+            .field("rule", &self.rule)
+            .field("span", &self.span())
+            .field("ordinal", &self.ordinal)
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+/// Markdown document parsed once, that can be transformed any number of times
+///
+/// A document is cheap to clone and can be shared between threads, to render it
+/// concurrently with different transformers.
+#[derive(Clone, Debug)]
+pub struct Document {
+    tree: Arc<Tree<'static>>,
+}
+
+impl Document {
+    pub fn parse(input: &str) -> Result<Document, Errcode> {
+        Document::parse_with_mode(input, ParseMode::Strict)
+    }
+
+    /// Parse the document, line endings (`\r\n`, `\r`) being normalized to `\n` first
+    pub fn parse_with_mode(input: &str, mode: ParseMode) -> Result<Document, Errcode> {
+        let normalized;
+        let input = if input.contains('\r') {
+            normalized = input.replace("\r\n", "\n").replace('\r', "\n");
+            normalized.as_str()
+        } else {
+            input
+        };
+        let tree = Tree::build(Source::Shared(Arc::new(input.to_string())), mode)?;
+        Ok(Document {
+            tree: Arc::new(tree),
+        })
+    }
+
+    /// Whole markdown source of the document, with normalized line endings
+    pub fn source(&self) -> &str {
+        self.root().as_str()
+    }
+
+    /// Node of the whole file, parent of all the top-level blocks
+    pub fn root(&self) -> &Node<'_> {
+        self.tree.root()
+    }
+
+    pub fn transform<T>(&self, transformer: &mut T) -> String
+    where
+        T: MarkdownTransformer,
+    {
+        self.transform_with_options(transformer, &TransformOptions::default())
+    }
+
+    pub fn transform_with_options<T>(
+        &self,
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> String
+    where
+        T: MarkdownTransformer,
+    {
+        self.transform_with_warnings(transformer, options).0
+    }
+
+    /// Transform the document, also returning the content issues found in it
+    pub fn transform_with_warnings<T>(
+        &self,
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> (String, Vec<Warning>)
+    where
+        T: MarkdownTransformer,
+    {
+        self.tree.transform_with_warnings(transformer, options)
+    }
+
+    /// Transform the elements of the given kinds only, keeping the markdown of the rest of the
+    /// document, see `transform_filtered`
+    pub fn transform_filtered<T>(
+        &self,
+        kinds: &[ElementKind],
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> String
+    where
+        T: MarkdownTransformer,
+    {
+        self.tree.transform_filtered(kinds, transformer, options)
+    }
+
+    /// Transform the document with hooks receiving structured inline content
+    pub fn transform_inline<T: InlineTransformer>(&self, transformer: &mut T) -> String {
+        self.transform_inline_with_options(transformer, &TransformOptions::default())
+    }
+
+    /// Same as `transform_inline`, with the options of the transformation
+    pub fn transform_inline_with_options<T>(
+        &self,
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> String
+    where
+        T: InlineTransformer,
+    {
+        let mut adapter = InlineAdapter::new(transformer);
+        let output = self.transform_with_options(&mut adapter, options);
+        adapter.transform_document(&output)
+    }
+
+    /// Call the visitor on every element of the document, without building any output
+    pub fn walk<V: MarkdownVisitor>(&self, visitor: &mut V) {
+        // NOTE    Elements are numbered and headings anchored as with the default options
+        let options = TransformOptions::default();
+        let numbering = Numbering::new(self.root(), &options);
+        let crossrefs = Crossrefs::new(self.root(), &numbering, &options);
+        walk_node(
+            self.root(),
+            visitor,
+            &mut IdGenerator::default(),
+            &numbering,
+            &crossrefs,
+        );
+    }
+
+    /// Transform the document, then restore the transformer to its state from before
+    pub fn transform_isolated<T>(&self, transformer: &mut T, options: &TransformOptions) -> String
+    where
+        T: StatefulTransformer,
+    {
+        self.tree.transform_isolated(transformer, options)
+    }
+}
+
+// Nodes of a document with the issues found while parsing it, built on their own to
+// transform a markdown string without the copy of its source made by a `Document`
+#[derive(Debug)]
+pub(crate) struct Tree<'s> {
+    root: Node<'s>,
+    warnings: Vec<Warning>,
+}
+
+impl<'s> Tree<'s> {
+    /// Parse the input, borrowed by the nodes unless its line endings are normalized
+    pub(crate) fn parse(input: &'s str, mode: ParseMode) -> Result<Tree<'s>, Errcode> {
+        let source = if input.contains('\r') {
+            let normalized = input.replace("\r\n", "\n").replace('\r', "\n");
+            Source::Shared(Arc::new(normalized))
+        } else {
+            Source::Borrowed(input)
+        };
+        Tree::build(source, mode)
+    }
+
+    fn build(source: Source<'s>, mode: ParseMode) -> Result<Tree<'s>, Errcode> {
+        let mut builder = TreeBuilder {
+            source: source.clone(),
+            position: LinePosition::default(),
+            ordinal: 0,
+            mode,
+            warnings: vec![],
+        };
+        let input = source.as_str();
+        if u32::try_from(input.len()).is_err() {
+            return Err(Errcode::ParsingError("Input larger than 4 GiB".to_string()));
+        }
+        let children = builder.blocks(input, &OffsetMap::default())?;
+        let span = Span {
+            start: 0,
+            end: input.len(),
+            line: 1,
+            column: 1,
+        };
+        let root = Node::new(Rule::file, span, 0, source.clone(), children);
+        Ok(Tree {
+            root,
+            warnings: builder.warnings,
+        })
+    }
+
+    pub(crate) fn root(&self) -> &Node<'s> {
+        &self.root
+    }
+
+    pub(crate) fn transform_with_options<T>(
+        &self,
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> String
+    where
+        T: MarkdownTransformer,
+    {
+        self.transform_with_warnings(transformer, options).0
+    }
+
+    pub(crate) fn transform_with_warnings<T>(
+        &self,
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> (String, Vec<Warning>)
+    where
+        T: MarkdownTransformer,
+    {
+        let mut parser = TransformFramework::new(transformer, options);
+        parser.peek_document(&self.root);
+        let res = parser.transform_document(&self.root);
+        let mut warnings = self.warnings.clone();
+        warnings.extend(parser.warnings);
+        (res, warnings)
+    }
+
+    pub(crate) fn transform_filtered<T>(
+        &self,
+        kinds: &[ElementKind],
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> String
+    where
+        T: MarkdownTransformer,
+    {
+        let mut parser = TransformFramework::new(transformer, options);
+        parser.index_document(&self.root);
+        parser.act_on_kinds(&mut ParseState::peek(), &self.root, kinds, &mut vec![]);
+        parser.transformer.finished(true);
+        parser.ids.reset();
+        let mut outputs = vec![];
+        parser.act_on_kinds(&mut ParseState::default(), &self.root, kinds, &mut outputs);
+        let source = self.root.as_str();
+        let mut res = String::with_capacity(source.len());
+        let mut last = 0;
+        for (span, output) in outputs {
+            res += &source[last..span.start];
+            res += output.as_str();
+            last = span.end;
+        }
+        res += &source[last..];
+        res += parser.transformer.finished(false).as_str();
+        options.finish_output(res)
+    }
+
+    pub(crate) fn transform_isolated<T>(
+        &self,
+        transformer: &mut T,
+        options: &TransformOptions,
+    ) -> String
+    where
+        T: StatefulTransformer,
+    {
+        let snapshot = transformer.snapshot();
+        let res = self.transform_with_options(transformer, options);
+        transformer.restore(snapshot);
+        res
+    }
+}
+
+// Line and column of the last visited position in the source
+struct LinePosition {
+    offset: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl Default for LinePosition {
+    fn default() -> Self {
+        LinePosition {
+            offset: 0,
+            line: 1,
+            line_start: 0,
+        }
+    }
+}
+
+// Maps the offsets of a dedented fragment of the document back to the document source,
+// from the offsets of the start of each line in both
+#[derive(Default)]
+struct OffsetMap {
+    lines: Vec<(usize, usize)>,
+}
+
+impl OffsetMap {
+    fn start(&self, offset: usize) -> usize {
+        match self.lines.iter().rev().find(|(line, _)| *line <= offset) {
+            Some((line, source)) => source + offset - line,
+            None => offset,
+        }
+    }
+
+    // NOTE    An element ending at the start of a line ends before the indentation of that line
+    fn end(&self, offset: usize) -> usize {
+        match self.lines.iter().rev().find(|(line, _)| *line < offset) {
+            Some((line, source)) => source + offset - line,
+            None => self.start(offset),
+        }
+    }
+
+    // Map of the part of the fragment starting at `from`
+    fn suffix(&self, from: usize) -> OffsetMap {
+        let mut lines = vec![(0, self.start(from))];
+        lines.extend(
+            self.lines
+                .iter()
+                .filter(|(line, _)| *line > from)
+                .map(|(line, source)| (line - from, *source)),
+        );
+        OffsetMap { lines }
+    }
+
+    // Removes the indentation common to all the lines of a fragment starting at `base`
+    fn dedent(&self, text: &str, base: usize) -> (String, OffsetMap) {
+        let indent = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start_matches(' ').len())
+            .min()
+            .unwrap_or(0);
+        let mut dedented = String::with_capacity(text.len());
+        let mut lines = vec![];
+        let mut offset = base;
+        for line in text.split_inclusive('\n') {
+            let removed = line.bytes().take(indent).take_while(|b| *b == b' ').count();
+            lines.push((dedented.len(), self.start(offset + removed)));
+            dedented.push_str(&line[removed..]);
+            offset += line.len();
+        }
+        (dedented, OffsetMap { lines })
+    }
+}
+
+struct TreeBuilder<'s> {
+    source: Source<'s>,
+    position: LinePosition,
+    // Ordinal of the last node created, the root of the document being the first
+    ordinal: usize,
+    mode: ParseMode,
+    warnings: Vec<Warning>,
+}
+
+// Rules whose content is made of blocks indented under them, parsed on their own
+fn has_indented_blocks(rule: Rule) -> bool {
+    #[cfg(feature = "details")]
+    if rule == Rule::details_body {
+        return true;
+    }
+    #[cfg(feature = "tabs")]
+    if rule == Rule::tab_body {
+        return true;
+    }
+    rule == Rule::list_element_blocks
+}
+
+impl<'s> TreeBuilder<'s> {
+    // NOTE    Pairs are visited in source order, so the position only moves forward
+    fn locate(&mut self, offset: usize) -> (usize, usize) {
+        let pos = &mut self.position;
+        let source = self.source.as_str();
+        for (i, byte) in source.as_bytes()[pos.offset..offset].iter().enumerate() {
+            if *byte == b'\n' {
+                pos.line += 1;
+                pos.line_start = pos.offset + i + 1;
+            }
+        }
+        pos.offset = offset;
+        let column = source[pos.line_start..offset].chars().count() + 1;
+        (pos.line, column)
+    }
+
+    // NOTE    Like the positions, ordinals are given in source order, parents first
+    fn next_ordinal(&mut self) -> usize {
+        self.ordinal += 1;
+        self.ordinal
+    }
+
+    fn node(&mut self, pair: Pair<'_, Rule>, map: &OffsetMap) -> Result<Node<'s>, Errcode> {
+        let rule = pair.as_rule();
+        let pair_span = pair.as_span();
+        let start = map.start(pair_span.start());
+        let (line, column) = self.locate(start);
+        let span = Span {
+            start,
+            end: map.end(pair_span.end()),
+            line,
+            column,
+        };
+        let ordinal = self.next_ordinal();
+        let children = if has_indented_blocks(rule) {
+            self.indented_blocks(pair.as_str(), pair_span.start(), map)?
+        } else if rule == Rule::table_cell {
+            self.cell_content(pair.as_str(), pair_span.start(), map)?
+        } else {
+            pair.into_inner()
+                .map(|child| self.node(child, map))
+                .collect::<Result<Vec<Node<'s>>, Errcode>>()?
+        };
+        Ok(Node::new(
+            rule,
+            span,
+            ordinal,
+            self.source.clone(),
+            children,
+        ))
+    }
+
+    // Blocks nested in a list element, a collapsible section or a tab are parsed as
+    // a document of their own
+    fn indented_blocks(
+        &mut self,
+        text: &str,
+        base: usize,
+        map: &OffsetMap,
+    ) -> Result<Vec<Node<'s>>, Errcode> {
+        let (dedented, map) = map.dedent(text, base);
+        self.blocks(&dedented, &map)
+    }
+
+    // NOTE    Cells are delimited by the pipes before their content is parsed, a cell whose
+    //         content is not valid inline markdown is kept as raw text, without children
+    #[cfg(not(feature = "dialect-v1"))]
+    fn cell_content(
+        &mut self,
+        text: &str,
+        base: usize,
+        map: &OffsetMap,
+    ) -> Result<Vec<Node<'s>>, Errcode> {
+        let content = text.trim();
+        let Ok(mut parsed) = MarkdownParser::parse(Rule::rich_txt, content) else {
+            return Ok(vec![]);
+        };
+        let Some(rich_txt) = parsed.next().filter(|c| c.as_str().len() == content.len()) else {
+            return Ok(vec![]);
+        };
+        let leading = text.len() - text.trim_start().len();
+        Ok(vec![self.node(rich_txt, &map.suffix(base + leading))?])
+    }
+
+    // NOTE    The dialect 1 keeps the content of all the cells as raw text
+    #[cfg(feature = "dialect-v1")]
+    fn cell_content(&mut self, _: &str, _: usize, _: &OffsetMap) -> Result<Vec<Node<'s>>, Errcode> {
+        Ok(vec![])
+    }
+
+    fn blocks(&mut self, text: &str, map: &OffsetMap) -> Result<Vec<Node<'s>>, Errcode> {
+        let mut parsed = match MarkdownParser::parse(Rule::file, text) {
+            Ok(parsed) => parsed,
+            Err(err) => return self.recover(text, map, err),
+        };
+        let Some(file) = parsed.next() else {
+            return Err(Errcode::ParsingError(
+                "Parsed input returned an empty tree".to_string(),
+            ));
+        };
+        file.into_inner()
+            .filter(|child| child.as_rule() != Rule::EOI)
+            .map(|child| self.node(child, map))
+            .collect()
+    }
+
+    // NOTE    Blocks are separated by empty lines, so the ones around an invalid block
+    //         are parsed on their own
+    fn recover(
+        &mut self,
+        text: &str,
+        map: &OffsetMap,
+        err: Error<Rule>,
+    ) -> Result<Vec<Node<'s>>, Errcode> {
+        let offset = match err.location {
+            InputLocation::Pos(offset) => offset,
+            InputLocation::Span((start, _)) => start,
+        };
+        let expected = match err.variant {
+            ErrorVariant::ParsingError { positives, .. } => ElementKind::expected(positives),
+            ErrorVariant::CustomError { .. } => vec![],
+        };
+        if self.mode == ParseMode::Strict {
+            let start = map.start(offset);
+            let (line, column) = self.locate(start);
+            let span = Span {
+                start,
+                end: start,
+                line,
+                column,
+            };
+            return Err(Errcode::SyntaxError { span, expected });
+        }
+
+        let (start, end) = invalid_block(text, offset);
+        let mut blocks = self.blocks(&text[..start], map)?;
+        let start = map.start(start);
+        let (line, column) = self.locate(start);
+        let span = Span {
+            start,
+            end: map.end(end),
+            line,
+            column,
+        };
+        let ordinal = self.next_ordinal();
+        blocks.push(Node::new(
+            Rule::invalid,
+            span,
+            ordinal,
+            self.source.clone(),
+            vec![],
+        ));
+        self.warnings
+            .push(Warning::InvalidSyntax { expected, span });
+        blocks.extend(self.blocks(&text[end..], &map.suffix(end))?);
+        Ok(blocks)
+    }
+}
+
+// Start and end of the block, delimited by empty lines, in which the parser gave up at `offset`
+fn invalid_block(text: &str, offset: usize) -> (usize, usize) {
+    let mut block = (0, text.len());
+    let mut start = None;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            start = None;
+        } else if start.is_none() {
+            if line_start > offset {
+                break;
+            }
+            start = Some(line_start);
+            block = (line_start, line_start);
+        }
+        if start.is_some() {
+            block.1 = line_start + line.trim_end().len();
+        }
+        line_start += line.len();
+    }
+    block
+}
@@ -0,0 +1,145 @@
+use crate::{prelude::*, Document, Errcode, Node, Rule, Span, TransformOptions};
+
+// Words marking an unfinished passage, matched in uppercase only
+const WORD_MARKERS: [(&str, DraftMarkerKind); 2] = [
+    ("TODO", DraftMarkerKind::Todo),
+    ("FIXME", DraftMarkerKind::Fixme),
+];
+
+/// Kind of a `DraftMarker`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DraftMarkerKind {
+    /// The word `TODO`
+    Todo,
+    /// The word `FIXME`
+    Fixme,
+    /// A `<!-- draft -->` comment, marking the whole document as a draft
+    Draft,
+}
+
+/// Marker of unfinished content found by the `DraftAudit`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DraftMarker {
+    /// Path of the document containing the marker
+    pub file: String,
+    pub kind: DraftMarkerKind,
+    /// Location of the word, or of the whole comment for the `<!-- draft -->` markers
+    pub span: Span,
+}
+
+/// Listing of the `TODO`, `FIXME` and `<!-- draft -->` markers left in a set of documents,
+/// to keep the unfinished ones from being published
+///
+/// The words are found in the text and the comments, as whole uppercase words, the code
+/// being skipped.
+///
+/// ```
+/// # use mdtrans::{DraftAudit, DraftMarkerKind};
+/// let mut audit = DraftAudit::default();
+/// audit.add_document("a.md", "# Intro\n\nTODO: write the intro").unwrap();
+/// audit.add_document("b.md", "<!-- draft -->\n\nRun `make TODO` first").unwrap();
+/// audit.add_document("c.md", "Nothing todo here").unwrap();
+///
+/// let markers = audit.markers();
+/// assert_eq!(markers.len(), 2);
+/// assert_eq!((markers[0].kind, markers[0].span.line), (DraftMarkerKind::Todo, 3));
+/// assert_eq!((markers[1].file.as_str(), markers[1].kind), ("b.md", DraftMarkerKind::Draft));
+/// assert!(!audit.is_clean());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DraftAudit {
+    markers: Vec<DraftMarker>,
+    options: TransformOptions,
+}
+
+impl DraftAudit {
+    /// Options of the documents added afterwards, setting how they are parsed
+    pub fn options(mut self, options: TransformOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn add_document<S: ToString>(&mut self, path: S, input: &str) -> Result<(), Errcode> {
+        let document = Document::parse_with_mode(input, self.options.parse_mode)?;
+        collect_markers(document.root(), &path.to_string(), &mut self.markers);
+        Ok(())
+    }
+
+    /// Markers found in the documents, in the order of the documents
+    pub fn markers(&self) -> &[DraftMarker] {
+        &self.markers
+    }
+
+    /// No marker was found, the documents are ready to be published
+    pub fn is_clean(&self) -> bool {
+        self.markers.is_empty()
+    }
+}
+
+fn collect_markers(node: &Node, file: &str, markers: &mut Vec<DraftMarker>) {
+    match node.rule() {
+        Rule::comment => {
+            let text = node.as_str();
+            let text = text.trim_start_matches("<!--").trim_end_matches("-->");
+            if text.trim().eq_ignore_ascii_case("draft") {
+                markers.push(DraftMarker {
+                    file: file.to_string(),
+                    kind: DraftMarkerKind::Draft,
+                    span: node.span(),
+                });
+                return;
+            }
+        }
+        Rule::text | Rule::link_text | Rule::comment_text => {
+            for (offset, word, kind) in find_words(node.as_str()) {
+                markers.push(DraftMarker {
+                    file: file.to_string(),
+                    kind,
+                    span: word_span(node, offset, word),
+                });
+            }
+            return;
+        }
+        Rule::inline_code | Rule::codeblock => return,
+        _ => {}
+    }
+    for child in node.children() {
+        collect_markers(child, file, markers);
+    }
+}
+
+// NOTE    Offsets of the markers standing as whole words, `TODOS` or `MY_TODO` are not
+//         markers
+fn find_words(text: &str) -> Vec<(usize, &'static str, DraftMarkerKind)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut found = vec![];
+    for (word, kind) in WORD_MARKERS {
+        for (offset, _) in text.match_indices(word) {
+            let before = text[..offset].chars().next_back();
+            let after = text[offset + word.len()..].chars().next();
+            if !before.is_some_and(is_word) && !after.is_some_and(is_word) {
+                found.push((offset, word, kind));
+            }
+        }
+    }
+    found.sort_by_key(|(offset, _, _)| *offset);
+    found
+}
+
+fn word_span(node: &Node, offset: usize, word: &str) -> Span {
+    let span = node.span();
+    let before = &node.as_str()[..offset];
+    let (line, column) = match before.rfind('\n') {
+        Some(newline) => (
+            span.line + before.matches('\n').count(),
+            before[newline + 1..].chars().count() + 1,
+        ),
+        None => (span.line, span.column + before.chars().count()),
+    };
+    Span {
+        start: span.start + offset,
+        end: span.start + offset + word.len(),
+        line,
+        column,
+    }
+}
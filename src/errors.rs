@@ -1,25 +1,76 @@
-use thiserror::Error;
+use crate::{prelude::*, ElementKind, Span};
 
-#[derive(Error, Debug)]
+#[derive(Debug)]
 pub enum Errcode {
     ParsingError(String),
+    /// Markdown the grammar can't match, at `span`, where one of the `expected` elements was required
+    SyntaxError {
+        span: Span,
+        expected: Vec<ElementKind>,
+    },
+    /// Anchor shared by several headings, at `span` for the second one (`SlugDedup::Error`)
+    DuplicateSlug {
+        slug: String,
+        span: Span,
+    },
+    /// Input that is not valid UTF-8, from its byte at `offset`
+    InvalidUtf8 {
+        offset: usize,
+    },
+    /// Edit of the bytes `start..end` of a `Rewriter` overlapping another edit, out of the
+    /// source or splitting one of its characters
+    InvalidEdit {
+        start: usize,
+        end: usize,
+    },
+    #[cfg(feature = "std")]
     IoError(std::io::Error),
+    /// Invalid JSON exchanged with mdBook
+    #[cfg(feature = "mdbook")]
+    JsonError(serde_json::Error),
 }
 
-impl std::fmt::Display for Errcode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+impl core::fmt::Display for Errcode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Errcode::SyntaxError { span, expected } => {
+                write!(
+                    f,
+                    "{}:{}: syntax error, expected {expected:?}",
+                    span.line, span.column
+                )
+            }
+            Errcode::DuplicateSlug { slug, span } => {
+                write!(
+                    f,
+                    "{}:{}: heading anchor \"{slug}\" is already used",
+                    span.line, span.column
+                )
+            }
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
-impl<T: std::fmt::Debug> From<pest::error::Error<T>> for Errcode {
+#[cfg(feature = "std")]
+impl std::error::Error for Errcode {}
+
+impl<T: core::fmt::Debug> From<pest::error::Error<T>> for Errcode {
     fn from(value: pest::error::Error<T>) -> Self {
         Errcode::ParsingError(format!("{:?}", value))
     }
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for Errcode {
     fn from(value: std::io::Error) -> Self {
         Errcode::IoError(value)
     }
 }
+
+#[cfg(feature = "mdbook")]
+impl From<serde_json::Error> for Errcode {
+    fn from(value: serde_json::Error) -> Self {
+        Errcode::JsonError(value)
+    }
+}
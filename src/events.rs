@@ -0,0 +1,245 @@
+use crate::mdast::write_string;
+#[cfg(feature = "image-tags")]
+use crate::ImageTags;
+use crate::{
+    prelude::*, Alignment, AttrValue, Document, ElementKind, Errcode, Node, Rule, TransformOptions,
+};
+
+// Value of an attribute of an element
+enum Attr {
+    Value(AttrValue),
+    List(Vec<String>),
+    #[cfg(feature = "image-tags")]
+    Tags(ImageTags),
+}
+
+impl Attr {
+    fn str(value: &str) -> Attr {
+        Attr::Value(AttrValue::Str(value.to_string()))
+    }
+
+    fn int(value: usize) -> Attr {
+        Attr::Value(AttrValue::Int(value as i64))
+    }
+
+    fn write(&self, out: &mut String) {
+        match self {
+            Attr::Value(AttrValue::Bool(value)) => out.push_str(&value.to_string()),
+            Attr::Value(AttrValue::Int(value)) => out.push_str(&value.to_string()),
+            Attr::Value(AttrValue::Str(value)) => write_string(out, value),
+            Attr::List(values) => {
+                out.push('[');
+                for (n, value) in values.iter().enumerate() {
+                    if n > 0 {
+                        out.push(',');
+                    }
+                    write_string(out, value);
+                }
+                out.push(']');
+            }
+            #[cfg(feature = "image-tags")]
+            Attr::Tags(tags) => {
+                out.push('{');
+                for (n, (key, value)) in tags.iter().enumerate() {
+                    if n > 0 {
+                        out.push(',');
+                    }
+                    write_string(out, key);
+                    out.push(':');
+                    Attr::Value(AttrValue::parse(value)).write(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Elements of a document in the [JSON Lines](https://jsonlines.org) format, one object
+/// per element in the order they are written, for the tools outside of Rust to process
+/// documents (`jq`, shell pipelines, ...)
+///
+/// Each object holds:
+/// - `kind`: kind of the element in snake case (`header`, `link`, `list_item`, ...)
+/// - `text`: markdown of the element
+/// - `attrs`: attributes of the element, like the `level` of a heading, the `url` of a link,
+///   the `language` of a code block or the `tags` of an image
+/// - `span`: `start` and `end` byte offsets, `line` and `column` of the element
+///
+/// The texts of the elements are the ones of the `Document` parsed from the input, with
+/// the `\r\n` line endings read as `\n`.
+///
+/// ```
+/// # use mdtrans::events_jsonl;
+/// let events = events_jsonl("# Intro\n\nSee [this](page.md)").unwrap();
+/// let lines = events.lines().collect::<Vec<_>>();
+/// assert_eq!(lines.len(), 5);
+/// assert_eq!(
+///     lines[0],
+///     concat!(
+///         r##"{"kind":"header","text":"# Intro","attrs":{"level":1},"##,
+///         r#""span":{"start":0,"end":7,"line":1,"column":1}}"#,
+///     )
+/// );
+/// let link = r#"{"kind":"link","text":"[this](page.md)","attrs":{"url":"page.md"}"#;
+/// assert!(lines[4].starts_with(link));
+/// ```
+pub fn events_jsonl(input: &str) -> Result<String, Errcode> {
+    events_jsonl_with_options(input, &TransformOptions::default())
+}
+
+/// Same as `events_jsonl`, the document being parsed as set by `TransformOptions::parse_mode`
+pub fn events_jsonl_with_options(
+    input: &str,
+    options: &TransformOptions,
+) -> Result<String, Errcode> {
+    let document = Document::parse_with_mode(input, options.parse_mode)?;
+    let mut out = String::with_capacity(input.len() * 4);
+    for child in document.root().children() {
+        write_events(&mut out, child);
+    }
+    Ok(out)
+}
+
+// NOTE    The texts holding other elements, like the content of a heading, are not elements
+//         on their own, only their content is written
+fn write_events(out: &mut String, node: &Node) {
+    let kind = node.kind();
+    let is_element = match kind {
+        ElementKind::Part | ElementKind::Document => false,
+        ElementKind::Text => node.children().is_empty(),
+        _ => true,
+    };
+    if is_element {
+        let span = node.span();
+        out.push_str("{\"kind\":");
+        write_string(out, kind.name());
+        out.push_str(",\"text\":");
+        write_string(out, node.as_str());
+        out.push_str(",\"attrs\":{");
+        for (n, (key, value)) in attrs(node).iter().enumerate() {
+            if n > 0 {
+                out.push(',');
+            }
+            write_string(out, key);
+            out.push(':');
+            value.write(out);
+        }
+        out.push_str(&format!(
+            "}},\"span\":{{\"start\":{},\"end\":{},\"line\":{},\"column\":{}}}}}\n",
+            span.start, span.end, span.line, span.column
+        ));
+    }
+    for child in node.children() {
+        write_events(out, child);
+    }
+}
+
+fn attrs(node: &Node) -> Vec<(&'static str, Attr)> {
+    let children = node.children();
+    let texts = |rule: Rule| {
+        children
+            .iter()
+            .filter(|c| c.rule() == rule)
+            .map(|c| c.as_str().trim_matches('"').to_string())
+            .collect::<Vec<String>>()
+    };
+    let first = |rule: Rule| children.iter().find(|c| c.rule() == rule);
+    match node.rule() {
+        Rule::h1 | Rule::h2 | Rule::h3 | Rule::h4 | Rule::h5 | Rule::h6 => {
+            let level = node.as_str().chars().take_while(|c| *c == '#').count();
+            vec![("level", Attr::int(level))]
+        }
+        Rule::link => first(Rule::url)
+            .map(|url| vec![("url", Attr::str(url.as_str()))])
+            .unwrap_or_default(),
+        Rule::autolink => vec![("url", Attr::str(node.as_str()))],
+        #[cfg(feature = "reflinks")]
+        Rule::reflink => first(Rule::slug)
+            .map(|slug| vec![("slug", Attr::str(slug.as_str()))])
+            .unwrap_or_default(),
+        #[cfg(feature = "reflinks")]
+        Rule::refurl => {
+            let mut attrs = vec![("slug", Attr::str(children[0].as_str()))];
+            attrs.extend(first(Rule::refurl_url).map(|url| ("url", Attr::str(url.as_str()))));
+            attrs.extend(first(Rule::refurl_title).map(|t| ("title", Attr::str(t.as_str()))));
+            attrs
+        }
+        Rule::image => {
+            let alt = children
+                .iter()
+                .take_while(|c| c.rule() != Rule::url)
+                .map(|c| c.as_str())
+                .collect::<String>();
+            let mut attrs = vec![("alt", Attr::Value(AttrValue::Str(alt)))];
+            attrs.extend(first(Rule::url).map(|url| ("url", Attr::str(url.as_str()))));
+            #[cfg(feature = "image-tags")]
+            if let Some(tags) = first(Rule::image_tags) {
+                let tags = tags
+                    .children()
+                    .iter()
+                    .filter_map(|tag| match tag.children() {
+                        [key, value] => {
+                            Some((key.as_str().to_string(), value.as_str().to_string()))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                attrs.push(("tags", Attr::Tags(tags)));
+            }
+            attrs
+        }
+        Rule::footnote_ref | Rule::footnote_def => vec![("label", Attr::str(children[0].as_str()))],
+        Rule::crossref => vec![("slug", Attr::str(children[0].as_str()))],
+        Rule::citation => vec![("keys", Attr::List(texts(Rule::citation_key)))],
+        Rule::shortcode => {
+            let name = first(Rule::shortcode_name).map_or("", |name| name.as_str());
+            vec![
+                ("name", Attr::str(name)),
+                ("args", Attr::List(texts(Rule::shortcode_arg))),
+            ]
+        }
+        Rule::alert => first(Rule::alert_kind)
+            .map(|kind| vec![("kind", Attr::str(&kind.as_str().to_lowercase()))])
+            .unwrap_or_default(),
+        Rule::codeblock => {
+            let mut attrs = vec![];
+            if let Some(language) = children.first().filter(|c| c.rule() == Rule::slug) {
+                attrs.push(("language", Attr::str(language.as_str())));
+            }
+            attrs.push(("flags", Attr::List(texts(Rule::codeblock_flag))));
+            attrs
+        }
+        Rule::ordered_list => children
+            .first()
+            .and_then(|element| element.children().first())
+            .and_then(|number| number.as_str().parse().ok())
+            .map(|start| vec![("start", Attr::int(start))])
+            .unwrap_or_default(),
+        Rule::list_element | Rule::ordered_list_element => {
+            let mut attrs = vec![];
+            if let Some(number) = first(Rule::list_number).and_then(|n| n.as_str().parse().ok()) {
+                attrs.push(("number", Attr::int(number)));
+            }
+            if let Some(checkbox) = first(Rule::task_checkbox) {
+                let checked = checkbox.as_str() != "[ ]";
+                attrs.push(("checked", Attr::Value(AttrValue::Bool(checked))));
+            }
+            attrs
+        }
+        Rule::table => {
+            let align = children[1]
+                .children()
+                .iter()
+                .map(|delim| match Alignment::from_delimiter(delim.as_str()) {
+                    Alignment::None => "none",
+                    Alignment::Left => "left",
+                    Alignment::Center => "center",
+                    Alignment::Right => "right",
+                })
+                .map(String::from)
+                .collect();
+            vec![("align", Attr::List(align))]
+        }
+        _ => vec![],
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{prelude::*, Node};
+
+/// A footnote definition, numbered in order of first reference in the document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Footnote {
+    pub number: usize,
+    pub label: String,
+    pub text: String,
+}
+
+// Footnotes numbering and definitions gathered during the peek pass
+#[derive(Default)]
+pub(crate) struct FootnoteTable<'i> {
+    numbers: HashMap<String, usize>,
+    definitions: Vec<(String, &'i Node<'i>)>,
+    pub(crate) section_emitted: bool,
+}
+
+impl<'i> FootnoteTable<'i> {
+    #[cfg(feature = "parallel")]
+    pub fn with_numbers(numbers: HashMap<String, usize>) -> FootnoteTable<'i> {
+        FootnoteTable {
+            numbers,
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn numbers(&self) -> &HashMap<String, usize> {
+        &self.numbers
+    }
+
+    pub fn number(&mut self, label: &str) -> usize {
+        let next = self.numbers.len() + 1;
+        *self.numbers.entry(label.to_string()).or_insert(next)
+    }
+
+    pub fn define(&mut self, label: String, text: &'i Node<'i>) {
+        if !self.is_defined(&label) {
+            self.definitions.push((label, text));
+        }
+    }
+
+    pub fn is_defined(&self, label: &str) -> bool {
+        self.definitions.iter().any(|(l, _)| l == label)
+    }
+
+    pub fn has_definitions(&self) -> bool {
+        !self.definitions.is_empty()
+    }
+
+    // Unreferenced definitions are numbered after the referenced ones
+    pub fn sorted_definitions(&mut self) -> Vec<(usize, String, &'i Node<'i>)> {
+        let definitions = self.definitions.clone();
+        let mut sorted = definitions
+            .into_iter()
+            .map(|(label, text)| (self.number(&label), label, text))
+            .collect::<Vec<_>>();
+        sorted.sort_by_key(|(number, _, _)| *number);
+        sorted
+    }
+}
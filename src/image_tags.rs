@@ -0,0 +1,336 @@
+#[cfg(feature = "image-tags")]
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+
+use crate::prelude::*;
+#[cfg(feature = "image-tags")]
+use crate::{Document, Errcode, Node, Rule, Span, TransformOptions};
+
+/// Tags `[key: value]` written after an image, given to the image hooks
+///
+/// The tags keep the order in which they are written, so the output built from them is the
+/// same from one run to the other. A key written twice keeps the value written last.
+///
+/// ```
+/// # use mdtrans::ImageTags;
+/// let tags = ImageTags::from_iter([
+///     ("width".to_string(), "200".to_string()),
+///     ("credit".to_string(), "Bob".to_string()),
+/// ]);
+/// assert_eq!(tags.get("credit").map(String::as_str), Some("Bob"));
+/// assert_eq!(tags.keys().collect::<Vec<_>>(), ["width", "credit"]);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ImageTags {
+    tags: Vec<(String, String)>,
+}
+
+impl core::fmt::Debug for ImageTags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl ImageTags {
+    /// Set the value of a tag, keeping its place if it is already set, returning its
+    /// previous value
+    pub fn insert<K: ToString, V: ToString>(&mut self, key: K, value: V) -> Option<String> {
+        let (key, value) = (key.to_string(), value.to_string());
+        match self.get_mut(&key) {
+            Some(previous) => Some(core::mem::replace(previous, value)),
+            None => {
+                self.tags.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.tags.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut String> {
+        self.tags.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.tags.iter().position(|(k, _)| k == key)?;
+        Some(self.tags.remove(index).1)
+    }
+
+    /// Value of a tag read as a boolean, an integer or a string, see `AttrValue::parse`
+    pub fn value(&self, key: &str) -> Option<AttrValue> {
+        self.get(key).map(|value| AttrValue::parse(value))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Keys and values of the tags, in the order they are written
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.tags.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.tags.iter().map(|(k, _)| k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Value of a tag `key: value`, typed from the way it is written
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttrValue {
+    /// `true` or `false`
+    Bool(bool),
+    /// Integer like `300` or `-2`
+    Int(i64),
+    /// Any other value, or a value written between quotes, without them
+    Str(String),
+}
+
+impl AttrValue {
+    /// Type of a raw value, the quoted ones (`"true"`, `"12"`) being strings
+    ///
+    /// ```
+    /// # use mdtrans::AttrValue;
+    /// assert_eq!(AttrValue::parse("true"), AttrValue::Bool(true));
+    /// assert_eq!(AttrValue::parse("-20"), AttrValue::Int(-20));
+    /// assert_eq!(AttrValue::parse("\"true\""), AttrValue::Str("true".to_string()));
+    /// assert_eq!(AttrValue::parse("20px"), AttrValue::Str("20px".to_string()));
+    /// ```
+    pub fn parse(raw: &str) -> AttrValue {
+        if let Some(quoted) = raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) {
+            return AttrValue::Str(quoted.to_string());
+        }
+        match raw {
+            "true" => AttrValue::Bool(true),
+            "false" => AttrValue::Bool(false),
+            _ => match raw.parse() {
+                Ok(int) => AttrValue::Int(int),
+                Err(_) => AttrValue::Str(raw.to_string()),
+            },
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttrValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            AttrValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromIterator<(String, String)> for ImageTags {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let mut tags = ImageTags::default();
+        for (key, value) in iter {
+            tags.insert(key, value);
+        }
+        tags
+    }
+}
+
+impl IntoIterator for ImageTags {
+    type Item = (String, String);
+    type IntoIter = alloc::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tags.into_iter()
+    }
+}
+
+// Tags understood by the framework and the renderers, known without being registered
+const BUILTIN_TAGS: [&str; 4] = ["width", "height", "caption", "id"];
+
+type TagValidator = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Tags `[key: value]` allowed after the images, with the validators of their values
+///
+/// The tags used by the framework and the renderers (`width`, `height`, `caption`, `id`)
+/// are always allowed.
+#[derive(Clone, Default)]
+pub struct ImageTagSchema {
+    tags: HashMap<String, Option<TagValidator>>,
+}
+
+impl core::fmt::Debug for ImageTagSchema {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.tags.keys()).finish()
+    }
+}
+
+impl ImageTagSchema {
+    /// Allow a tag with any value
+    pub fn tag<S: ToString>(mut self, key: S) -> Self {
+        self.tags.insert(key.to_string(), None);
+        self
+    }
+
+    /// Allow a tag whose values are checked by `validator`, returning why a value
+    /// is not valid
+    pub fn tag_with<S, F>(mut self, key: S, validator: F) -> Self
+    where
+        S: ToString,
+        F: Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.tags.insert(key.to_string(), Some(Arc::new(validator)));
+        self
+    }
+
+    /// Why the tag is not valid, `unknown tag` for the keys not allowed
+    pub fn validate(&self, key: &str, value: &str) -> Result<(), String> {
+        if BUILTIN_TAGS.contains(&key) {
+            return Ok(());
+        }
+        match self.tags.get(key) {
+            None => Err("unknown tag".to_string()),
+            Some(Some(validator)) => validator(value),
+            Some(None) => Ok(()),
+        }
+    }
+}
+
+/// Tag `[key: value]` written after an image, found by the `ImageTagAudit`
+#[cfg(feature = "image-tags")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageTag {
+    /// Path of the document containing the image
+    pub file: String,
+    pub key: String,
+    /// Value of the tag, without its quotes
+    pub value: String,
+    pub span: Span,
+}
+
+/// Image tag not matching the schema of the `ImageTagAudit`
+#[cfg(feature = "image-tags")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageTagIssue {
+    pub tag: ImageTag,
+    /// `unknown tag` for the keys not in the schema, or the error of the validator of the key
+    pub reason: String,
+}
+
+/// Listing of the image tags used across a set of documents, checked against a schema
+///
+/// ```
+/// # use mdtrans::{ImageTagAudit, ImageTagSchema};
+/// let schema = ImageTagSchema::default()
+///     .tag("credit")
+///     .tag_with("loading", |value| match value {
+///         "lazy" | "eager" => Ok(()),
+///         _ => Err(format!("expected lazy or eager, got {value}")),
+///     });
+/// let mut audit = ImageTagAudit::default().schema(schema);
+/// audit.add_document("a.md", "![cat](cat.png)[credit: Bob, loading: later]").unwrap();
+/// audit.add_document("b.md", "![dog](dog.png)[credits: Alice]").unwrap();
+///
+/// assert_eq!(audit.keys().into_iter().collect::<Vec<_>>(), [
+///     ("credit".to_string(), 1),
+///     ("credits".to_string(), 1),
+///     ("loading".to_string(), 1),
+/// ]);
+/// let issues = audit.check();
+/// assert_eq!(issues[0].reason, "expected lazy or eager, got later");
+/// assert_eq!((issues[1].tag.file.as_str(), issues[1].reason.as_str()), ("b.md", "unknown tag"));
+/// ```
+#[cfg(feature = "image-tags")]
+#[derive(Clone, Debug, Default)]
+pub struct ImageTagAudit {
+    tags: Vec<ImageTag>,
+    schema: ImageTagSchema,
+    options: TransformOptions,
+}
+
+#[cfg(feature = "image-tags")]
+impl ImageTagAudit {
+    /// Options of the documents added afterwards, setting how they are parsed
+    pub fn options(mut self, options: TransformOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Tags the documents are checked against
+    pub fn schema(mut self, schema: ImageTagSchema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    pub fn add_document<S: ToString>(&mut self, path: S, input: &str) -> Result<(), Errcode> {
+        let document = Document::parse_with_mode(input, self.options.parse_mode)?;
+        collect_tags(document.root(), &path.to_string(), &mut self.tags);
+        Ok(())
+    }
+
+    /// Tags found in the documents, in the order of the documents
+    pub fn tags(&self) -> &[ImageTag] {
+        &self.tags
+    }
+
+    /// Keys of the custom tags used, with the number of times each is used
+    pub fn keys(&self) -> BTreeMap<String, usize> {
+        let mut keys = BTreeMap::new();
+        for tag in self.custom_tags() {
+            *keys.entry(tag.key.clone()).or_insert(0) += 1;
+        }
+        keys
+    }
+
+    pub fn check(&self) -> Vec<ImageTagIssue> {
+        self.custom_tags()
+            .filter_map(|tag| {
+                let reason = self.schema.validate(&tag.key, &tag.value).err()?;
+                Some(ImageTagIssue {
+                    tag: tag.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    fn custom_tags(&self) -> impl Iterator<Item = &ImageTag> {
+        self.tags
+            .iter()
+            .filter(|tag| !BUILTIN_TAGS.contains(&tag.key.as_str()))
+    }
+}
+
+#[cfg(feature = "image-tags")]
+fn collect_tags(node: &Node, file: &str, tags: &mut Vec<ImageTag>) {
+    if node.rule() == Rule::img_tag {
+        if let [key, value] = node.children() {
+            tags.push(ImageTag {
+                file: file.to_string(),
+                key: key.as_str().to_string(),
+                value: value.as_str().trim_matches('"').to_string(),
+                span: node.span(),
+            });
+        }
+        return;
+    }
+    for child in node.children() {
+        collect_tags(child, file, tags);
+    }
+}
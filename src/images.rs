@@ -0,0 +1,29 @@
+use crate::prelude::*;
+
+/// Variant of an image returned by `TransformOptions::image_resolver`, one of the
+/// candidates a browser picks from depending on the size of the screen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageCandidate {
+    pub url: String,
+    /// Width of the image in pixels
+    pub width: usize,
+}
+
+impl ImageCandidate {
+    pub fn new<S: ToString>(url: S, width: usize) -> ImageCandidate {
+        ImageCandidate {
+            url: url.to_string(),
+            width,
+        }
+    }
+}
+
+/// Value of the HTML `srcset` attribute listing the candidates (`a.png 640w, b.png 1280w`),
+/// not escaped
+pub fn srcset(candidates: &[ImageCandidate]) -> String {
+    candidates
+        .iter()
+        .map(|candidate| format!("{} {}w", candidate.url, candidate.width))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
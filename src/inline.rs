@@ -0,0 +1,517 @@
+use crate::{
+    document::Tree, prelude::*, AlertKind, Alignment, Errcode, Footnote, ImageTags, LinkKind,
+    ListItemInfo, MarkdownTransformer, TransformOptions,
+};
+
+/// Inline content of a block, keeping the formatting that the string hooks flatten
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inline {
+    Text(String),
+    Bold(Vec<Inline>),
+    Italic(Vec<Inline>),
+    Strike(Vec<Inline>),
+    Code(String),
+    /// Direct, reference or bare link, reference links being resolved to their URL
+    Link {
+        text: Vec<Inline>,
+        url: String,
+    },
+    /// Image with its tags, the `caption` being one of them
+    Image {
+        alt: String,
+        url: String,
+        tags: ImageTags,
+    },
+    FootnoteRef(String),
+    /// Hard line break, or the end of a quote line
+    LineBreak,
+}
+
+impl Inline {
+    /// Text of the runs, without their formatting
+    pub fn plain_text(runs: &[Inline]) -> String {
+        let mut text = String::new();
+        for run in runs {
+            match run {
+                Inline::Text(t) | Inline::Code(t) => text.push_str(t),
+                Inline::Bold(inner) | Inline::Italic(inner) | Inline::Strike(inner) => {
+                    text.push_str(&Inline::plain_text(inner))
+                }
+                Inline::Link { text: inner, .. } => text.push_str(&Inline::plain_text(inner)),
+                Inline::Image { alt, .. } => text.push_str(alt),
+                Inline::FootnoteRef(label) => text.push_str(&format!("[^{label}]")),
+                Inline::LineBreak => text.push('\n'),
+            }
+        }
+        text
+    }
+}
+
+/// Alternative to `MarkdownTransformer`, whose block hooks receive their content as
+/// structured inline runs instead of already rendered strings
+///
+/// Useful for output formats that build their own representation of the text (DOCX, JSON, ...).
+/// The document is walked by the same framework as the `MarkdownTransformer`, with the same
+/// `TransformOptions`. All the hooks have a default implementation rendering plain text.
+#[allow(unused_variables)]
+pub trait InlineTransformer {
+    fn transform_header(&mut self, level: usize, content: Vec<Inline>) -> String {
+        Inline::plain_text(&content)
+    }
+
+    fn transform_paragraph(&mut self, content: Vec<Inline>) -> String {
+        Inline::plain_text(&content)
+    }
+
+    /// Lines of the quote are separated by `Inline::LineBreak`
+    fn transform_quote(&mut self, content: Vec<Inline>) -> String {
+        Inline::plain_text(&content)
+    }
+
+    /// GitHub alerts, falling back to a regular quote
+    fn transform_alert(&mut self, kind: AlertKind, content: Vec<Inline>) -> String {
+        self.transform_quote(content)
+    }
+
+    /// Collapsible sections (feature `details`), falling back to the summary as a paragraph
+    /// followed by the `blocks` of the section
+    fn transform_details(&mut self, summary: Vec<Inline>, blocks: Vec<String>) -> String {
+        let mut parts = vec![self.transform_paragraph(summary)];
+        parts.extend(blocks);
+        parts.join("\n\n")
+    }
+
+    /// Groups of tabs (feature `tabs`), as the title and the `blocks` of each tab, falling
+    /// back to the title of each tab as a paragraph followed by its blocks
+    fn transform_tab_group(&mut self, tabs: Vec<(String, Vec<String>)>) -> String {
+        let mut parts = vec![];
+        for (title, blocks) in tabs {
+            parts.push(self.transform_paragraph(vec![Inline::Text(title)]));
+            parts.extend(blocks);
+        }
+        parts.join("\n\n")
+    }
+
+    /// `blocks` are the already transformed blocks indented under the element
+    fn transform_list_element(
+        &mut self,
+        content: Vec<Inline>,
+        blocks: Vec<String>,
+        info: ListItemInfo,
+    ) -> String {
+        let mut lines = vec![Inline::plain_text(&content)];
+        lines.extend(blocks);
+        lines.join("\n")
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        elements.join("\n")
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        self.transform_list(elements)
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        lang: Option<String>,
+        flags: Vec<String>,
+        code: String,
+    ) -> String {
+        code
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<Vec<Inline>>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    ) -> String {
+        let line = |row: Vec<Vec<Inline>>| {
+            row.iter()
+                .map(|cell| Inline::plain_text(cell))
+                .collect::<Vec<String>>()
+                .join(" | ")
+        };
+        let mut lines = vec![line(header)];
+        lines.extend(rows.into_iter().map(line));
+        lines.join("\n")
+    }
+
+    /// Footnote definitions, in the footnotes section, falling back to a paragraph
+    fn transform_footnote(&mut self, label: String, content: Vec<Inline>) -> String {
+        self.transform_paragraph(content)
+    }
+
+    fn transform_horiz_sep(&mut self) -> String {
+        String::new()
+    }
+
+    /// Raw HTML blocks, dropped by default
+    fn transform_html(&mut self, raw: String) -> String {
+        String::new()
+    }
+
+    /// Assemble the transformed top-level blocks of the document
+    fn transform_document(&mut self, blocks: Vec<String>) -> String {
+        blocks.join("\n\n")
+    }
+}
+
+// Output of the hooks of `InlineAdapter` kept aside, the strings going through the framework
+// only holding tokens pointing to them
+enum Piece {
+    Run(Inline),
+    Block(String),
+}
+
+// NOTE    Tokens are the index of the piece between two `TOKEN` characters, the texts of the
+//         document having theirs replaced
+const TOKEN: char = '\u{0}';
+
+/// `MarkdownTransformer` giving the runs and blocks of the document to an `InlineTransformer`,
+/// the document being walked by the same framework as the string hooks
+pub(crate) struct InlineAdapter<'t, T: InlineTransformer> {
+    transformer: &'t mut T,
+    pieces: Vec<Option<Piece>>,
+    #[cfg(feature = "reflinks")]
+    definitions: HashMap<String, String>,
+}
+
+impl<'t, T: InlineTransformer> InlineAdapter<'t, T> {
+    pub(crate) fn new(transformer: &'t mut T) -> InlineAdapter<'t, T> {
+        InlineAdapter {
+            transformer,
+            pieces: vec![],
+            #[cfg(feature = "reflinks")]
+            definitions: HashMap::new(),
+        }
+    }
+
+    fn token(&mut self, piece: Piece) -> String {
+        self.pieces.push(Some(piece));
+        format!("{TOKEN}{}{TOKEN}", self.pieces.len() - 1)
+    }
+
+    fn run(&mut self, run: Inline) -> String {
+        self.token(Piece::Run(run))
+    }
+
+    fn block(&mut self, block: String) -> String {
+        self.token(Piece::Block(block))
+    }
+
+    // Pieces of an output in order, its texts split at the line breaks
+    fn pieces(&mut self, output: &str) -> Vec<Piece> {
+        let mut pieces = vec![];
+        for (n, part) in output.split(TOKEN).enumerate() {
+            let piece = match n % 2 {
+                0 => None,
+                _ => part
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| self.pieces.get_mut(index))
+                    .and_then(Option::take),
+            };
+            match piece {
+                Some(piece) => pieces.push(piece),
+                None => {
+                    for (n, line) in part.split('\n').enumerate() {
+                        if n > 0 {
+                            pieces.push(Piece::Run(Inline::LineBreak));
+                        }
+                        if !line.is_empty() {
+                            pieces.push(Piece::Run(Inline::Text(line.to_string())));
+                        }
+                    }
+                }
+            }
+        }
+        pieces
+    }
+
+    fn runs(&mut self, output: &str) -> Vec<Inline> {
+        let runs = self
+            .pieces(output)
+            .into_iter()
+            .filter_map(|piece| match piece {
+                Piece::Run(run) => Some(run),
+                Piece::Block(_) => None,
+            });
+        merge_texts(runs.collect())
+    }
+
+    // Blocks of an output, the runs written between them, like the bibliography, being
+    // transformed as paragraphs
+    fn blocks(&mut self, output: &str) -> Vec<String> {
+        let mut blocks = vec![];
+        let mut runs = vec![];
+        for piece in self.pieces(output) {
+            match piece {
+                Piece::Run(run) => runs.push(run),
+                Piece::Block(block) => {
+                    blocks.extend(self.paragraph(core::mem::take(&mut runs)));
+                    blocks.push(block);
+                }
+            }
+        }
+        blocks.extend(self.paragraph(runs));
+        blocks
+    }
+
+    // NOTE    Paragraphs only made of reference definitions are not rendered
+    fn paragraph(&mut self, runs: Vec<Inline>) -> Option<String> {
+        let content = merge_texts(runs);
+        let is_blank = content.iter().all(|run| match run {
+            Inline::Text(text) => text.trim().is_empty(),
+            Inline::LineBreak => true,
+            _ => false,
+        });
+        (!is_blank).then(|| self.transformer.transform_paragraph(content))
+    }
+
+    /// Assemble the top-level blocks of the output of the framework
+    pub(crate) fn transform_document(&mut self, output: &str) -> String {
+        let blocks = self.blocks(output);
+        self.transformer.transform_document(blocks)
+    }
+}
+
+#[allow(unused_variables)]
+impl<T: InlineTransformer> MarkdownTransformer for InlineAdapter<'_, T> {
+    fn transform_text(&mut self, text: String) -> String {
+        text.replace(TOKEN, "\u{FFFD}")
+    }
+
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        let content = self.runs(&text);
+        let header = self.transformer.transform_header(level, content);
+        self.block(header)
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        let runs = self.runs(&text);
+        self.run(Inline::Bold(runs))
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        let runs = self.runs(&text);
+        self.run(Inline::Italic(runs))
+    }
+
+    fn transform_strikethrough(&mut self, text: String) -> String {
+        let runs = self.runs(&text);
+        self.run(Inline::Strike(runs))
+    }
+
+    fn transform_inline_code(&mut self, text: String) -> String {
+        self.run(Inline::Code(text))
+    }
+
+    fn transform_link(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        let text = self.runs(&text);
+        self.run(Inline::Link { text, url })
+    }
+
+    fn transform_autolink(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        let text = vec![Inline::Text(text)];
+        self.run(Inline::Link { text, url })
+    }
+
+    // NOTE    References can be defined after the links using them
+    #[cfg(feature = "reflinks")]
+    fn peek_refurl(&mut self, slug: String, url: String, title: Option<String>) {
+        self.definitions.insert(slug, url);
+    }
+
+    #[cfg(feature = "reflinks")]
+    fn transform_reflink(&mut self, text: String, slug: String, title: Option<String>) -> String {
+        match self.definitions.get(&slug).cloned() {
+            Some(url) => {
+                let text = self.runs(&text);
+                self.run(Inline::Link { text, url })
+            }
+            None => format!("[{text}][{slug}]"),
+        }
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, tags: ImageTags) -> String {
+        self.run(Inline::Image { alt, url, tags })
+    }
+
+    // NOTE    The caption is given back to the tags of the image
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        let mut pieces = self.pieces(&image);
+        if let [Piece::Run(Inline::Image { tags, .. })] = pieces.as_mut_slice() {
+            tags.insert("caption", caption);
+        }
+        pieces.into_iter().map(|piece| self.token(piece)).collect()
+    }
+
+    fn transform_footnote_ref(&mut self, label: String, number: usize) -> String {
+        self.run(Inline::FootnoteRef(label))
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let mut section = String::new();
+        for entry in entries {
+            let content = self.runs(&entry.text);
+            let footnote = self.transformer.transform_footnote(entry.label, content);
+            section += &self.block(footnote);
+        }
+        section
+    }
+
+    fn transform_comment(&mut self, text: String) -> String {
+        String::new()
+    }
+
+    fn transform_html_block(&mut self, tag: String, html: String) -> String {
+        let html = self.transformer.transform_html(html);
+        self.block(html)
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        let content = self.runs(&text);
+        let quote = self.transformer.transform_quote(content);
+        self.block(quote)
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let content = self.runs(&body);
+        let alert = self.transformer.transform_alert(kind, content);
+        self.block(alert)
+    }
+
+    fn transform_details(&mut self, summary: String, body: String) -> String {
+        let summary = self.runs(&summary);
+        let blocks = self.blocks(&body);
+        let details = self.transformer.transform_details(summary, blocks);
+        self.block(details)
+    }
+
+    fn transform_tab_group(&mut self, tabs: Vec<(String, String)>) -> String {
+        let tabs = tabs
+            .into_iter()
+            .map(|(title, body)| (Inline::plain_text(&self.runs(&title)), self.blocks(&body)))
+            .collect();
+        let group = self.transformer.transform_tab_group(tabs);
+        self.block(group)
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        flags: Vec<String>,
+        text: String,
+    ) -> String {
+        let codeblock = self.transformer.transform_codeblock(language, flags, text);
+        self.block(codeblock)
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        let separator = self.transformer.transform_horiz_sep();
+        self.block(separator)
+    }
+
+    fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+        let mut content = vec![];
+        let mut blocks = vec![];
+        for piece in self.pieces(&element) {
+            match piece {
+                Piece::Run(run) => content.push(run),
+                Piece::Block(block) => blocks.push(block),
+            }
+        }
+        let content = merge_texts(content);
+        let element = self
+            .transformer
+            .transform_list_element(content, blocks, info);
+        self.block(element)
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        let elements = elements.iter().map(|e| self.blocks(e).concat()).collect();
+        let list = self.transformer.transform_list(elements);
+        self.block(list)
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        let elements = elements.iter().map(|e| self.blocks(e).concat()).collect();
+        let list = self.transformer.transform_ordered_list(start, elements);
+        self.block(list)
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let header = header.iter().map(|cell| self.runs(cell)).collect();
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| self.runs(cell)).collect())
+            .collect();
+        let table = self.transformer.transform_table(header, alignments, rows);
+        self.block(table)
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        String::new()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        let content = self.runs(&text);
+        match self.paragraph(content) {
+            Some(paragraph) => self.block(paragraph),
+            None => String::new(),
+        }
+    }
+
+    // NOTE    Pieces of the peek pass, like the rendered heading texts, are never used
+    fn finished(&mut self, peek: bool) -> String {
+        if peek {
+            self.pieces.clear();
+        }
+        String::new()
+    }
+}
+
+fn merge_texts(runs: Vec<Inline>) -> Vec<Inline> {
+    let mut merged: Vec<Inline> = vec![];
+    for run in runs {
+        match (merged.last_mut(), run) {
+            (Some(Inline::Text(last)), Inline::Text(text)) => last.push_str(&text),
+            (_, run) => merged.push(run),
+        }
+    }
+    // NOTE    Line breaks of reference definitions are left behind them
+    while let Some(Inline::Text(text)) = merged.last() {
+        if !text.trim().is_empty() {
+            break;
+        }
+        merged.pop();
+    }
+    merged
+}
+
+/// Parse the markdown input and transform it with an `InlineTransformer`
+pub fn transform_inline_markdown<T: InlineTransformer>(
+    input: &str,
+    transformer: &mut T,
+) -> Result<String, Errcode> {
+    transform_inline_markdown_with_options(input, transformer, &TransformOptions::default())
+}
+
+/// Same as `transform_inline_markdown`, with the options of the transformation
+pub fn transform_inline_markdown_with_options<T: InlineTransformer>(
+    input: &str,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<String, Errcode> {
+    let mut adapter = InlineAdapter::new(transformer);
+    let output =
+        Tree::parse(input, options.parse_mode)?.transform_with_options(&mut adapter, options);
+    Ok(adapter.transform_document(&output))
+}
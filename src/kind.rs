@@ -0,0 +1,214 @@
+use crate::{prelude::*, Rule};
+
+/// Kind of a markdown element, independent from the rules of the grammar
+///
+/// `Rule` is generated from the grammar and changes with it, the kinds stay the same,
+/// which makes them the ones to match on in code meant to survive the grammar changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ElementKind {
+    /// The whole document
+    Document,
+    /// Heading, with its level
+    Header(usize),
+    Paragraph,
+    /// Plain text, within another element
+    Text,
+    Bold,
+    Italic,
+    Strikethrough,
+    InlineCode,
+    Link,
+    /// Bare URL starting with `https://`, `http://` or `www.`, written in the text
+    Autolink,
+    /// Reference link `[text][slug]`
+    ReferenceLink,
+    /// Definition of a reference link `[slug]: url`
+    ReferenceDefinition,
+    Image,
+    /// Footnote reference `[^label]`
+    FootnoteReference,
+    /// Footnote definition `[^label]: text`
+    FootnoteDefinition,
+    /// `[FOOTNOTES]` marker
+    FootnotesMarker,
+    /// Cross-reference `[#slug]`
+    Crossref,
+    /// Citation `[@key]`
+    Citation,
+    /// `[BIBLIOGRAPHY]` marker
+    BibliographyMarker,
+    /// Shortcode `{{name args}}`
+    Shortcode,
+    Comment,
+    HtmlBlock,
+    Quote,
+    /// Quote starting with an alert marker like `[!NOTE]`
+    Alert,
+    /// Collapsible section `??? summary` (feature `details`)
+    Details,
+    /// Group of tabs `=== "Title"` (feature `tabs`)
+    TabGroup,
+    CodeBlock,
+    List,
+    OrderedList,
+    /// Element of a list or of an ordered list
+    ListItem,
+    Table,
+    TableRow,
+    TableCell,
+    HorizontalSeparator,
+    /// Empty lines between blocks
+    VerticalSpace,
+    /// Block kept as plain text as it couldn't be parsed (`ParseMode::Lenient`)
+    Invalid,
+    /// Component of another element that is not an element on its own
+    /// (the URL of a link, the language of a code block, ...)
+    Part,
+}
+
+impl ElementKind {
+    // Name of the kind in snake case, stable across versions
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ElementKind::Document => "document",
+            ElementKind::Header(_) => "header",
+            ElementKind::Paragraph => "paragraph",
+            ElementKind::Text => "text",
+            ElementKind::Bold => "bold",
+            ElementKind::Italic => "italic",
+            ElementKind::Strikethrough => "strikethrough",
+            ElementKind::InlineCode => "inline_code",
+            ElementKind::Link => "link",
+            ElementKind::Autolink => "autolink",
+            ElementKind::ReferenceLink => "reference_link",
+            ElementKind::ReferenceDefinition => "reference_definition",
+            ElementKind::Image => "image",
+            ElementKind::FootnoteReference => "footnote_reference",
+            ElementKind::FootnoteDefinition => "footnote_definition",
+            ElementKind::FootnotesMarker => "footnotes_marker",
+            ElementKind::Crossref => "crossref",
+            ElementKind::Citation => "citation",
+            ElementKind::BibliographyMarker => "bibliography_marker",
+            ElementKind::Shortcode => "shortcode",
+            ElementKind::Comment => "comment",
+            ElementKind::HtmlBlock => "html_block",
+            ElementKind::Quote => "quote",
+            ElementKind::Alert => "alert",
+            ElementKind::Details => "details",
+            ElementKind::TabGroup => "tab_group",
+            ElementKind::CodeBlock => "code_block",
+            ElementKind::List => "list",
+            ElementKind::OrderedList => "ordered_list",
+            ElementKind::ListItem => "list_item",
+            ElementKind::Table => "table",
+            ElementKind::TableRow => "table_row",
+            ElementKind::TableCell => "table_cell",
+            ElementKind::HorizontalSeparator => "horizontal_separator",
+            ElementKind::VerticalSpace => "vertical_space",
+            ElementKind::Invalid => "invalid",
+            ElementKind::Part => "part",
+        }
+    }
+
+    // Kinds of the elements expected by the grammar where it failed, the parts being
+    // reported as the element they belong to
+    pub(crate) fn expected(rules: Vec<Rule>) -> Vec<ElementKind> {
+        let mut kinds = Vec::with_capacity(rules.len());
+        for kind in rules.into_iter().map(ElementKind::owning) {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+
+    fn owning(rule: Rule) -> ElementKind {
+        match rule {
+            Rule::list_number | Rule::task_checkbox | Rule::list_element_blocks => {
+                ElementKind::ListItem
+            }
+            Rule::quote_line => ElementKind::Quote,
+            Rule::alert_kind => ElementKind::Alert,
+            Rule::inline_code_code => ElementKind::InlineCode,
+            Rule::codeblock_code | Rule::codeblock_flag | Rule::codeblock_flag_value => {
+                ElementKind::CodeBlock
+            }
+            Rule::comment_text => ElementKind::Comment,
+            Rule::html_tag_name => ElementKind::HtmlBlock,
+            Rule::url | Rule::link_text => ElementKind::Link,
+            Rule::citation_key => ElementKind::Citation,
+            Rule::shortcode_name | Rule::shortcode_arg => ElementKind::Shortcode,
+            Rule::table_align | Rule::table_delim_row => ElementKind::Table,
+            #[cfg(feature = "table-spans")]
+            Rule::table_row_continue => ElementKind::TableRow,
+            Rule::paragraph_newline | Rule::paragraph_lang => ElementKind::Paragraph,
+            #[cfg(feature = "image-tags")]
+            Rule::image_tags | Rule::img_tag | Rule::img_tag_key | Rule::img_tag_val => {
+                ElementKind::Image
+            }
+            #[cfg(feature = "reflinks")]
+            Rule::refurl_url | Rule::refurl_title => ElementKind::ReferenceDefinition,
+            #[cfg(feature = "details")]
+            Rule::details_body => ElementKind::Details,
+            #[cfg(feature = "tabs")]
+            Rule::tab | Rule::tab_title | Rule::tab_body => ElementKind::TabGroup,
+            rule => ElementKind::from(rule),
+        }
+    }
+}
+
+impl From<Rule> for ElementKind {
+    fn from(rule: Rule) -> Self {
+        match rule {
+            Rule::file => ElementKind::Document,
+            Rule::h1 => ElementKind::Header(1),
+            Rule::h2 => ElementKind::Header(2),
+            Rule::h3 => ElementKind::Header(3),
+            Rule::h4 => ElementKind::Header(4),
+            Rule::h5 => ElementKind::Header(5),
+            Rule::h6 => ElementKind::Header(6),
+            Rule::paragraph => ElementKind::Paragraph,
+            Rule::text | Rule::rich_txt | Rule::quote_txt | Rule::NO_INLINE_TEXT => {
+                ElementKind::Text
+            }
+            Rule::bold => ElementKind::Bold,
+            Rule::italic => ElementKind::Italic,
+            Rule::strike => ElementKind::Strikethrough,
+            Rule::inline_code => ElementKind::InlineCode,
+            Rule::link => ElementKind::Link,
+            Rule::autolink => ElementKind::Autolink,
+            #[cfg(feature = "reflinks")]
+            Rule::reflink => ElementKind::ReferenceLink,
+            #[cfg(feature = "reflinks")]
+            Rule::refurl => ElementKind::ReferenceDefinition,
+            Rule::image => ElementKind::Image,
+            Rule::footnote_ref => ElementKind::FootnoteReference,
+            Rule::footnote_def => ElementKind::FootnoteDefinition,
+            Rule::footnotes_marker => ElementKind::FootnotesMarker,
+            Rule::crossref => ElementKind::Crossref,
+            Rule::citation => ElementKind::Citation,
+            Rule::bibliography_marker => ElementKind::BibliographyMarker,
+            Rule::shortcode => ElementKind::Shortcode,
+            Rule::comment => ElementKind::Comment,
+            Rule::html_block => ElementKind::HtmlBlock,
+            Rule::quote => ElementKind::Quote,
+            Rule::alert => ElementKind::Alert,
+            #[cfg(feature = "details")]
+            Rule::details => ElementKind::Details,
+            #[cfg(feature = "tabs")]
+            Rule::tab_group => ElementKind::TabGroup,
+            Rule::codeblock => ElementKind::CodeBlock,
+            Rule::list => ElementKind::List,
+            Rule::ordered_list => ElementKind::OrderedList,
+            Rule::list_element | Rule::ordered_list_element => ElementKind::ListItem,
+            Rule::table => ElementKind::Table,
+            Rule::table_row => ElementKind::TableRow,
+            Rule::table_cell => ElementKind::TableCell,
+            Rule::horiz_sep => ElementKind::HorizontalSeparator,
+            Rule::vertical_space => ElementKind::VerticalSpace,
+            Rule::invalid => ElementKind::Invalid,
+            _ => ElementKind::Part,
+        }
+    }
+}
@@ -1,13 +1,140 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod alerts;
+mod anchors;
+mod batch;
+mod changelog;
+mod citations;
+mod codeblock;
+#[cfg(feature = "compare")]
+mod compare;
+mod context;
+mod crossref;
+mod direction;
+mod directives;
+mod document;
+mod drafts;
 mod errors;
+mod events;
+mod footnotes;
+mod image_tags;
+mod images;
+mod inline;
+mod kind;
+mod links;
+mod lists;
+mod localize;
+mod mdast;
+#[cfg(feature = "mdbook")]
+mod mdbook;
+mod numbering;
+mod options;
+mod outline;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod pipeline;
+mod prelude;
+mod reflow;
+mod rewrite;
+mod syntax;
+mod table;
 mod transform;
+pub mod transformers;
+mod visitor;
+mod warnings;
 
 #[cfg(test)]
 mod tests;
 
+pub use alerts::AlertKind;
+pub use anchors::{slugify, BrokenLink, LinkChecker, SlugCharset, SlugDedup};
+#[allow(deprecated)]
+pub use batch::transform_markdown_batch;
+pub use changelog::{ChangeGroup, Changelog, Release};
+pub use codeblock::{
+    extract_anchor, extract_code_blocks, rust_test_harness, CodeBlock, CodeTheme, CodeblockFilter,
+    CodeblockInfo, CodeblockLines, DiagramKind,
+};
+#[cfg(feature = "compare")]
+pub use compare::{compare_with_pulldown_cmark, DialectDiff};
+pub use context::{ElementContext, ElementId, Span};
+pub use crossref::CrossrefKind;
+pub use direction::{ParagraphInfo, TextDirection};
+pub use directives::Directive;
+pub use document::{Document, Node};
+pub use drafts::{DraftAudit, DraftMarker, DraftMarkerKind};
 pub use errors::Errcode;
+pub use events::{events_jsonl, events_jsonl_with_options};
+pub use footnotes::Footnote;
+pub use image_tags::{AttrValue, ImageTagSchema, ImageTags};
+#[cfg(feature = "image-tags")]
+pub use image_tags::{ImageTag, ImageTagAudit, ImageTagIssue};
+pub use images::{srcset, ImageCandidate};
+pub use inline::{
+    transform_inline_markdown, transform_inline_markdown_with_options, Inline, InlineTransformer,
+};
+pub use kind::ElementKind;
+pub use links::{extract_links, LinkKind, LinkRecord};
+pub use lists::ListItemInfo;
+pub use localize::{Localizer, Phrase};
+pub use mdast::mdast_json;
+#[cfg(feature = "mdbook")]
+pub use mdbook::MdbookPreprocessor;
+pub use numbering::{CounterKind, CounterReset, ElementNumber};
+pub use options::{
+    EmptyElements, ImageResolver, IncludeResolver, LineEnding, ParseMode, RuleRenderer,
+    TransformOptions, UnknownVariables,
+};
+pub use outline::{
+    extract_section, extract_section_with_options, outline, outline_with_options, task_progress,
+    task_progress_with_options, Heading, HeadingRewrite, TaskProgress,
+};
+#[cfg(feature = "parallel")]
+#[allow(deprecated)]
+pub use parallel::transform_markdown_string_parallel;
 use pest_derive::Parser;
+pub use pipeline::{Extension, Pipeline};
+/// Map type used in transformer hooks: `std`'s `HashMap`, or `hashbrown`'s without `std`
+pub use prelude::HashMap;
+pub use reflow::reflow;
+pub use rewrite::{link_changes, rewrite_links, LinkChange, Rewriter};
+pub use syntax::{syntax, SyntaxEntry};
+pub use table::{format_table, Alignment, TableCell};
 pub use transform::*;
+pub use visitor::{walk_markdown, MarkdownVisitor};
+pub use warnings::Warning;
+
+/// Version of the markdown dialect parsed, raised by the changes of the grammar altering the
+/// rendering of the existing documents
+///
+/// The dialect 1 is the grammar of mdtrans 0.1.5. The dialect 2 adds the tables, the ordered
+/// lists, the `*` and `+` bullets, the blocks indented under list elements, the task lists
+/// (`- [x] done`), the empty headers and list elements, the footnotes, the HTML blocks, the
+/// shortcodes (`{{ name }}`), the cross-references (`[#slug]`), the citations (`[@key]`)
+/// and the `[BIBLIOGRAPHY]` marker, the alerts (`> [!NOTE]`), the bare URLs
+/// (`https://...`), the paragraph languages (`{lang=fr}`), the code block flags, the titles
+/// of the reference definitions and the signed image tag values. The feature `dialect-v1`
+/// parses the dialect 1 for a release, rendering the documents written for 0.1.5 as this
+/// version did, to upgrade the crate before the documents.
+#[cfg(not(feature = "dialect-v1"))]
+pub const GRAMMAR_VERSION: u32 = 2;
+#[cfg(feature = "dialect-v1")]
+pub const GRAMMAR_VERSION: u32 = 1;
 
 #[derive(Parser)]
 #[grammar = "markdown.pest"]
+#[cfg_attr(feature = "reflinks", grammar = "grammar/reflinks.pest")]
+#[cfg_attr(not(feature = "reflinks"), grammar = "grammar/no_reflinks.pest")]
+#[cfg_attr(feature = "image-tags", grammar = "grammar/image_tags.pest")]
+#[cfg_attr(not(feature = "image-tags"), grammar = "grammar/no_image_tags.pest")]
+#[cfg_attr(feature = "details", grammar = "grammar/details.pest")]
+#[cfg_attr(not(feature = "details"), grammar = "grammar/no_details.pest")]
+#[cfg_attr(feature = "tabs", grammar = "grammar/tabs.pest")]
+#[cfg_attr(not(feature = "tabs"), grammar = "grammar/no_tabs.pest")]
+#[cfg_attr(feature = "table-spans", grammar = "grammar/table_spans.pest")]
+#[cfg_attr(not(feature = "table-spans"), grammar = "grammar/no_table_spans.pest")]
+#[cfg_attr(feature = "dialect-v1", grammar = "grammar/dialect_v1.pest")]
+#[cfg_attr(not(feature = "dialect-v1"), grammar = "grammar/dialect_v2.pest")]
 pub struct MarkdownParser;
@@ -0,0 +1,157 @@
+use crate::{
+    context::CurrentSpan, prelude::*, Document, ElementContext, Errcode, ImageTags,
+    MarkdownTransformer, Span, TransformOptions,
+};
+
+/// Where a link points to, relative to the site being generated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkKind {
+    /// Link to a page of the same site (relative path or same host)
+    Internal,
+    /// Link leaving the site
+    External,
+    /// Link to a fragment of the current page (`#section`)
+    Anchor,
+}
+
+impl LinkKind {
+    /// Classify an URL, `site_host` being the host of the site the document is published on
+    pub fn classify(url: &str, site_host: Option<&str>) -> LinkKind {
+        if url.starts_with('#') {
+            return LinkKind::Anchor;
+        }
+        let Some(host) = url_host(url) else {
+            return LinkKind::Internal;
+        };
+        match site_host {
+            Some(site) if host.eq_ignore_ascii_case(site) => LinkKind::Internal,
+            _ => LinkKind::External,
+        }
+    }
+}
+
+// Hosts serving nothing but badge images
+const BADGE_HOSTS: [&str; 4] = [
+    "img.shields.io",
+    "badgen.net",
+    "flat.badgen.net",
+    "badge.fury.io",
+];
+
+// Whether the image at `url` is a badge, see `TransformOptions::badges`
+pub(crate) fn is_badge(url: &str, options: &TransformOptions) -> bool {
+    if let Some(host) = url_host(url) {
+        let host = host.to_lowercase();
+        if BADGE_HOSTS.contains(&host.as_str()) || options.badge_hosts.contains(&host) {
+            return true;
+        }
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file == "badge.svg" || file == "badge"
+}
+
+// Returns the host of an absolute URL, or None if the URL is relative
+fn url_host(url: &str) -> Option<&str> {
+    let rest = if let Some(rest) = url.strip_prefix("//") {
+        rest
+    } else {
+        let (scheme, rest) = url.split_once(':')?;
+        let is_scheme = !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        if !is_scheme {
+            return None;
+        }
+        // NOTE    Schemes like `mailto:` have no authority, they always leave the site
+        rest.strip_prefix("//").unwrap_or("")
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    Some(host.split(':').next().unwrap_or(host))
+}
+
+/// Link or image found in a document, reference links being resolved to their URL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkRecord {
+    /// Text of the link without its formatting, or alternative text of the image
+    pub text: String,
+    pub url: String,
+    pub kind: LinkKind,
+    pub span: Span,
+}
+
+#[derive(Default)]
+struct LinkRecorder {
+    span: CurrentSpan,
+    transforming: bool,
+    definitions: HashMap<String, String>,
+    records: Vec<LinkRecord>,
+}
+
+impl LinkRecorder {
+    fn record(&mut self, text: &str, url: String, kind: LinkKind) {
+        if !self.transforming {
+            return;
+        }
+        self.records.push(LinkRecord {
+            text: text.to_string(),
+            url,
+            kind,
+            span: self.span.get(),
+        });
+    }
+}
+
+// NOTE    Link texts are only known during the transform pass, the peek pass gathers
+//         the reference definitions. The texts of some elements, like headings, are
+//         transformed during the peek pass too, only the transform pass records the links.
+impl MarkdownTransformer for LinkRecorder {
+    fn finished(&mut self, peek: bool) -> String {
+        self.transforming = peek;
+        String::new()
+    }
+
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.span.set(ctx);
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.definitions.insert(slug, url);
+    }
+
+    fn transform_link(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        self.record(&text, url, kind);
+        text
+    }
+
+    fn transform_autolink(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        self.record(&text, url, kind);
+        text
+    }
+
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        if let Some(url) = self.definitions.get(&slug).cloned() {
+            let kind = LinkKind::classify(&url, None);
+            self.record(&text, url, kind);
+        }
+        text
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, _add_tags: ImageTags) -> String {
+        let kind = LinkKind::classify(&url, None);
+        self.record(&alt, url, kind);
+        alt
+    }
+}
+
+/// Links, resolved reference links, autolinks and images of a document, in order
+///
+/// Reference links whose label is not defined are left out.
+pub fn extract_links(input: &str) -> Result<Vec<LinkRecord>, Errcode> {
+    let mut recorder = LinkRecorder::default();
+    Document::parse(input)?.transform_with_options(&mut recorder, &TransformOptions::default());
+    Ok(recorder.records)
+}
@@ -0,0 +1,39 @@
+use crate::{prelude::*, Node, Rule};
+
+/// Position and marker of a list element
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItemInfo {
+    /// Position of the element in its list, starting from 0
+    pub index: usize,
+    /// Number of lists the element's list is nested in, 0 for a top-level list
+    pub depth: usize,
+    /// Marker as written in the source (`-`, `*`, `+`, `1.`, `2)`)
+    pub marker: String,
+    pub ordered: bool,
+    /// State of the checkbox of a task list item (`- [x] Done`), `None` for the other elements
+    pub checked: Option<bool>,
+}
+
+impl ListItemInfo {
+    pub(crate) fn new(index: usize, depth: usize, source: &str, ordered: bool) -> ListItemInfo {
+        let marker = source.split(' ').next().unwrap_or_default().to_string();
+        ListItemInfo {
+            index,
+            depth,
+            marker,
+            ordered,
+            checked: None,
+        }
+    }
+
+    // NOTE    The checkbox is the first child of the element, or the one after its number
+    pub(crate) fn with_checkbox(mut self, element: &Node) -> ListItemInfo {
+        self.checked = element
+            .children()
+            .iter()
+            .take(2)
+            .find(|child| child.rule() == Rule::task_checkbox)
+            .map(|checkbox| checkbox.as_str() != "[ ]");
+        self
+    }
+}
@@ -0,0 +1,96 @@
+use alloc::sync::Arc;
+
+use crate::{prelude::*, AlertKind, CounterKind};
+
+/// Text generated by the framework or a renderer, rather than written in the document
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Phrase {
+    /// Name of a kind of numbered element, followed by its number ("Figure" 3)
+    Counter(CounterKind),
+    /// Title of an alert ("Note", "Warning")
+    Alert(AlertKind),
+    /// Title of the footnotes section
+    Footnotes,
+    /// Name of a footnote, followed by its number ("Note" 2)
+    Footnote,
+    /// Announce of the start of a quote, in speech
+    QuoteStart,
+    /// Announce of the end of a quote, in speech
+    QuoteEnd,
+    /// Name of an image, followed by its alternative text
+    Image,
+    /// Name of a link whose URL isn't spelled out
+    Link,
+    /// Announce of a code block left out
+    CodeOmitted,
+    /// Announce of a code block left out, `{}` standing for its language
+    CodeOmittedIn,
+}
+
+impl Phrase {
+    /// Text of the phrase in English, used when it isn't localized
+    pub fn english(&self) -> &'static str {
+        match self {
+            Phrase::Counter(kind) => kind.label(),
+            Phrase::Alert(AlertKind::Note) => "Note",
+            Phrase::Alert(AlertKind::Tip) => "Tip",
+            Phrase::Alert(AlertKind::Important) => "Important",
+            Phrase::Alert(AlertKind::Warning) => "Warning",
+            Phrase::Alert(AlertKind::Caution) => "Caution",
+            Phrase::Footnotes => "Notes",
+            Phrase::Footnote => "Note",
+            Phrase::QuoteStart => "Quote",
+            Phrase::QuoteEnd => "End of quote",
+            Phrase::Image => "Image",
+            Phrase::Link => "link",
+            Phrase::CodeOmitted => "Code sample omitted",
+            Phrase::CodeOmittedIn => "Code sample in {}, omitted",
+        }
+    }
+}
+
+/// Translation of the phrases generated by the renderers, for the documents that are not
+/// written in English
+///
+/// ```
+/// # use mdtrans::{AlertKind, Localizer, Phrase};
+/// let french = Localizer::new(|phrase| match phrase {
+///     Phrase::Alert(AlertKind::Warning) => Some("Attention".to_string()),
+///     _ => None,
+/// });
+/// assert_eq!(french.text(Phrase::Alert(AlertKind::Warning)), "Attention");
+/// assert_eq!(french.text(Phrase::Alert(AlertKind::Tip)), "Tip");
+/// ```
+#[derive(Clone, Default)]
+pub struct Localizer {
+    translate: Option<Arc<dyn Fn(Phrase) -> Option<String> + Send + Sync>>,
+}
+
+impl core::fmt::Debug for Localizer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Localizer")
+            .field("translate", &self.translate.is_some())
+            .finish()
+    }
+}
+
+impl Localizer {
+    /// Localizer translating the phrases `translate` returns a text for, the other
+    /// ones being left in English
+    pub fn new<F>(translate: F) -> Localizer
+    where
+        F: Fn(Phrase) -> Option<String> + Send + Sync + 'static,
+    {
+        Localizer {
+            translate: Some(Arc::new(translate)),
+        }
+    }
+
+    pub fn text(&self, phrase: Phrase) -> String {
+        self.translate
+            .as_ref()
+            .and_then(|translate| translate(phrase))
+            .unwrap_or_else(|| phrase.english().to_string())
+    }
+}
@@ -0,0 +1,432 @@
+use crate::{prelude::*, Alignment, Document, Errcode, Node, Rule};
+
+// Value of a property of an mdast node
+enum Value {
+    Null,
+    Bool(bool),
+    Number(usize),
+    Str(String),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn optional(value: Option<String>) -> Value {
+        value.map(Value::Str).unwrap_or(Value::Null)
+    }
+
+    fn write(&self, out: &mut String) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Value::Number(value) => out.push_str(&value.to_string()),
+            Value::Str(value) => write_string(out, value),
+            Value::Array(values) => {
+                out.push('[');
+                for (n, value) in values.iter().enumerate() {
+                    if n > 0 {
+                        out.push(',');
+                    }
+                    value.write(out);
+                }
+                out.push(']');
+            }
+        }
+    }
+}
+
+pub(crate) fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct MdastNode {
+    kind: &'static str,
+    props: Vec<(&'static str, Value)>,
+    children: Option<Vec<MdastNode>>,
+    start: usize,
+    end: usize,
+}
+
+impl MdastNode {
+    // NOTE    Blocks end before the line break following them
+    fn leaf(kind: &'static str, node: &Node) -> MdastNode {
+        let span = node.span();
+        let text = node.as_str().trim_end_matches('\n');
+        MdastNode {
+            kind,
+            props: vec![],
+            children: None,
+            start: span.start,
+            end: if text.is_empty() {
+                span.end
+            } else {
+                span.start + text.len()
+            },
+        }
+    }
+
+    fn parent(kind: &'static str, node: &Node, children: Vec<MdastNode>) -> MdastNode {
+        MdastNode {
+            children: Some(children),
+            ..MdastNode::leaf(kind, node)
+        }
+    }
+
+    fn text(node: &Node, value: &str) -> MdastNode {
+        MdastNode::leaf("text", node).with("value", Value::Str(value.to_string()))
+    }
+
+    // Text that is not part of a single node of the document
+    fn text_between(start: usize, end: usize, value: &str) -> MdastNode {
+        MdastNode {
+            kind: "text",
+            props: vec![("value", Value::Str(value.to_string()))],
+            children: None,
+            start,
+            end,
+        }
+    }
+
+    fn with(mut self, key: &'static str, value: Value) -> MdastNode {
+        self.props.push((key, value));
+        self
+    }
+}
+
+// Line and column of the offsets of the source
+struct LineIndex<'s> {
+    source: &'s str,
+    starts: Vec<usize>,
+}
+
+impl<'s> LineIndex<'s> {
+    fn new(source: &'s str) -> LineIndex<'s> {
+        let starts = core::iter::once(0)
+            .chain(source.match_indices('\n').map(|(n, _)| n + 1))
+            .collect();
+        LineIndex { source, starts }
+    }
+
+    fn write_point(&self, out: &mut String, offset: usize) {
+        let line = self.starts.partition_point(|start| *start <= offset);
+        let column = self.source[self.starts[line - 1]..offset].chars().count() + 1;
+        out.push_str(&format!(
+            "{{\"line\":{line},\"column\":{column},\"offset\":{offset}}}"
+        ));
+    }
+
+    fn write(&self, out: &mut String, node: &MdastNode) {
+        out.push_str("{\"type\":");
+        write_string(out, node.kind);
+        for (key, value) in node.props.iter() {
+            out.push_str(&format!(",\"{key}\":"));
+            value.write(out);
+        }
+        if let Some(children) = node.children.as_ref() {
+            out.push_str(",\"children\":[");
+            for (n, child) in children.iter().enumerate() {
+                if n > 0 {
+                    out.push(',');
+                }
+                self.write(out, child);
+            }
+            out.push(']');
+        }
+        out.push_str(",\"position\":{\"start\":");
+        self.write_point(out, node.start);
+        out.push_str(",\"end\":");
+        self.write_point(out, node.end);
+        out.push_str("}}");
+    }
+}
+
+// Phrasing content of the nodes
+fn inline(nodes: &[Node]) -> Vec<MdastNode> {
+    merge_texts(nodes.iter().flat_map(convert))
+}
+
+fn merge_texts<I: IntoIterator<Item = MdastNode>>(nodes: I) -> Vec<MdastNode> {
+    let mut children: Vec<MdastNode> = vec![];
+    for child in nodes {
+        let Some(last) = children.last_mut().filter(|last| last.kind == "text") else {
+            children.push(child);
+            continue;
+        };
+        match (&mut last.props[0].1, child.kind, child.props.first()) {
+            (Value::Str(text), "text", Some((_, Value::Str(value)))) => {
+                text.push_str(value);
+                last.end = child.end;
+            }
+            _ => children.push(child),
+        }
+    }
+    children
+}
+
+fn paragraph(node: &Node, children: Vec<MdastNode>) -> MdastNode {
+    MdastNode::parent("paragraph", node, children)
+}
+
+// Quote lines are joined in a single paragraph, after the marker of alerts
+fn quote_paragraph(node: &Node, marker: Option<&Node>, lines: &[&Node]) -> MdastNode {
+    let mut children = vec![];
+    let mut previous_end = None;
+    if let Some(kind) = marker {
+        let value = format!("[!{}]", kind.as_str());
+        let start = kind.span().start - 2;
+        children.push(MdastNode::text_between(start, start + value.len(), &value));
+        previous_end = Some(start + value.len());
+    }
+    for line in lines {
+        if let Some(end) = previous_end {
+            children.push(MdastNode::text_between(end, line.span().start, "\n"));
+        }
+        children.extend(inline(line.children()));
+        previous_end = Some(line.span().end);
+    }
+    paragraph(node, merge_texts(children))
+}
+
+fn convert(node: &Node) -> Vec<MdastNode> {
+    let children = node.children();
+    let converted = match node.rule() {
+        Rule::h1 | Rule::h2 | Rule::h3 | Rule::h4 | Rule::h5 | Rule::h6 => {
+            let depth = node.as_str().chars().take_while(|c| *c == '#').count();
+            let text = children.first().map(|t| t.children()).unwrap_or_default();
+            MdastNode::parent("heading", node, inline(text)).with("depth", Value::Number(depth))
+        }
+        // NOTE    Reference definitions are parsed as inline elements, they are blocks in mdast
+        #[cfg(feature = "reflinks")]
+        Rule::paragraph
+            if children
+                .iter()
+                .all(|c| matches!(c.rule(), Rule::refurl | Rule::paragraph_newline)) =>
+        {
+            let definitions = children.iter().filter(|c| c.rule() == Rule::refurl);
+            return definitions.flat_map(convert).collect();
+        }
+        Rule::paragraph => paragraph(node, inline(children)),
+        Rule::invalid => paragraph(node, vec![MdastNode::text(node, node.as_str())]),
+        Rule::text
+        | Rule::NO_INLINE_TEXT
+        | Rule::link_text
+        | Rule::shortcode
+        | Rule::crossref
+        | Rule::citation => MdastNode::text(node, node.as_str()),
+        Rule::paragraph_newline => MdastNode::text(node, "\n"),
+        Rule::vertical_space => MdastNode::leaf("break", node),
+        Rule::bold => MdastNode::parent("strong", node, inline(children)),
+        Rule::italic => MdastNode::parent("emphasis", node, inline(children)),
+        Rule::strike => MdastNode::parent("delete", node, inline(children)),
+        Rule::inline_code => {
+            let code = children.first().map(|c| c.as_str()).unwrap_or_default();
+            MdastNode::leaf("inlineCode", node).with("value", Value::Str(code.to_string()))
+        }
+        Rule::link => {
+            let (url, text) = children.split_last().expect("Link without url");
+            MdastNode::parent("link", node, inline(text))
+                .with("url", Value::Str(url.as_str().to_string()))
+                .with("title", Value::Null)
+        }
+        Rule::autolink => {
+            let text = node.as_str();
+            let url = if text.starts_with("www.") {
+                format!("http://{text}")
+            } else {
+                text.to_string()
+            };
+            MdastNode::parent("link", node, vec![MdastNode::text(node, text)])
+                .with("url", Value::Str(url))
+                .with("title", Value::Null)
+        }
+        #[cfg(feature = "reflinks")]
+        Rule::reflink => {
+            let (slug, text) = children.split_last().expect("Reference link without slug");
+            MdastNode::parent("linkReference", node, inline(text))
+                .with("identifier", Value::Str(slug.as_str().to_lowercase()))
+                .with("label", Value::Str(slug.as_str().to_string()))
+                .with("referenceType", Value::Str("full".to_string()))
+        }
+        #[cfg(feature = "reflinks")]
+        Rule::refurl => {
+            let slug = children[0].as_str();
+            let title = children.get(2).map(|t| t.as_str().to_string());
+            MdastNode::leaf("definition", node)
+                .with("identifier", Value::Str(slug.to_lowercase()))
+                .with("label", Value::Str(slug.to_string()))
+                .with("url", Value::Str(children[1].as_str().to_string()))
+                .with("title", Value::optional(title))
+        }
+        Rule::image => {
+            let alt = children
+                .iter()
+                .take_while(|c| c.rule() != Rule::url)
+                .map(|c| c.as_str())
+                .collect::<String>();
+            let url = children.iter().find(|c| c.rule() == Rule::url);
+            MdastNode::leaf("image", node)
+                .with("url", Value::optional(url.map(|u| u.as_str().to_string())))
+                .with("title", Value::Null)
+                .with("alt", Value::Str(alt))
+        }
+        Rule::footnote_ref => {
+            let label = children[0].as_str();
+            MdastNode::leaf("footnoteReference", node)
+                .with("identifier", Value::Str(label.to_lowercase()))
+                .with("label", Value::Str(label.to_string()))
+        }
+        Rule::footnote_def => {
+            let label = children[0].as_str();
+            let text = &children[1];
+            let content = paragraph(text, inline(text.children()));
+            MdastNode::parent("footnoteDefinition", node, vec![content])
+                .with("identifier", Value::Str(label.to_lowercase()))
+                .with("label", Value::Str(label.to_string()))
+        }
+        // NOTE    GitHub alerts are regular quotes for remark, starting with their `[!KIND]`
+        Rule::quote | Rule::alert => {
+            let lines = children
+                .iter()
+                .filter(|c| c.rule() == Rule::quote_line)
+                .collect::<Vec<&Node>>();
+            let marker = children.first().filter(|c| c.rule() == Rule::alert_kind);
+            let content = quote_paragraph(node, marker, &lines);
+            MdastNode::parent("blockquote", node, vec![content])
+        }
+        Rule::codeblock => {
+            let lang = children
+                .first()
+                .filter(|c| c.rule() == Rule::slug)
+                .map(|c| c.as_str().to_string());
+            let flags = children
+                .iter()
+                .filter(|c| c.rule() == Rule::codeblock_flag)
+                .map(|c| c.as_str())
+                .collect::<Vec<&str>>();
+            let code = children
+                .iter()
+                .filter(|c| c.rule() == Rule::codeblock_code)
+                .map(|c| c.as_str())
+                .collect::<Vec<&str>>()
+                .join("\n");
+            let meta = (!flags.is_empty()).then(|| flags.join(" "));
+            MdastNode::leaf("code", node)
+                .with("lang", Value::optional(lang))
+                .with("meta", Value::optional(meta))
+                .with("value", Value::Str(code))
+        }
+        Rule::comment => {
+            MdastNode::leaf("html", node).with("value", Value::Str(node.as_str().to_string()))
+        }
+        Rule::html_block => {
+            let html = node.as_str().trim_end().to_string();
+            MdastNode::leaf("html", node).with("value", Value::Str(html))
+        }
+        Rule::horiz_sep => MdastNode::leaf("thematicBreak", node),
+        Rule::list | Rule::ordered_list => {
+            let ordered = node.rule() == Rule::ordered_list;
+            let start = children
+                .first()
+                .and_then(|el| el.children().first())
+                .filter(|_| ordered)
+                .and_then(|nb| nb.as_str().parse().ok())
+                .map(Value::Number)
+                .unwrap_or(Value::Null);
+            let items = children.iter().flat_map(convert).collect();
+            MdastNode::parent("list", node, items)
+                .with("ordered", Value::Bool(ordered))
+                .with("start", start)
+                .with("spread", Value::Bool(false))
+        }
+        Rule::list_element | Rule::ordered_list_element => {
+            let (text, blocks): (Vec<Node>, Vec<Node>) = children
+                .iter()
+                .filter(|c| !matches!(c.rule(), Rule::list_number | Rule::task_checkbox))
+                .cloned()
+                .partition(|c| c.rule() != Rule::list_element_blocks);
+            let mut content = vec![];
+            if let Some(first) = text.first() {
+                content.push(MdastNode {
+                    end: text
+                        .last()
+                        .map(|t| t.span().end)
+                        .unwrap_or(first.span().end),
+                    ..paragraph(first, inline(&text))
+                });
+            }
+            content.extend(
+                blocks
+                    .iter()
+                    .flat_map(|b| b.children().iter().flat_map(convert)),
+            );
+            let checked = children
+                .iter()
+                .find(|c| c.rule() == Rule::task_checkbox)
+                .map_or(Value::Null, |c| Value::Bool(c.as_str() != "[ ]"));
+            MdastNode::parent("listItem", node, content)
+                .with("spread", Value::Bool(false))
+                .with("checked", checked)
+        }
+        Rule::table => {
+            let align = children[1]
+                .children()
+                .iter()
+                .map(|delim| match Alignment::from_delimiter(delim.as_str()) {
+                    Alignment::None => Value::Null,
+                    Alignment::Left => Value::Str("left".to_string()),
+                    Alignment::Center => Value::Str("center".to_string()),
+                    Alignment::Right => Value::Str("right".to_string()),
+                })
+                .collect();
+            let rows = children
+                .iter()
+                .filter(|row| row.rule() == Rule::table_row)
+                .map(|row| {
+                    let cells = row
+                        .children()
+                        .iter()
+                        .map(|cell| match cell.children().first() {
+                            Some(content) => {
+                                MdastNode::parent("tableCell", cell, inline(content.children()))
+                            }
+                            None => {
+                                let text = MdastNode::text(cell, cell.as_str().trim());
+                                MdastNode::parent("tableCell", cell, vec![text])
+                            }
+                        })
+                        .collect();
+                    MdastNode::parent("tableRow", row, cells)
+                })
+                .collect();
+            MdastNode::parent("table", node, rows).with("align", Value::Array(align))
+        }
+        Rule::footnotes_marker | Rule::bibliography_marker | Rule::EOI => return vec![],
+        _ => return children.iter().flat_map(convert).collect(),
+    };
+    vec![converted]
+}
+
+/// Syntax tree of a document in the [mdast](https://github.com/syntax-tree/mdast) format,
+/// serialized as JSON, for the tools of the unified / remark ecosystem
+pub fn mdast_json(input: &str) -> Result<String, Errcode> {
+    let document = Document::parse(input)?;
+    let root = document.root();
+    let blocks = root.children().iter().flat_map(convert).collect();
+    let tree = MdastNode {
+        end: root.span().end,
+        ..MdastNode::parent("root", root, blocks)
+    };
+    let mut out = String::with_capacity(input.len() * 4);
+    LineIndex::new(document.source()).write(&mut out, &tree);
+    Ok(out)
+}
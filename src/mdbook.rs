@@ -0,0 +1,135 @@
+use std::io::{Read, Write};
+
+use serde_json::Value;
+
+use crate::{prelude::*, Document, Errcode, MarkdownTransformer, TransformOptions, Warning};
+
+/// Adapter running a transformer on the chapters of a book as an mdBook preprocessor,
+/// speaking its JSON protocol over stdin / stdout
+///
+/// The markdown of each chapter is replaced by the output of the transformer, for the
+/// renderers of the book to process it:
+///
+/// ```no_run
+/// # use mdtrans::{MarkdownTransformer, MdbookPreprocessor};
+/// struct Shout;
+/// impl MarkdownTransformer for Shout {
+///     fn transform_text(&mut self, text: String) -> String {
+///         text.to_uppercase()
+///     }
+/// }
+///
+/// let mut preprocessor = MdbookPreprocessor::new(Shout).renderer("html");
+/// if let Err(err) = preprocessor.run() {
+///     eprintln!("{err}");
+///     std::process::exit(1);
+/// }
+/// ```
+pub struct MdbookPreprocessor<T: MarkdownTransformer> {
+    transformer: T,
+    options: TransformOptions,
+    renderers: HashSet<String>,
+}
+
+impl<T: MarkdownTransformer> MdbookPreprocessor<T> {
+    pub fn new(transformer: T) -> MdbookPreprocessor<T> {
+        MdbookPreprocessor {
+            transformer,
+            options: TransformOptions::default(),
+            renderers: HashSet::new(),
+        }
+    }
+
+    /// Options used to transform each chapter
+    pub fn options(mut self, options: TransformOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Register a renderer the output of the transformer is meant for, once any is
+    /// registered, mdBook doesn't run the preprocessor for the other ones
+    pub fn renderer<S: ToString>(mut self, renderer: S) -> Self {
+        self.renderers.insert(renderer.to_string());
+        self
+    }
+
+    pub fn supports(&self, renderer: &str) -> bool {
+        self.renderers.is_empty() || self.renderers.contains(renderer)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.transformer
+    }
+
+    /// Entry point of the preprocessor binary: answers `supports <renderer>` with the exit
+    /// code of the process, or transforms the book read from stdin to stdout, printing the
+    /// warnings on stderr
+    pub fn run(&mut self) -> Result<(), Errcode> {
+        let mut args = std::env::args().skip(1);
+        if args.next().as_deref() == Some("supports") {
+            let renderer = args.next().unwrap_or_default();
+            std::process::exit(if self.supports(&renderer) { 0 } else { 1 });
+        }
+        let warnings = self.preprocess(std::io::stdin().lock(), std::io::stdout().lock())?;
+        for (chapter, warning) in warnings {
+            eprintln!("{chapter}:{warning}");
+        }
+        Ok(())
+    }
+
+    /// Read the `[context, book]` sent by mdBook, write back the book with its chapters
+    /// transformed, returning the warnings raised along with the path of their chapter
+    pub fn preprocess<R: Read, W: Write>(
+        &mut self,
+        input: R,
+        mut output: W,
+    ) -> Result<Vec<(String, Warning)>, Errcode> {
+        let input: Value = serde_json::from_reader(input)?;
+        let Some(Value::Object(mut book)) = input.get(1).cloned() else {
+            return Err(Errcode::ParsingError(
+                "mdBook input is not a [context, book] array".to_string(),
+            ));
+        };
+        let mut warnings = vec![];
+        // NOTE    The sections of the book were renamed items in mdBook 0.5
+        for key in ["sections", "items"] {
+            if let Some(Value::Array(items)) = book.get_mut(key) {
+                self.transform_items(items, &mut warnings)?;
+            }
+        }
+        serde_json::to_writer(&mut output, &book)?;
+        output.flush()?;
+        Ok(warnings)
+    }
+
+    // Chapters and their sub-chapters, the separators and part titles are left untouched
+    fn transform_items(
+        &mut self,
+        items: &mut [Value],
+        warnings: &mut Vec<(String, Warning)>,
+    ) -> Result<(), Errcode> {
+        for chapter in items.iter_mut().filter_map(|item| item.get_mut("Chapter")) {
+            let path = chapter
+                .get("path")
+                .and_then(Value::as_str)
+                .or_else(|| chapter.get("name").and_then(Value::as_str))
+                .unwrap_or_default()
+                .to_string();
+            if let Some(Value::String(content)) = chapter.get_mut("content") {
+                let (transformed, chapter_warnings) =
+                    Document::parse_with_mode(content, self.options.parse_mode)?
+                        .transform_with_warnings(&mut self.transformer, &self.options);
+                *content = transformed;
+                warnings.extend(
+                    chapter_warnings
+                        .into_iter()
+                        .map(|warning| (path.clone(), warning)),
+                );
+            }
+            if let Some(Value::Array(sub_items)) = chapter.get_mut("sub_items") {
+                self.transform_items(sub_items, warnings)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{prelude::*, DiagramKind, Node, Rule, TransformOptions};
+
+/// Kinds of elements numbered by the framework
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CounterKind {
+    /// Images with a `caption` tag, and diagrams
+    Figure,
+    /// Tables, including the ones written as ```` ```csvtable ```` code blocks
+    Table,
+    /// Code blocks
+    Listing,
+}
+
+impl CounterKind {
+    /// English name of the kind, to label the elements ("Figure 3")
+    pub fn label(&self) -> &'static str {
+        match self {
+            CounterKind::Figure => "Figure",
+            CounterKind::Table => "Table",
+            CounterKind::Listing => "Listing",
+        }
+    }
+}
+
+/// When the counter of a kind of element starts again from 1
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CounterReset {
+    /// Elements are numbered through the whole document
+    #[default]
+    Never,
+    /// At every header of this level or a lower one (`1` numbers the elements per chapter)
+    Header(usize),
+}
+
+/// Number given by the framework to a figure, table or listing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementNumber {
+    pub kind: CounterKind,
+    /// Position of the element among the ones of its kind, starting from 1
+    pub number: usize,
+    /// Number of headers resetting the counter found before the element,
+    /// `None` if the counter is never reset
+    pub section: Option<usize>,
+}
+
+/// Written as `3`, or `2.3` in the second section of a counter reset by headers
+impl core::fmt::Display for ElementNumber {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.section {
+            Some(section) => write!(f, "{section}.{}", self.number),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Counter {
+    section: usize,
+    number: usize,
+}
+
+// Numbers of the elements of the document, by ordinal
+//
+// NOTE    Computed from the tree before the passes, so that the numbers don't depend on
+//         the order the hooks are called in (ex: footnote definitions)
+#[derive(Default)]
+pub(crate) struct Numbering {
+    numbers: HashMap<usize, ElementNumber>,
+    counters: HashMap<CounterKind, Counter>,
+}
+
+impl Numbering {
+    pub(crate) fn new(root: &Node, options: &TransformOptions) -> Numbering {
+        let mut numbering = Numbering::default();
+        for kind in [
+            CounterKind::Figure,
+            CounterKind::Table,
+            CounterKind::Listing,
+        ] {
+            numbering.counters.insert(kind, Counter::default());
+        }
+        numbering.visit(root, options);
+        numbering
+    }
+
+    pub(crate) fn get(&self, node: &Node) -> Option<ElementNumber> {
+        self.numbers.get(&node.ordinal()).copied()
+    }
+
+    fn visit(&mut self, node: &Node, options: &TransformOptions) {
+        if let Some(level) = header_level(node.rule()) {
+            let level = options.remap_heading(level);
+            for (kind, counter) in self.counters.iter_mut() {
+                if let CounterReset::Header(reset) = options.counter_reset(*kind) {
+                    if level <= reset {
+                        counter.section += 1;
+                        counter.number = 0;
+                    }
+                }
+            }
+        }
+        if let Some(kind) = counted_kind(node) {
+            let counter = self.counters.get_mut(&kind).unwrap();
+            counter.number += 1;
+            let section = match options.counter_reset(kind) {
+                CounterReset::Never => None,
+                CounterReset::Header(_) => Some(counter.section),
+            };
+            let number = ElementNumber {
+                kind,
+                number: counter.number,
+                section,
+            };
+            self.numbers.insert(node.ordinal(), number);
+        }
+        for child in node.children() {
+            self.visit(child, options);
+        }
+    }
+}
+
+fn header_level(rule: Rule) -> Option<usize> {
+    match rule {
+        Rule::h1 => Some(1),
+        Rule::h2 => Some(2),
+        Rule::h3 => Some(3),
+        Rule::h4 => Some(4),
+        Rule::h5 => Some(5),
+        Rule::h6 => Some(6),
+        _ => None,
+    }
+}
+
+fn counted_kind(node: &Node) -> Option<CounterKind> {
+    match node.rule() {
+        Rule::table => Some(CounterKind::Table),
+        Rule::image if has_caption(node) => Some(CounterKind::Figure),
+        Rule::codeblock => {
+            let lang = node
+                .children()
+                .first()
+                .filter(|child| child.rule() == Rule::slug)
+                .map(|slug| slug.as_str());
+            match lang {
+                Some("csvtable" | "tsvtable") => Some(CounterKind::Table),
+                Some(lang) if DiagramKind::from_language(lang).is_some() => {
+                    Some(CounterKind::Figure)
+                }
+                _ => Some(CounterKind::Listing),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "image-tags")]
+fn has_caption(image: &Node) -> bool {
+    image
+        .children()
+        .iter()
+        .filter(|child| child.rule() == Rule::image_tags)
+        .flat_map(|tags| tags.children())
+        .any(|tag| tag.children().first().map(|key| key.as_str()) == Some("caption"))
+}
+
+#[cfg(not(feature = "image-tags"))]
+fn has_caption(_image: &Node) -> bool {
+    false
+}
@@ -0,0 +1,536 @@
+use alloc::sync::Arc;
+
+use crate::{
+    prelude::*, CounterKind, CounterReset, ElementKind, ImageCandidate, ImageTagSchema, Rule,
+    SlugCharset, SlugDedup,
+};
+
+/// Custom rendering of a grammar rule, receiving the raw text of the element
+/// and the transformed text of each of its inner elements
+pub type RuleRenderer = Arc<dyn Fn(&str, Vec<String>) -> String + Send + Sync>;
+
+/// Loader of the files included in code blocks, receiving the path given with `file=`
+/// and returning the content of the file, or the reason it can't be read
+pub type IncludeResolver = Arc<dyn Fn(&str) -> Result<String, String> + Send + Sync>;
+
+/// Provider of the variants of an image (sizes, formats), receiving its URL and returning
+/// the candidates to pick from, none for the images it doesn't handle
+pub type ImageResolver = Arc<dyn Fn(&str) -> Vec<ImageCandidate> + Send + Sync>;
+
+// Aliases of the languages of code blocks commonly used, see `TransformOptions::common_language_aliases`
+const COMMON_LANGUAGE_ALIASES: [(&str, &str); 16] = [
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("sh", "bash"),
+    ("shell", "bash"),
+    ("zsh", "bash"),
+    ("py", "python"),
+    ("py3", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("yml", "yaml"),
+    ("md", "markdown"),
+    ("kt", "kotlin"),
+    ("cs", "csharp"),
+    ("c++", "cpp"),
+];
+
+/// How the parser handles markdown it can't make sense of
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail with an `Errcode::SyntaxError`
+    #[default]
+    Strict,
+    /// Keep the blocks that can't be parsed as `Rule::invalid` nodes, passed to
+    /// `transform_invalid`, reporting a `Warning::InvalidSyntax` for each
+    Lenient,
+}
+
+/// How the framework handles headers, list elements and links without any content
+/// (`# `, `- `, `[](url)`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmptyElements {
+    /// Call the hooks of the element with an empty text
+    #[default]
+    Dispatch,
+    /// Leave the element out of the output, without calling its hooks nor reporting
+    /// the links without text
+    ///
+    /// A list whose elements are all empty is left out along with them.
+    Skip,
+    /// Call the hooks of the element, reporting a `Warning::EmptyElement` for it
+    ///
+    /// Links without text are reported as `Warning::EmptyLinkText` instead.
+    Warn,
+}
+
+/// Line endings of the output of a transformation, set by `TransformOptions::line_ending`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Keep the line endings written by the transformer
+    #[default]
+    Keep,
+    /// `\n`, the `\r\n` written by the transformer being replaced
+    Lf,
+    /// `\r\n`, as expected by the Windows tools
+    Crlf,
+}
+
+/// How the framework handles the variables `{{ name }}` without a value, once
+/// `TransformOptions::variables` are given
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownVariables {
+    /// Keep the variable as written, reporting a `Warning::UnknownVariable` for it
+    #[default]
+    Keep,
+    /// Leave the variable out of the output, reporting a `Warning::UnknownVariable` for it
+    Remove,
+}
+
+/// Options altering how the framework walks the parsed document before handing
+/// elements to a `MarkdownTransformer`
+#[derive(Clone)]
+pub struct TransformOptions {
+    pub(crate) heading_offset: isize,
+    pub(crate) max_heading_level: usize,
+    pub(crate) site_host: Option<String>,
+    pub(crate) shortcodes: HashSet<String>,
+    pub(crate) renderers: HashMap<Rule, RuleRenderer>,
+    pub(crate) element_renderers: HashMap<ElementKind, RuleRenderer>,
+    pub(crate) share_batch_state: bool,
+    pub(crate) known_languages: HashSet<String>,
+    pub(crate) language_aliases: HashMap<String, String>,
+    pub(crate) parse_mode: ParseMode,
+    pub(crate) empty_elements: EmptyElements,
+    pub(crate) lossy_utf8: bool,
+    pub(crate) counter_resets: HashMap<CounterKind, CounterReset>,
+    pub(crate) crossrefs: bool,
+    pub(crate) citations: bool,
+    pub(crate) badges: bool,
+    pub(crate) text_direction: bool,
+    pub(crate) directives: bool,
+    pub(crate) strip_comments: bool,
+    pub(crate) line_ending: LineEnding,
+    pub(crate) final_newline: bool,
+    pub(crate) code_themes: bool,
+    pub(crate) image_tag_schema: Option<ImageTagSchema>,
+    pub(crate) slug_charset: SlugCharset,
+    pub(crate) slug_dedup: SlugDedup,
+    pub(crate) badge_hosts: HashSet<String>,
+    pub(crate) include_resolver: Option<IncludeResolver>,
+    pub(crate) image_resolver: Option<ImageResolver>,
+    pub(crate) variables: Option<HashMap<String, String>>,
+    pub(crate) unknown_variables: UnknownVariables,
+}
+
+impl core::fmt::Debug for TransformOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TransformOptions")
+            .field("heading_offset", &self.heading_offset)
+            .field("max_heading_level", &self.max_heading_level)
+            .field("site_host", &self.site_host)
+            .field("shortcodes", &self.shortcodes)
+            .field("renderers", &self.renderers.keys().collect::<Vec<&Rule>>())
+            .field(
+                "element_renderers",
+                &self.element_renderers.keys().collect::<Vec<&ElementKind>>(),
+            )
+            .field("share_batch_state", &self.share_batch_state)
+            .field("known_languages", &self.known_languages)
+            .field("language_aliases", &self.language_aliases)
+            .field("parse_mode", &self.parse_mode)
+            .field("empty_elements", &self.empty_elements)
+            .field("lossy_utf8", &self.lossy_utf8)
+            .field("counter_resets", &self.counter_resets)
+            .field("crossrefs", &self.crossrefs)
+            .field("citations", &self.citations)
+            .field("badges", &self.badges)
+            .field("badge_hosts", &self.badge_hosts)
+            .field("text_direction", &self.text_direction)
+            .field("directives", &self.directives)
+            .field("strip_comments", &self.strip_comments)
+            .field("line_ending", &self.line_ending)
+            .field("final_newline", &self.final_newline)
+            .field("code_themes", &self.code_themes)
+            .field("image_tag_schema", &self.image_tag_schema)
+            .field("slug_charset", &self.slug_charset)
+            .field("slug_dedup", &self.slug_dedup)
+            .field("include_resolver", &self.include_resolver.is_some())
+            .field("image_resolver", &self.image_resolver.is_some())
+            .field("variables", &self.variables)
+            .field("unknown_variables", &self.unknown_variables)
+            .finish()
+    }
+}
+
+impl Default for TransformOptions {
+    fn default() -> Self {
+        TransformOptions {
+            heading_offset: 0,
+            max_heading_level: 6,
+            site_host: None,
+            shortcodes: HashSet::new(),
+            renderers: HashMap::new(),
+            element_renderers: HashMap::new(),
+            share_batch_state: false,
+            known_languages: HashSet::new(),
+            language_aliases: HashMap::new(),
+            parse_mode: ParseMode::default(),
+            empty_elements: EmptyElements::default(),
+            lossy_utf8: false,
+            counter_resets: HashMap::new(),
+            crossrefs: false,
+            citations: false,
+            badges: false,
+            badge_hosts: HashSet::new(),
+            text_direction: false,
+            directives: false,
+            strip_comments: false,
+            line_ending: LineEnding::default(),
+            final_newline: false,
+            code_themes: false,
+            image_tag_schema: None,
+            slug_charset: SlugCharset::default(),
+            slug_dedup: SlugDedup::default(),
+            include_resolver: None,
+            image_resolver: None,
+            variables: None,
+            unknown_variables: UnknownVariables::default(),
+        }
+    }
+}
+
+impl TransformOptions {
+    /// Shift every heading level by `offset` (ex: `1` turns `#` into `##`)
+    pub fn heading_offset(mut self, offset: isize) -> Self {
+        self.heading_offset = offset;
+        self
+    }
+
+    /// Clamp heading levels so they never go deeper than `level`
+    pub fn max_heading_level(mut self, level: usize) -> Self {
+        self.max_heading_level = level.max(1);
+        self
+    }
+
+    /// Host of the site the document is published on, used to classify links
+    pub fn site_host<S: ToString>(mut self, host: S) -> Self {
+        self.site_host = Some(host.to_string());
+        self
+    }
+
+    /// Register a shortcode name, so `{{name args}}` is passed to `transform_shortcode`
+    ///
+    /// Shortcodes that are not registered are left as plain text
+    pub fn shortcode<S: ToString>(mut self, name: S) -> Self {
+        self.shortcodes.insert(name.to_string());
+        self
+    }
+
+    /// Override the handling of a grammar rule, bypassing the transformer hooks
+    ///
+    /// The inner elements are still walked, so their `peek_*` hooks are called.
+    /// The rules change with the grammar, `render_element` is the stable alternative.
+    pub fn render_rule<F>(mut self, rule: Rule, renderer: F) -> Self
+    where
+        F: Fn(&str, Vec<String>) -> String + Send + Sync + 'static,
+    {
+        self.renderers.insert(rule, Arc::new(renderer));
+        self
+    }
+
+    /// Override the handling of a kind of element, like `render_rule` does for a grammar rule
+    ///
+    /// A renderer registered with `render_rule` for the rule of an element takes precedence.
+    pub fn render_element<F>(mut self, kind: ElementKind, renderer: F) -> Self
+    where
+        F: Fn(&str, Vec<String>) -> String + Send + Sync + 'static,
+    {
+        self.element_renderers.insert(kind, Arc::new(renderer));
+        self
+    }
+
+    /// Let the documents of a batch see the state gathered from each other
+    /// (ex: reference links defined in another file)
+    ///
+    /// All the documents are peeked before the first one is transformed.
+    pub fn share_batch_state(mut self, share: bool) -> Self {
+        self.share_batch_state = share;
+        self
+    }
+
+    /// Register a code block language, once any is registered, code blocks using
+    /// another language produce a `Warning::UnknownLanguage`
+    pub fn known_language<S: ToString>(mut self, language: S) -> Self {
+        self.known_languages
+            .insert(language.to_string().to_lowercase());
+        self
+    }
+
+    /// Replace the language `alias` of the code blocks by `language` before giving it to
+    /// the transformer (ex: `js` by `javascript`), the alias being case insensitive
+    ///
+    /// The known languages and the diagram languages are matched after the replacement.
+    pub fn language_alias<S: ToString, L: ToString>(mut self, alias: S, language: L) -> Self {
+        self.language_aliases
+            .insert(alias.to_string().to_lowercase(), language.to_string());
+        self
+    }
+
+    /// Register the aliases commonly used for the languages of code blocks (`js`, `sh`,
+    /// `py`, `yml`, ...), keeping the ones registered before
+    pub fn common_language_aliases(mut self) -> Self {
+        for (alias, language) in COMMON_LANGUAGE_ALIASES {
+            self.language_aliases
+                .entry(alias.to_string())
+                .or_insert_with(|| language.to_string());
+        }
+        self
+    }
+
+    /// Choose whether invalid markdown makes the transformation fail or is kept as plain text
+    /// Replace the variables `{{ name }}` (`{{ page.title }}`) by their value, passed to
+    /// `transform_text` like the text around them
+    ///
+    /// Meant for the values of the front matter of the document, flattened with dots
+    /// (`page.title`). The shortcodes registered with the same name take precedence.
+    pub fn variables<I, K, V>(mut self, variables: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: ToString,
+        V: ToString,
+    {
+        let map = self.variables.get_or_insert_with(HashMap::new);
+        for (name, value) in variables {
+            map.insert(name.to_string(), value.to_string());
+        }
+        self
+    }
+
+    /// Choose how the variables without a value are handled
+    pub fn unknown_variables(mut self, policy: UnknownVariables) -> Self {
+        self.unknown_variables = policy;
+        self
+    }
+
+    pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Choose how the headers, list elements and links without any content are handled
+    pub fn empty_elements(mut self, policy: EmptyElements) -> Self {
+        self.empty_elements = policy;
+        self
+    }
+
+    /// Replace the invalid UTF-8 sequences of inputs read with `Pipeline::run_io`
+    /// by `U+FFFD`, instead of failing with `Errcode::InvalidUtf8`
+    pub fn lossy_utf8(mut self, lossy: bool) -> Self {
+        self.lossy_utf8 = lossy;
+        self
+    }
+
+    /// Choose when the numbering of figures, tables or listings starts again from 1,
+    /// they are numbered through the whole document by default
+    pub fn reset_counter(mut self, kind: CounterKind, reset: CounterReset) -> Self {
+        self.counter_resets.insert(kind, reset);
+        self
+    }
+
+    /// Resolve the cross-references `[#slug]` to the headings and figures of the document,
+    /// passed to `transform_crossref`
+    ///
+    /// They are left as plain text when disabled, which they are by default.
+    pub fn crossrefs(mut self, enable: bool) -> Self {
+        self.crossrefs = enable;
+        self
+    }
+
+    /// Pass the citations `[@key]` to `transform_citation` and the keys cited to
+    /// `transform_bibliography`, at the `[BIBLIOGRAPHY]` marker or at the end of the document
+    ///
+    /// The citations and the marker are left as plain text when disabled, which they are
+    /// by default.
+    pub fn citations(mut self, enable: bool) -> Self {
+        self.citations = enable;
+        self
+    }
+
+    /// Follow the `Directive`s written as `<!-- mdtrans: name -->` comments, given to
+    /// `transform_directive` instead of `transform_comment`, to leave blocks out of the
+    /// output (`skip-start` / `skip-end`) or pass their source as is (`raw`)
+    pub fn directives(mut self, enable: bool) -> Self {
+        self.directives = enable;
+        self
+    }
+
+    /// Leave the comments `<!-- text -->` out of the output, `transform_comment` is not called
+    /// but `peek_comment` still is
+    ///
+    /// The directives followed with `TransformOptions::directives` are still given to
+    /// `transform_directive`.
+    pub fn strip_comments(mut self, enable: bool) -> Self {
+        self.strip_comments = enable;
+        self
+    }
+
+    /// Line endings of the whole output, applied once the document is transformed
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// End the output with a line ending if it doesn't already, unless it is empty
+    pub fn final_newline(mut self, enable: bool) -> Self {
+        self.final_newline = enable;
+        self
+    }
+
+    /// Recognize the links whose only content is a badge image (`[![CI](badge.svg)](url)`),
+    /// passed to `transform_badge`
+    ///
+    /// Badges are the images served by shields.io, badgen.net or badge.fury.io, the
+    /// `badge.svg` images (GitHub Actions, docs.rs, codecov), and the images of the hosts
+    /// registered with `badge_host`. They are left as an image in a link when disabled,
+    /// which they are by default.
+    pub fn badges(mut self, enable: bool) -> Self {
+        self.badges = enable;
+        self
+    }
+
+    /// Register a host serving badge images, recognized once `badges` is enabled
+    pub fn badge_host<S: ToString>(mut self, host: S) -> Self {
+        self.badge_hosts.insert(host.to_string().to_lowercase());
+        self
+    }
+
+    /// Detect the direction of each paragraph from its first letter, calling the
+    /// `*_paragraph_with_info` hooks with it instead of the `*_paragraph` ones, for the
+    /// renderers to mark the right-to-left paragraphs (`dir="rtl"` in HTML)
+    pub fn text_direction(mut self, detect: bool) -> Self {
+        self.text_direction = detect;
+        self
+    }
+
+    /// Call the `*_codeblock_with_info` hooks instead of the `*_codeblock` ones, with the color
+    /// themes to render the code block in for the sites switching between a light and a
+    /// dark theme, both of them unless the block has a `theme=light` or `theme=dark` flag
+    pub fn code_themes(mut self, enable: bool) -> Self {
+        self.code_themes = enable;
+        self
+    }
+
+    /// Check the tags of the images against `schema`, reporting a `Warning::InvalidImageTag`
+    /// for each tag it doesn't allow, like a misspelled `widht`
+    pub fn image_tag_schema(mut self, schema: ImageTagSchema) -> Self {
+        self.image_tag_schema = Some(schema);
+        self
+    }
+
+    /// Characters kept in the anchors generated for the headings (outline, cross-references
+    /// and link checks), to match the ones of the site generator publishing the documents
+    pub fn slug_charset(mut self, charset: SlugCharset) -> Self {
+        self.slug_charset = charset;
+        self
+    }
+
+    /// How the anchors of headings with the same text are told apart
+    ///
+    /// The cross-references point to the first heading of an anchor with `SlugDedup::Error`.
+    pub fn slug_dedup(mut self, dedup: SlugDedup) -> Self {
+        self.slug_dedup = dedup;
+        self
+    }
+
+    /// Replace the code of the blocks including a file (```` ```rust file=src/main.rs lines=10-20 ````)
+    /// by the content the resolver returns for it, or only the lines of the range if given
+    ///
+    /// An `anchor=name` flag only includes the region of the file between the `ANCHOR: name`
+    /// and `ANCHOR_END: name` markers (see `extract_anchor`), the line range then counting
+    /// from the start of the region.
+    ///
+    /// Each file is loaded once per transformation. A file that can't be loaded produces a
+    /// `Warning::IncludeFailed` and the code written in the block is kept. The `file=`,
+    /// `anchor=` and `lines=` flags are not passed to the transformer.
+    pub fn include_resolver<F>(mut self, resolver: F) -> Self
+    where
+        F: Fn(&str) -> Result<String, String> + Send + Sync + 'static,
+    {
+        self.include_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Include the files in code blocks from `dir`, see `include_resolver`
+    ///
+    /// Absolute paths and paths going out of `dir` with `..` are refused.
+    #[cfg(feature = "std")]
+    pub fn include_dir<P: Into<std::path::PathBuf>>(self, dir: P) -> Self {
+        use std::path::{Component, Path};
+
+        let dir = dir.into();
+        self.include_resolver(move |path| {
+            let path = Path::new(path);
+            let inside = path
+                .components()
+                .all(|part| matches!(part, Component::Normal(_) | Component::CurDir));
+            if !inside {
+                return Err("path outside of the include directory".to_string());
+            }
+            std::fs::read_to_string(dir.join(path)).map_err(|err| err.to_string())
+        })
+    }
+
+    /// Resolve the variants of each image, given with them to the `*_responsive_image` hooks
+    /// instead of the `*_image` ones when the resolver returns any
+    ///
+    /// The resolver is called once per URL and transformation, the badges are not resolved.
+    pub fn image_resolver<F>(mut self, resolver: F) -> Self
+    where
+        F: Fn(&str) -> Vec<ImageCandidate> + Send + Sync + 'static,
+    {
+        self.image_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    pub(crate) fn renderer(&self, rule: Rule) -> Option<&RuleRenderer> {
+        self.renderers
+            .get(&rule)
+            .or_else(|| self.element_renderers.get(&ElementKind::from(rule)))
+    }
+
+    pub(crate) fn counter_reset(&self, kind: CounterKind) -> CounterReset {
+        self.counter_resets.get(&kind).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn normalize_language(&self, language: String) -> String {
+        match self.language_aliases.get(&language.to_lowercase()) {
+            Some(canonical) => canonical.clone(),
+            None => language,
+        }
+    }
+
+    pub(crate) fn is_unknown_language(&self, language: &str) -> bool {
+        !self.known_languages.is_empty() && !self.known_languages.contains(&language.to_lowercase())
+    }
+
+    // NOTE    Applied to the output of the whole document, the transformer being done with it
+    pub(crate) fn finish_output(&self, mut output: String) -> String {
+        if self.final_newline && !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        match self.line_ending {
+            LineEnding::Keep => output,
+            LineEnding::Lf if !output.contains('\r') => output,
+            LineEnding::Lf => output.replace("\r\n", "\n"),
+            LineEnding::Crlf => output.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+
+    pub(crate) fn remap_heading(&self, level: usize) -> usize {
+        let level = (level as isize + self.heading_offset).max(1) as usize;
+        level.min(self.max_heading_level)
+    }
+}
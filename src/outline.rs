@@ -0,0 +1,334 @@
+use core::ops::{Range, RangeInclusive};
+
+use crate::{
+    anchors::AnchorGenerator, context::CurrentSpan, prelude::*, transform::TransformFramework,
+    Document, ElementContext, ElementKind, Errcode, ListItemInfo, MarkdownTransformer, Node,
+    Rewriter, Span, TransformOptions,
+};
+
+/// Heading of a document, as listed in its outline
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heading {
+    pub level: usize,
+    /// Text of the heading, without its formatting
+    pub text: String,
+    /// Anchor of the heading, unique in the document
+    pub slug: String,
+    pub span: Span,
+}
+
+struct HeadingCollector {
+    span: CurrentSpan,
+    generator: AnchorGenerator,
+    headings: Vec<Heading>,
+    duplicate: Option<(String, Span)>,
+}
+
+impl MarkdownTransformer for HeadingCollector {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.span.set(ctx);
+    }
+
+    fn peek_header(&mut self, level: usize, text: String) {
+        let span = self.span.get();
+        match self.generator.anchor(&text) {
+            Ok(slug) => self.headings.push(Heading {
+                level,
+                slug,
+                text,
+                span,
+            }),
+            Err(slug) => {
+                self.duplicate.get_or_insert((slug, span));
+            }
+        }
+    }
+}
+
+/// Headings of a document in order, to build a navigation menu or a table of contents
+///
+/// Only the peek pass is performed, nothing is rendered.
+pub fn outline(input: &str) -> Result<Vec<Heading>, Errcode> {
+    outline_with_options(input, &TransformOptions::default())
+}
+
+/// Same as `outline`, the anchors of the headings being generated as set by
+/// `TransformOptions::slug_charset` and `TransformOptions::slug_dedup`
+pub fn outline_with_options(
+    input: &str,
+    options: &TransformOptions,
+) -> Result<Vec<Heading>, Errcode> {
+    let document = Document::parse_with_mode(input, options.parse_mode)?;
+    let mut collector = HeadingCollector {
+        span: CurrentSpan::default(),
+        generator: AnchorGenerator::new(options),
+        headings: vec![],
+        duplicate: None,
+    };
+    TransformFramework::new(&mut collector, options).peek_document(document.root());
+    match collector.duplicate {
+        Some((slug, span)) => Err(Errcode::DuplicateSlug { slug, span }),
+        None => Ok(collector.headings),
+    }
+}
+
+/// Markdown of the section starting at the heading whose text or anchor is `heading`, up to
+/// the next heading of the same or a higher level, `None` if there is no such heading
+///
+/// ```
+/// # use mdtrans::extract_section;
+/// let input = "# Changelog\n\n## 1.2.0\n\n- Fix\n\n### Notes\n\nText\n\n## 1.1.0\n\n- Add";
+/// let section = extract_section(input, "1.2.0").unwrap();
+/// assert_eq!(section.as_deref(), Some("## 1.2.0\n\n- Fix\n\n### Notes\n\nText"));
+/// assert_eq!(extract_section(input, "notes").unwrap().as_deref(), Some("### Notes\n\nText"));
+/// assert_eq!(extract_section(input, "2.0.0").unwrap(), None);
+/// ```
+pub fn extract_section(input: &str, heading: &str) -> Result<Option<String>, Errcode> {
+    extract_section_with_options(input, heading, &TransformOptions::default())
+}
+
+/// Same as `extract_section`, the anchors of the headings being generated as set by
+/// `TransformOptions::slug_charset` and `TransformOptions::slug_dedup`
+pub fn extract_section_with_options(
+    input: &str,
+    heading: &str,
+    options: &TransformOptions,
+) -> Result<Option<String>, Errcode> {
+    let headings = outline_with_options(input, options)?;
+    let Some(index) = headings
+        .iter()
+        .position(|h| h.text == heading || h.slug == heading)
+    else {
+        return Ok(None);
+    };
+    let level = headings[index].level;
+    let start = headings[index].span.start;
+    let end = headings[index + 1..]
+        .iter()
+        .find(|h| h.level <= level)
+        .map_or(input.len(), |h| h.span.start);
+    Ok(Some(input[start..end].trim_end().to_string()))
+}
+
+/// Task list items of a section, checked or not, to render the progress of a TODO file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProgress {
+    /// Heading starting the section, `None` for the items before the first heading
+    pub heading: Option<Heading>,
+    pub checked: usize,
+    pub unchecked: usize,
+}
+
+impl TaskProgress {
+    pub fn total(&self) -> usize {
+        self.checked + self.unchecked
+    }
+
+    /// Part of the items checked, from 0 to 1
+    pub fn ratio(&self) -> f32 {
+        self.checked as f32 / self.total().max(1) as f32
+    }
+}
+
+#[derive(Default)]
+struct TaskCollector {
+    span: CurrentSpan,
+    tasks: Vec<(Span, bool)>,
+}
+
+impl MarkdownTransformer for TaskCollector {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.span.set(ctx);
+    }
+
+    fn peek_list_element(&mut self, _element: String, info: ListItemInfo) {
+        let span = self.span.get();
+        if let Some(checked) = info.checked {
+            self.tasks.push((span, checked));
+        }
+    }
+}
+
+/// Number of task list items checked and unchecked in each section of a document, a section
+/// going from a heading to the next one whatever its level
+///
+/// Only the sections containing task list items are listed, in order. The task lists are
+/// parsed from the dialect 2 of the grammar (`GRAMMAR_VERSION`).
+///
+/// ```
+/// # use mdtrans::task_progress;
+/// # if mdtrans::GRAMMAR_VERSION < 2 { return; }
+/// let input = "# Release\n\n- [x] Tests\n- [ ] Changelog\n\n## Docs\n\n- [X] README\n\nDone.";
+/// let progress = task_progress(input).unwrap();
+/// assert_eq!(progress[0].heading.as_ref().unwrap().text, "Release");
+/// assert_eq!((progress[0].checked, progress[0].unchecked), (1, 1));
+/// assert_eq!(progress[1].ratio(), 1.0);
+/// ```
+pub fn task_progress(input: &str) -> Result<Vec<TaskProgress>, Errcode> {
+    task_progress_with_options(input, &TransformOptions::default())
+}
+
+/// Same as `task_progress`, the anchors of the headings being generated as set by
+/// `TransformOptions::slug_charset` and `TransformOptions::slug_dedup`
+pub fn task_progress_with_options(
+    input: &str,
+    options: &TransformOptions,
+) -> Result<Vec<TaskProgress>, Errcode> {
+    let headings = outline_with_options(input, options)?;
+    let document = Document::parse_with_mode(input, options.parse_mode)?;
+    let mut collector = TaskCollector::default();
+    TransformFramework::new(&mut collector, options).peek_document(document.root());
+
+    let mut progress: Vec<TaskProgress> = vec![];
+    for (span, checked) in collector.tasks {
+        let heading = headings.iter().rfind(|h| h.span.start <= span.start);
+        if progress
+            .last()
+            .is_none_or(|p| p.heading.as_ref() != heading)
+        {
+            progress.push(TaskProgress {
+                heading: heading.cloned(),
+                checked: 0,
+                unchecked: 0,
+            });
+        }
+        // NOTE    Safe to unwrap, a section was pushed above if there was none
+        let section = progress.last_mut().unwrap();
+        match checked {
+            true => section.checked += 1,
+            false => section.unchecked += 1,
+        }
+    }
+    Ok(progress)
+}
+
+/// Promotion or demotion of the headings of a document and removal of their manual numbering,
+/// editing their markers in the source and keeping the rest of it as it was written
+///
+/// Unlike `TransformOptions::heading_offset`, applied when transforming a document, it
+/// rewrites the markdown itself.
+///
+/// ```
+/// # use mdtrans::HeadingRewrite;
+/// let input = concat!("# Guide\n\n", "## 1. Install\n\n", "### 1.1) Linux\n\n", "## 2. Usage\n");
+/// let output = HeadingRewrite::default()
+///     .shift(1)
+///     .levels(2..=6)
+///     .strip_numbers(true)
+///     .rewrite(input)
+///     .unwrap();
+/// assert_eq!(output, concat!("# Guide\n\n", "### Install\n\n", "#### Linux\n\n", "### Usage\n"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeadingRewrite {
+    shift: isize,
+    levels: RangeInclusive<usize>,
+    within: Option<Range<usize>>,
+    strip_numbers: bool,
+}
+
+impl Default for HeadingRewrite {
+    fn default() -> Self {
+        HeadingRewrite {
+            shift: 0,
+            levels: 1..=6,
+            within: None,
+            strip_numbers: false,
+        }
+    }
+}
+
+impl HeadingRewrite {
+    /// Shift the heading levels by `offset`, a negative one promoting them, the levels
+    /// being kept between 1 and 6
+    pub fn shift(mut self, offset: isize) -> Self {
+        self.shift = offset;
+        self
+    }
+
+    /// Only rewrite the headings of these levels
+    pub fn levels(mut self, levels: RangeInclusive<usize>) -> Self {
+        self.levels = levels;
+        self
+    }
+
+    /// Only rewrite the headings starting within these bytes of the document, like the
+    /// range of a section found with `outline`
+    pub fn within(mut self, range: Range<usize>) -> Self {
+        self.within = Some(range);
+        self
+    }
+
+    /// Remove the numbers written at the start of the headings (`1.2.3`, `1.2.` or `1)`),
+    /// to number them from `ElementContext::number` instead
+    pub fn strip_numbers(mut self, strip: bool) -> Self {
+        self.strip_numbers = strip;
+        self
+    }
+
+    pub fn rewrite(&self, input: &str) -> Result<String, Errcode> {
+        let document = Document::parse(input)?;
+        let mut headings = vec![];
+        collect_headings(document.root(), &mut headings);
+        let mut rewriter = Rewriter::new(input);
+        for heading in headings {
+            let ElementKind::Header(level) = heading.kind() else {
+                continue;
+            };
+            let span = heading.span();
+            let within = self.within.as_ref().is_none_or(|r| r.contains(&span.start));
+            if !within || !self.levels.contains(&level) {
+                continue;
+            }
+            let new_level = (level as isize + self.shift).clamp(1, 6) as usize;
+            if new_level != level {
+                let marker = Span {
+                    end: span.start + level,
+                    ..span
+                };
+                rewriter.replace(marker, "#".repeat(new_level));
+            }
+            let Some(text) = heading.children().first().filter(|_| self.strip_numbers) else {
+                continue;
+            };
+            let len = number_prefix(text.as_str());
+            if len > 0 {
+                let text_span = text.span();
+                let number = Span {
+                    end: text_span.start + len,
+                    ..text_span
+                };
+                rewriter.delete(number);
+            }
+        }
+        rewriter.apply()
+    }
+}
+
+fn collect_headings<'n>(node: &'n Node<'n>, headings: &mut Vec<&'n Node<'n>>) {
+    if let ElementKind::Header(_) = node.kind() {
+        return headings.push(node);
+    }
+    for child in node.children() {
+        collect_headings(child, headings);
+    }
+}
+
+// NOTE    Length of the numbering at the start of a heading text with the spaces after
+//         it, `0` if there is none. A number alone (`2024 review`) is not a numbering.
+fn number_prefix(text: &str) -> usize {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ')'))
+        .unwrap_or(text.len());
+    let (number, rest) = text.split_at(end);
+    let closed = number.find(')').is_none_or(|i| i == number.len() - 1);
+    let valid = number.starts_with(|c: char| c.is_ascii_digit())
+        && number.contains(['.', ')'])
+        && !number.contains("..")
+        && closed
+        && rest.starts_with(' ');
+    match valid {
+        true => text.len() - rest.trim_start().len(),
+        false => 0,
+    }
+}
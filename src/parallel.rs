@@ -0,0 +1,120 @@
+use alloc::sync::Arc;
+use rayon::prelude::*;
+
+use crate::{
+    context::IdGenerator, document::Tree, footnotes::FootnoteTable, transform::TransformFramework,
+    Errcode, MarkdownTransformer, Node, ParseState, Rule, TransformOptions,
+};
+
+// A top-level block of the document, with what is needed to transform it on its own
+struct Block<'i> {
+    node: &'i Node<'i>,
+    ids: IdGenerator,
+    state: ParseState,
+}
+
+/// Same as `transform_markdown_string_with_options`, but transforms the top-level blocks
+/// of the document in parallel, for CPU-heavy transformers
+///
+/// The peek pass is performed sequentially on `transformer`, which is then cloned for
+/// each block, so any state modified during the transform pass is not shared between blocks.
+/// The output keeps the order of the document.
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run_parallel` instead")]
+pub fn transform_markdown_string_parallel<T>(
+    input: String,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<String, Errcode>
+where
+    T: MarkdownTransformer + Clone + Send,
+{
+    transform_parallel(&input, transformer, options)
+}
+
+pub(crate) fn transform_parallel<T>(
+    input: &str,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<String, Errcode>
+where
+    T: MarkdownTransformer + Clone + Send,
+{
+    let tree = Tree::parse(input, options.parse_mode)?;
+    let mut parser = TransformFramework::new(transformer, options);
+    let blocks = peek_blocks(&mut parser, tree.root());
+    parser.transformer.finished(true);
+
+    let numbers = parser.footnotes.numbers().clone();
+    let jobs = blocks
+        .into_iter()
+        .map(|block| (block, parser.transformer.clone(), numbers.clone()))
+        .collect::<Vec<_>>();
+    let (numbering, crossrefs) = (&parser.numbering, &parser.crossrefs);
+    #[cfg(feature = "reflinks")]
+    let references = &parser.references;
+    let results = jobs
+        .into_par_iter()
+        .map(|(mut block, mut transformer, numbers)| {
+            match block.node.rule() {
+                Rule::footnotes_marker => return Err(block.node),
+                Rule::bibliography_marker if options.citations => return Err(block.node),
+                _ => {}
+            }
+            let mut worker = TransformFramework::new(&mut transformer, options);
+            worker.footnotes = FootnoteTable::with_numbers(numbers);
+            worker.ids = block.ids;
+            worker.numbering = Arc::clone(numbering);
+            worker.crossrefs = Arc::clone(crossrefs);
+            #[cfg(feature = "reflinks")]
+            {
+                worker.references = references.clone();
+            }
+            Ok(worker.act_on_pair(&mut block.state, block.node))
+        })
+        .collect::<Vec<Result<String, &Node>>>();
+
+    let mut res = String::new();
+    for result in results {
+        match result {
+            Ok(text) => res += text.as_str(),
+            // NOTE    Footnotes definitions and cited keys are only known by the main transformer
+            Err(node) if node.rule() == Rule::footnotes_marker => {
+                res += parser
+                    .footnotes_marker(&ParseState::default(), node.span())
+                    .as_str()
+            }
+            Err(node) => {
+                res += parser
+                    .bibliography_marker(&ParseState::default(), node.span())
+                    .as_str()
+            }
+        }
+    }
+    res += parser.finish_document().as_str();
+    res += parser.transformer.finished(false).as_str();
+    Ok(options.finish_output(res))
+}
+
+// Peek pass over the whole document, remembering the state of the IDs before each block
+fn peek_blocks<'i, T: MarkdownTransformer>(
+    parser: &mut TransformFramework<'_, 'i, T>,
+    file: &'i Node<'i>,
+) -> Vec<Block<'i>> {
+    parser.ids.reset();
+    parser.ids.next(Rule::file, file.as_str());
+    parser.index_document(file);
+    let mut state = ParseState::peek();
+    let mut blocks = vec![];
+    for child in file.children() {
+        if child.rule() == Rule::EOI {
+            continue;
+        }
+        blocks.push(Block {
+            node: child,
+            ids: parser.ids.clone(),
+            state: ParseState::transform_from(&state),
+        });
+        parser.act_on_pair(&mut state, child);
+    }
+    blocks
+}
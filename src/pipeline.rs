@@ -0,0 +1,198 @@
+use crate::{
+    batch::transform_batch, document::Tree, prelude::*, transform::transform_input, Document,
+    Errcode, MarkdownTransformer, StatefulTransformer, TransformOptions, Warning,
+};
+
+/// Syntax extensions enabled at runtime, see `Pipeline::extension`
+///
+/// The reference links, the image tags, the collapsible sections, the tab groups and the
+/// table spans are compiled in or out with their features instead. The tables are part of
+/// the grammar and always parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Extension {
+    /// Cross-references `[#slug]`, see `TransformOptions::crossrefs`
+    Crossrefs,
+    /// Citations `[@key]` and bibliography, see `TransformOptions::citations`
+    Citations,
+    /// Shortcode `{{name args}}` of this name, see `TransformOptions::shortcode`
+    Shortcode(String),
+    /// Directive comments `<!-- mdtrans: name -->`, see `TransformOptions::directives`
+    Directives,
+}
+
+impl Extension {
+    fn enable(&self, options: TransformOptions) -> TransformOptions {
+        match self {
+            Extension::Crossrefs => options.crossrefs(true),
+            Extension::Citations => options.citations(true),
+            Extension::Shortcode(name) => options.shortcode(name),
+            Extension::Directives => options.directives(true),
+        }
+    }
+}
+
+/// Transformation of markdown documents, built from its options, the syntax extensions
+/// enabled and the transformer rendering the documents
+///
+/// ```
+/// # use mdtrans::{Extension, MarkdownTransformer, Pipeline, TransformOptions};
+/// struct Shout;
+/// impl MarkdownTransformer for Shout {
+///     fn transform_text(&mut self, text: String) -> String {
+///         text.to_uppercase()
+///     }
+/// }
+///
+/// let output = Pipeline::new()
+///     .options(TransformOptions::default().heading_offset(1))
+///     .extension(Extension::Crossrefs)
+///     .transformer(Shout)
+///     .run("Hello")
+///     .unwrap();
+/// assert_eq!(output, "HELLO");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline<T> {
+    options: TransformOptions,
+    extensions: Vec<Extension>,
+    cache: Option<HashMap<String, String>>,
+    transformer: T,
+}
+
+impl Pipeline<()> {
+    /// Pipeline with the default options, still missing its transformer
+    pub fn new() -> Pipeline<()> {
+        Pipeline::default()
+    }
+}
+
+impl<T> Pipeline<T> {
+    /// Options of the transformation, replacing the ones given before, the extensions
+    /// enabled being kept
+    pub fn options(mut self, options: TransformOptions) -> Self {
+        self.options = self
+            .extensions
+            .iter()
+            .fold(options, |options, extension| extension.enable(options));
+        self
+    }
+
+    pub fn extension(mut self, extension: Extension) -> Self {
+        self.options = extension.enable(self.options);
+        self.extensions.push(extension);
+        self
+    }
+
+    /// Keep the output of each input given to `run`, an identical input being given the
+    /// same output without being transformed again
+    ///
+    /// Only for the transformers whose output depends on the document alone, the hooks
+    /// are not called for the cached inputs.
+    pub fn cache(mut self, enable: bool) -> Self {
+        self.cache = enable.then(HashMap::new);
+        self
+    }
+
+    /// Transformer rendering the documents, replacing the one given before
+    pub fn transformer<U: MarkdownTransformer>(self, transformer: U) -> Pipeline<U> {
+        Pipeline {
+            options: self.options,
+            extensions: self.extensions,
+            cache: self.cache,
+            transformer,
+        }
+    }
+
+    pub fn transformer_mut(&mut self) -> &mut T {
+        &mut self.transformer
+    }
+
+    pub fn into_transformer(self) -> T {
+        self.transformer
+    }
+}
+
+impl<T: MarkdownTransformer> Pipeline<T> {
+    /// Wrap the transformer in another one adding a behavior to it, like a `TraceTransformer`
+    pub fn decorate<U, F>(self, decorator: F) -> Pipeline<U>
+    where
+        U: MarkdownTransformer,
+        F: FnOnce(T) -> U,
+    {
+        Pipeline {
+            options: self.options,
+            extensions: self.extensions,
+            cache: self.cache,
+            transformer: decorator(self.transformer),
+        }
+    }
+
+    pub fn run(&mut self, input: &str) -> Result<String, Errcode> {
+        if let Some(output) = self.cache.as_ref().and_then(|cache| cache.get(input)) {
+            return Ok(output.clone());
+        }
+        let output = transform_input(input, &mut self.transformer, &self.options)?;
+        if let Some(cache) = self.cache.as_mut() {
+            cache.insert(input.to_string(), output.clone());
+        }
+        Ok(output)
+    }
+
+    /// Same as `run`, also returning the content issues found in the document
+    pub fn run_with_warnings(&mut self, input: &str) -> Result<(String, Vec<Warning>), Errcode> {
+        Ok(Tree::parse(input, self.options.parse_mode)?
+            .transform_with_warnings(&mut self.transformer, &self.options))
+    }
+
+    /// Transform a document parsed beforehand, to render it several times without parsing it again
+    pub fn run_document(&mut self, document: &Document) -> String {
+        document.transform_with_options(&mut self.transformer, &self.options)
+    }
+
+    /// Transform the markdown read from `input` into `output`, returning the number of bytes written
+    ///
+    /// A leading byte order mark is skipped, see `TransformOptions::lossy_utf8` for the
+    /// inputs that are not valid UTF-8.
+    #[cfg(feature = "std")]
+    pub fn run_io<R, W>(&mut self, input: &mut R, output: &mut W) -> Result<usize, Errcode>
+    where
+        R: std::io::Read,
+        W: std::io::Write,
+    {
+        crate::transform::transform_io(input, output, &mut self.transformer, &self.options)
+    }
+}
+
+impl<T: StatefulTransformer> Pipeline<T> {
+    /// Same as `run`, but the state of the transformer is restored once the document is
+    /// transformed, so nothing gathered from it leaks into the next one
+    pub fn run_isolated(&mut self, input: &str) -> Result<String, Errcode> {
+        Ok(Tree::parse(input, self.options.parse_mode)?
+            .transform_isolated(&mut self.transformer, &self.options))
+    }
+
+    /// Transform several documents, returning the outputs in order
+    ///
+    /// Each document is isolated from the others unless `TransformOptions::share_batch_state`
+    /// is set, the transformer is restored to its original state once the batch is done.
+    pub fn run_batch<I>(&mut self, inputs: I) -> Result<Vec<String>, Errcode>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        transform_batch(inputs, &mut self.transformer, &self.options)
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<T: MarkdownTransformer + Clone + Send> Pipeline<T> {
+    /// Same as `run`, but transforms the top-level blocks of the document in parallel,
+    /// for CPU-heavy transformers
+    ///
+    /// The peek pass is performed sequentially, the transformer is then cloned for each
+    /// block, so any state modified during the transform pass is not shared between blocks.
+    pub fn run_parallel(&mut self, input: &str) -> Result<String, Errcode> {
+        crate::parallel::transform_parallel(input, &mut self.transformer, &self.options)
+    }
+}
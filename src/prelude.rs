@@ -0,0 +1,14 @@
+// Items of the std prelude that have to be imported from `alloc` in no_std builds
+
+pub use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+#[cfg(not(feature = "std"))]
+pub use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap, HashSet};
@@ -0,0 +1,63 @@
+use crate::prelude::*;
+
+/// Wrap a rendered markdown paragraph so its lines don't exceed `width` columns
+///
+/// Lines are only broken on spaces outside of inline code, links and URLs,
+/// so a single atom longer than `width` is left on its own line.
+/// Hard line breaks (two trailing spaces) are kept in place.
+pub fn reflow(text: &str, width: usize) -> String {
+    text.split("  \n")
+        .map(|segment| wrap_segment(segment, width))
+        .collect::<Vec<String>>()
+        .join("  \n")
+}
+
+fn wrap_segment(text: &str, width: usize) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut current = String::new();
+    for atom in atoms(text) {
+        if !current.is_empty() && current.chars().count() + 1 + atom.chars().count() > width {
+            lines.push(core::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current += atom.as_str();
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines.join("\n")
+}
+
+// Splits the text on whitespaces, except inside constructs that must not be broken
+fn atoms(text: &str) -> Vec<String> {
+    let mut atoms = vec![];
+    let mut current = String::new();
+    let mut in_code = false;
+    let mut brackets = 0usize;
+    let mut parens = 0usize;
+    let mut prev = None;
+    for c in text.chars() {
+        match c {
+            '`' => in_code = !in_code,
+            '[' if !in_code => brackets += 1,
+            ']' if !in_code => brackets = brackets.saturating_sub(1),
+            '(' if !in_code && (prev == Some(']') || parens > 0) => parens += 1,
+            ')' if !in_code => parens = parens.saturating_sub(1),
+            _ => {}
+        }
+        prev = Some(c);
+        if c.is_whitespace() && !in_code && brackets == 0 && parens == 0 {
+            if !current.is_empty() {
+                atoms.push(core::mem::take(&mut current));
+            }
+            continue;
+        }
+        current.push(if c.is_whitespace() { ' ' } else { c });
+    }
+    if !current.is_empty() {
+        atoms.push(current);
+    }
+    atoms
+}
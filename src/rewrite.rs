@@ -0,0 +1,195 @@
+use core::ops::Range;
+
+use crate::{prelude::*, Document, Errcode, Node, Rule, Span};
+
+/// Edits of a markdown source, applied to its bytes so that everything outside of the edited
+/// spans is kept exactly as it was written, instead of rendering the whole document again
+///
+/// The spans are the ones of the elements of the `Document` parsed from the same source. Its
+/// `\r\n` line endings being read as `\n`, the spans are mapped back to the source when the
+/// edits are applied.
+///
+/// ```
+/// # use mdtrans::{Document, ElementKind, Rewriter};
+/// let input = "# Intro\r\n\r\nSee *this*.\r\n";
+/// let document = Document::parse(input).unwrap();
+/// let root = document.root();
+/// let paragraph = root.children().iter().find(|n| n.kind() == ElementKind::Paragraph).unwrap();
+/// let italic = paragraph.children().iter().find(|n| n.kind() == ElementKind::Italic).unwrap();
+///
+/// let mut rewriter = Rewriter::new(input);
+/// rewriter.replace(italic.span(), "**that**");
+/// rewriter.insert(paragraph.span().end, " Done.");
+/// assert_eq!(rewriter.apply().unwrap(), "# Intro\r\n\r\nSee **that**. Done.\r\n");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rewriter<'i> {
+    source: &'i str,
+    // Offsets of the parsed input where a `\r` of the source was dropped
+    dropped: Vec<usize>,
+    edits: Vec<(Range<usize>, String)>,
+}
+
+impl<'i> Rewriter<'i> {
+    pub fn new(source: &'i str) -> Rewriter<'i> {
+        let mut dropped = vec![];
+        for (index, (offset, _)) in source.match_indices("\r\n").enumerate() {
+            dropped.push(offset - index);
+        }
+        Rewriter {
+            source,
+            dropped,
+            edits: vec![],
+        }
+    }
+
+    /// Replace the text of the span with `text`
+    pub fn replace<S: ToString>(&mut self, span: Span, text: S) {
+        self.edits.push((span.start..span.end, text.to_string()));
+    }
+
+    /// Insert `text` at the byte offset, after the other texts inserted at the same offset
+    pub fn insert<S: ToString>(&mut self, offset: usize, text: S) {
+        self.edits.push((offset..offset, text.to_string()));
+    }
+
+    pub fn delete(&mut self, span: Span) {
+        self.replace(span, "");
+    }
+
+    /// Whether no edit was made, applying them giving back the source
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Source with the edits applied, failing with `Errcode::InvalidEdit` if two of them
+    /// overlap or if one is out of the source
+    pub fn apply(&self) -> Result<String, Errcode> {
+        let mut edits = self
+            .edits
+            .iter()
+            .map(|(range, text)| {
+                (
+                    self.source_offset(range.start)..self.source_offset(range.end),
+                    text,
+                )
+            })
+            .collect::<Vec<_>>();
+        // NOTE    Stable sort, the texts inserted at the same offset stay in order, before
+        //         the replacement starting there
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+        let mut res = String::with_capacity(self.source.len());
+        let mut last = 0;
+        for (range, text) in edits {
+            let valid = last <= range.start
+                && range.start <= range.end
+                && self.source.is_char_boundary(range.start)
+                && self.source.is_char_boundary(range.end);
+            if !valid {
+                return Err(Errcode::InvalidEdit {
+                    start: range.start,
+                    end: range.end,
+                });
+            }
+            res += &self.source[last..range.start];
+            res += text.as_str();
+            last = range.end;
+        }
+        res += &self.source[last..];
+        Ok(res)
+    }
+
+    // Span of the parsed input on the source, its line and column being the same
+    fn source_span(&self, span: Span) -> Span {
+        Span {
+            start: self.source_offset(span.start),
+            end: self.source_offset(span.end),
+            ..span
+        }
+    }
+
+    // NOTE    An offset right before a `\n` of the parsed input stays before the `\r\n` of
+    //         the source, so replacing a line keeps its line ending
+    fn source_offset(&self, offset: usize) -> usize {
+        offset + self.dropped.partition_point(|dropped| *dropped < offset)
+    }
+}
+
+/// URL of a link replaced by `rewrite_links`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkChange {
+    pub old_url: String,
+    pub new_url: String,
+    /// Location of the URL in the source
+    pub span: Span,
+}
+
+/// Replace the URLs of the links, images, autolinks and reference definitions for which
+/// `rewrite` gives a new one, keeping the rest of the document as it was written, and list
+/// the URLs changed
+///
+/// ```
+/// # use mdtrans::rewrite_links;
+/// let input = "[Guide](old.md#setup), ![](img/old.png) and [old.md](old.md)";
+/// let (output, changes) = rewrite_links(input, |url| {
+///     url.strip_prefix("old.md").map(|anchor| format!("new.md{anchor}"))
+/// })
+/// .unwrap();
+/// assert_eq!(output, "[Guide](new.md#setup), ![](img/old.png) and [old.md](new.md)");
+/// assert_eq!(changes.len(), 2);
+/// assert_eq!((changes[1].new_url.as_str(), changes[1].span.column), ("new.md", 54));
+/// ```
+pub fn rewrite_links<F>(input: &str, rewrite: F) -> Result<(String, Vec<LinkChange>), Errcode>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let (rewriter, changes) = plan_link_changes(input, rewrite)?;
+    Ok((rewriter.apply()?, changes))
+}
+
+/// Dry run of `rewrite_links`, listing the URLs it would change without rewriting anything
+pub fn link_changes<F>(input: &str, rewrite: F) -> Result<Vec<LinkChange>, Errcode>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    Ok(plan_link_changes(input, rewrite)?.1)
+}
+
+fn plan_link_changes<F>(
+    input: &str,
+    mut rewrite: F,
+) -> Result<(Rewriter<'_>, Vec<LinkChange>), Errcode>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let document = Document::parse(input)?;
+    let mut rewriter = Rewriter::new(input);
+    let mut changes = vec![];
+    let mut urls = vec![];
+    collect_urls(document.root(), &mut urls);
+    for url in urls {
+        let old_url = url.as_str();
+        let Some(new_url) = rewrite(old_url).filter(|new_url| new_url != old_url) else {
+            continue;
+        };
+        rewriter.replace(url.span(), &new_url);
+        changes.push(LinkChange {
+            old_url: old_url.to_string(),
+            new_url,
+            span: rewriter.source_span(url.span()),
+        });
+    }
+    Ok((rewriter, changes))
+}
+
+fn collect_urls<'n>(node: &'n Node<'n>, urls: &mut Vec<&'n Node<'n>>) {
+    match node.rule() {
+        Rule::url | Rule::autolink => return urls.push(node),
+        #[cfg(feature = "reflinks")]
+        Rule::refurl_url => return urls.push(node),
+        _ => {}
+    }
+    for child in node.children() {
+        collect_urls(child, urls);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{prelude::*, ElementKind, GRAMMAR_VERSION};
+
+/// Construct of the markdown syntax understood by the crate, with an example of it and the
+/// hook of `MarkdownTransformer` it is given to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyntaxEntry {
+    pub name: &'static str,
+    pub kind: ElementKind,
+    /// Markdown of the construct
+    pub example: &'static str,
+    /// `transform_*` hook called for the construct, its `peek_*` one being called as well
+    pub hook: &'static str,
+    /// Cargo feature needed for the construct to be parsed
+    pub feature: Option<&'static str>,
+    /// Option of `TransformOptions` to enable for the construct to be recognized
+    pub option: Option<&'static str>,
+    /// Dialect of the grammar (`GRAMMAR_VERSION`) the construct was introduced in
+    pub since: u32,
+}
+
+impl SyntaxEntry {
+    const fn new(
+        name: &'static str,
+        kind: ElementKind,
+        example: &'static str,
+        hook: &'static str,
+    ) -> SyntaxEntry {
+        SyntaxEntry {
+            name,
+            kind,
+            example,
+            hook,
+            feature: None,
+            option: None,
+            since: 1,
+        }
+    }
+
+    const fn feature(mut self, feature: &'static str) -> Self {
+        self.feature = Some(feature);
+        self
+    }
+
+    const fn option(mut self, option: &'static str) -> Self {
+        self.option = Some(option);
+        self
+    }
+
+    const fn since(mut self, dialect: u32) -> Self {
+        self.since = dialect;
+        self
+    }
+}
+
+// NOTE    Every example is checked to reach its hook by the tests, keep them in sync with the
+//         grammar and the framework
+const SYNTAX: [SyntaxEntry; 38] = [
+    SyntaxEntry::new(
+        "Heading",
+        ElementKind::Header(1),
+        "# Title",
+        "transform_header",
+    ),
+    SyntaxEntry::new(
+        "Paragraph",
+        ElementKind::Paragraph,
+        "Some text",
+        "transform_paragraph",
+    ),
+    SyntaxEntry::new("Bold", ElementKind::Bold, "**bold**", "transform_bold"),
+    SyntaxEntry::new(
+        "Italic",
+        ElementKind::Italic,
+        "*italic*",
+        "transform_italic",
+    ),
+    SyntaxEntry::new(
+        "Strikethrough",
+        ElementKind::Strikethrough,
+        "~~struck~~",
+        "transform_strikethrough",
+    ),
+    SyntaxEntry::new(
+        "Inline code",
+        ElementKind::InlineCode,
+        "`code`",
+        "transform_inline_code",
+    ),
+    SyntaxEntry::new(
+        "Link",
+        ElementKind::Link,
+        "[text](https://example.com)",
+        "transform_link",
+    ),
+    SyntaxEntry::new(
+        "Autolink",
+        ElementKind::Autolink,
+        "https://example.com",
+        "transform_autolink",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Reference link",
+        ElementKind::ReferenceLink,
+        "[text][slug]\n\n[slug]: https://example.com",
+        "transform_reflink",
+    )
+    .feature("reflinks"),
+    SyntaxEntry::new(
+        "Reference definition",
+        ElementKind::ReferenceDefinition,
+        "[slug]: https://example.com \"Title\"",
+        "transform_refurl",
+    )
+    .feature("reflinks"),
+    SyntaxEntry::new(
+        "Image",
+        ElementKind::Image,
+        "![alt](image.png)",
+        "transform_image",
+    ),
+    SyntaxEntry::new(
+        "Image tags",
+        ElementKind::Image,
+        "![alt](image.png)[width: 200, credit: \"Bob\"]",
+        "transform_image",
+    )
+    .feature("image-tags"),
+    SyntaxEntry::new(
+        "Figure",
+        ElementKind::Image,
+        "![alt](image.png)[caption: \"A caption\"]",
+        "transform_figure",
+    )
+    .feature("image-tags"),
+    SyntaxEntry::new(
+        "Badge",
+        ElementKind::Link,
+        "[![CI](https://img.shields.io/ci.svg)](https://example.com)",
+        "transform_badge",
+    )
+    .option("badges"),
+    SyntaxEntry::new(
+        "Footnote reference",
+        ElementKind::FootnoteReference,
+        "Text[^1]\n\n[^1]: The note",
+        "transform_footnote_ref",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Footnote definition",
+        ElementKind::FootnoteDefinition,
+        "[^1]: The note",
+        "transform_footnotes_section",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Footnotes marker",
+        ElementKind::FootnotesMarker,
+        "Text[^1]\n\n[FOOTNOTES]\n\n[^1]: The note",
+        "transform_footnotes_section",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Cross-reference",
+        ElementKind::Crossref,
+        "# Intro\n\nSee [#intro]",
+        "transform_crossref",
+    )
+    .option("crossrefs")
+    .since(2),
+    SyntaxEntry::new(
+        "Citation",
+        ElementKind::Citation,
+        "[@key]",
+        "transform_citation",
+    )
+    .option("citations")
+    .since(2),
+    SyntaxEntry::new(
+        "Bibliography marker",
+        ElementKind::BibliographyMarker,
+        "[@key]\n\n[BIBLIOGRAPHY]",
+        "transform_bibliography",
+    )
+    .option("citations")
+    .since(2),
+    SyntaxEntry::new(
+        "Shortcode",
+        ElementKind::Shortcode,
+        "{{youtube dQw4w9WgXcQ}}",
+        "transform_shortcode",
+    )
+    .option("shortcode")
+    .since(2),
+    SyntaxEntry::new(
+        "Comment",
+        ElementKind::Comment,
+        "<!-- comment -->",
+        "transform_comment",
+    ),
+    SyntaxEntry::new(
+        "Directive",
+        ElementKind::Comment,
+        "<!-- mdtrans: skip-start -->",
+        "transform_directive",
+    )
+    .option("directives"),
+    SyntaxEntry::new(
+        "HTML block",
+        ElementKind::HtmlBlock,
+        "<div>\nHTML\n</div>",
+        "transform_html_block",
+    )
+    .since(2),
+    SyntaxEntry::new("Quote", ElementKind::Quote, "> Quoted", "transform_quote"),
+    SyntaxEntry::new(
+        "Alert",
+        ElementKind::Alert,
+        "> [!NOTE]\n> Text",
+        "transform_alert",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Collapsible section",
+        ElementKind::Details,
+        "??? Summary\n    Content",
+        "transform_details",
+    )
+    .feature("details"),
+    SyntaxEntry::new(
+        "Tab group",
+        ElementKind::TabGroup,
+        "=== \"Tab\"\n    Content",
+        "transform_tab_group",
+    )
+    .feature("tabs"),
+    SyntaxEntry::new(
+        "Code block",
+        ElementKind::CodeBlock,
+        "```rust\nlet a = 1;\n```",
+        "transform_codeblock",
+    ),
+    SyntaxEntry::new(
+        "Diagram",
+        ElementKind::CodeBlock,
+        "```mermaid\ngraph TD\n```",
+        "transform_diagram",
+    ),
+    SyntaxEntry::new("List", ElementKind::List, "- item", "transform_list"),
+    SyntaxEntry::new(
+        "Ordered list",
+        ElementKind::OrderedList,
+        "1. item",
+        "transform_ordered_list",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "List item",
+        ElementKind::ListItem,
+        "- item",
+        "transform_list_element",
+    ),
+    SyntaxEntry::new(
+        "Task list item",
+        ElementKind::ListItem,
+        "- [x] done",
+        "transform_list_element",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Table",
+        ElementKind::Table,
+        "| a | b |\n|---|:-:|\n| 1 | 2 |",
+        "transform_table",
+    )
+    .since(2),
+    SyntaxEntry::new(
+        "Table spans",
+        ElementKind::Table,
+        "| a ||\n|---|---|\n| 1 | \\\n| 2 | 3 |",
+        "transform_table_with_spans",
+    )
+    .feature("table-spans")
+    .since(2),
+    SyntaxEntry::new(
+        "Horizontal separator",
+        ElementKind::HorizontalSeparator,
+        "---",
+        "transform_horizontal_separator",
+    ),
+    SyntaxEntry::new(
+        "Variable",
+        ElementKind::Shortcode,
+        "{{ name }}",
+        "transform_text",
+    )
+    .option("variables")
+    .since(2),
+];
+
+/// Constructs of the syntax parsed with the features and the dialect the crate is built with,
+/// to list what a transformer has to handle
+///
+/// ```
+/// # use mdtrans::{syntax, ElementKind};
+/// let bold = syntax().into_iter().find(|entry| entry.kind == ElementKind::Bold).unwrap();
+/// assert_eq!((bold.example, bold.hook), ("**bold**", "transform_bold"));
+/// ```
+pub fn syntax() -> Vec<SyntaxEntry> {
+    SYNTAX
+        .into_iter()
+        .filter(|entry| entry.feature.is_none_or(|f| FEATURES.contains(&f)))
+        .filter(|entry| entry.since <= GRAMMAR_VERSION)
+        .collect()
+}
+
+// Features of the syntax the crate is built with
+const FEATURES: &[&str] = &[
+    #[cfg(feature = "reflinks")]
+    "reflinks",
+    #[cfg(feature = "image-tags")]
+    "image-tags",
+    #[cfg(feature = "details")]
+    "details",
+    #[cfg(feature = "tabs")]
+    "tabs",
+    #[cfg(feature = "table-spans")]
+    "table-spans",
+];
@@ -0,0 +1,211 @@
+use crate::prelude::*;
+
+/// Alignment of a table column, given by the delimiter row
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    pub(crate) fn from_delimiter(delim: &str) -> Alignment {
+        match (delim.starts_with(':'), delim.ends_with(':')) {
+            (true, true) => Alignment::Center,
+            (true, false) => Alignment::Left,
+            (false, true) => Alignment::Right,
+            (false, false) => Alignment::None,
+        }
+    }
+
+    fn delimiter(&self, width: usize) -> String {
+        match self {
+            Alignment::None => "-".repeat(width),
+            Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+            Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+            Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+        }
+    }
+
+    fn pad(&self, cell: &str, width: usize) -> String {
+        let missing = width - cell.chars().count();
+        match self {
+            Alignment::None | Alignment::Left => format!("{cell}{}", " ".repeat(missing)),
+            Alignment::Right => format!("{}{cell}", " ".repeat(missing)),
+            Alignment::Center => {
+                let left = missing / 2;
+                format!("{}{cell}{}", " ".repeat(left), " ".repeat(missing - left))
+            }
+        }
+    }
+}
+
+/// Cell of a table, given to the `*_table_with_spans` hooks (feature `table-spans`)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableCell {
+    /// Transformed content of the cell, its lines separated by `\n` when its row is
+    /// continued by the next ones
+    pub text: String,
+    /// Number of columns covered by the cell, 1 unless followed by empty cells `||`
+    pub colspan: usize,
+}
+
+impl TableCell {
+    pub(crate) fn new(text: String) -> TableCell {
+        TableCell { text, colspan: 1 }
+    }
+}
+
+// Cells of a row laid out on the grid, the spanned columns being left empty
+pub(crate) fn expand_cells(cells: Vec<TableCell>) -> Vec<String> {
+    let mut row = vec![];
+    for cell in cells {
+        row.push(cell.text);
+        row.resize(row.len() + cell.colspan - 1, String::new());
+    }
+    row
+}
+
+// Cells of a row covering exactly `width` columns, the missing ones being added empty
+#[cfg(feature = "table-spans")]
+pub(crate) fn fit_cells(mut cells: Vec<TableCell>, width: usize) -> Vec<TableCell> {
+    let mut used = 0;
+    cells.retain_mut(|cell| {
+        cell.colspan = cell.colspan.min(width - used);
+        used += cell.colspan;
+        cell.colspan > 0
+    });
+    cells.resize(cells.len() + width - used, TableCell::new(String::new()));
+    cells
+}
+
+// Whether the cells can be laid out on a grid without losing any information
+#[cfg(feature = "table-spans")]
+pub(crate) fn is_simple_grid(header: &[TableCell], rows: &[Vec<TableCell>]) -> bool {
+    header
+        .iter()
+        .chain(rows.iter().flatten())
+        .all(|cell| cell.colspan == 1 && !cell.text.contains('\n'))
+}
+
+/// Format a markdown table with aligned pipes and padded cells
+///
+/// The first row is the header, columns missing from a row are rendered empty.
+pub fn format_table(rows: &[Vec<String>], alignments: &[Alignment]) -> String {
+    let nb_cols = rows
+        .iter()
+        .map(|r| r.len())
+        .chain([alignments.len()])
+        .max()
+        .unwrap_or(0);
+    let widths = (0..nb_cols)
+        .map(|n| {
+            rows.iter()
+                .filter_map(|r| r.get(n))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect::<Vec<usize>>();
+    let align = |n: usize| alignments.get(n).copied().unwrap_or_default();
+
+    let format_row = |row: &Vec<String>| {
+        let cells = widths
+            .iter()
+            .enumerate()
+            .map(|(n, width)| align(n).pad(row.get(n).map(|c| c.as_str()).unwrap_or(""), *width))
+            .collect::<Vec<String>>();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![];
+    let mut rows = rows.iter();
+    if let Some(header) = rows.next() {
+        lines.push(format_row(header));
+        let delims = widths
+            .iter()
+            .enumerate()
+            .map(|(n, width)| align(n).delimiter(*width))
+            .collect::<Vec<String>>();
+        lines.push(format!("| {} |", delims.join(" | ")));
+    }
+    lines.extend(rows.map(format_row));
+    lines.join("\n")
+}
+
+// Markdown of a table whose cells span several columns or lines, without padding the cells
+pub(crate) fn format_spanned_table(
+    header: &[TableCell],
+    alignments: &[Alignment],
+    rows: &[Vec<TableCell>],
+) -> String {
+    let format_row = |row: &[TableCell]| {
+        let nb_lines = row
+            .iter()
+            .map(|c| c.text.lines().count())
+            .max()
+            .unwrap_or(0);
+        (0..nb_lines.max(1))
+            .map(|n| {
+                let mut line = "|".to_string();
+                for cell in row {
+                    let text = cell.text.lines().nth(n).unwrap_or_default();
+                    line += &format!(" {text} |{}", "|".repeat(cell.colspan - 1));
+                }
+                line
+            })
+            .collect::<Vec<String>>()
+            .join(" \\\n")
+    };
+    let delims = alignments
+        .iter()
+        .map(|align| align.delimiter(3))
+        .collect::<Vec<String>>();
+    let mut lines = vec![format_row(header), format!("| {} |", delims.join(" | "))];
+    lines.extend(rows.iter().map(|row| format_row(row)));
+    lines.join("\n")
+}
+
+// Records of a CSV (or TSV) text, quoted fields can contain separators, newlines
+// and quotes escaped as `""`
+pub(crate) fn parse_delimited(text: &str, separator: char) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            '\n' if !quoted => {
+                record.push(core::mem::take(&mut field));
+                records.push(core::mem::take(&mut record));
+            }
+            c if c == separator && !quoted => record.push(core::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    records.push(record);
+    records
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+        .map(|record| {
+            record
+                .into_iter()
+                .map(|field| field.trim().to_string())
+                .collect()
+        })
+        .collect()
+}
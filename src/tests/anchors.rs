@@ -0,0 +1,62 @@
+use crate::{slugify, LinkChecker, SlugCharset, TransformOptions};
+
+#[test]
+fn test_slugify() {
+    assert_eq!(slugify("Hello World"), "hello-world");
+    assert_eq!(slugify(" What's new? (v2.0) "), "whats-new-v20");
+    assert_eq!(slugify("snake_case-title"), "snake_case-title");
+}
+
+#[test]
+fn test_check_links() {
+    let mut checker = LinkChecker::default();
+    let index = "# Intro\n\n## Intro\n\nSee [a](#intro-1), [b](#nope), [c](guide/setup.md#install)\nand [d](guide/missing.md), [e](/abs.md) or [f](https://example.com#nope)";
+    let setup =
+        "# Install **now**\n\nBack to [index](../index.md#intro), [g](setup.md#install-now)";
+    assert!(checker.add_document("index.md", index).is_ok());
+    assert!(checker.add_document("guide/setup.md", setup).is_ok());
+
+    let broken = checker
+        .check()
+        .into_iter()
+        .map(|link| (link.file, link.url, link.span.line))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        broken,
+        [
+            ("index.md".to_string(), "#nope".to_string(), 5),
+            (
+                "index.md".to_string(),
+                "guide/setup.md#install".to_string(),
+                5
+            ),
+            ("index.md".to_string(), "guide/missing.md".to_string(), 6),
+        ]
+    );
+}
+
+#[test]
+fn test_check_links_ascii_slugs() {
+    let input = "# Déjà vu\n\nSee [a](#deja-vu)";
+    let mut checker = LinkChecker::default();
+    assert!(checker.add_document("index.md", input).is_ok());
+    assert_eq!(checker.check().len(), 1);
+
+    let options = TransformOptions::default().slug_charset(SlugCharset::Ascii);
+    let mut checker = LinkChecker::default().options(options);
+    assert!(checker.add_document("index.md", input).is_ok());
+    assert!(checker.check().is_empty());
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_check_reference_links() {
+    let mut checker = LinkChecker::default();
+    assert!(checker
+        .add_document("a.md", "# Title\n\n[x][1] [y][2]\n\n[1]: #title\n[2]: b.md")
+        .is_ok());
+    let broken = checker.check();
+    assert_eq!(broken.len(), 1, "{broken:?}");
+    assert_eq!(broken[0].url, "b.md");
+    assert_eq!(broken[0].span.line, 6);
+}
@@ -0,0 +1,61 @@
+use crate::transform_markdown_string;
+use crate::transformers::{AccessibilityAudit, AuditIssue};
+
+#[test]
+fn test_audit_images_and_links() {
+    let mut t = AccessibilityAudit::default();
+    let input =
+        "See [here](a), [the docs](b) or [https://c.com](https://c.com)\n\n![](d) ![alt](e)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let issues = t.issues();
+    assert_eq!(issues.len(), 3, "{issues:?}");
+    assert!(
+        matches!(&issues[0], AuditIssue::UninformativeLinkText { text, url, span } if text == "here" && url == "a" && span.column == 5)
+    );
+    assert!(
+        matches!(&issues[1], AuditIssue::UninformativeLinkText { url, .. } if url == "https://c.com")
+    );
+    assert!(
+        matches!(&issues[2], AuditIssue::MissingAltText { url, span } if url == "d" && span.line == 3)
+    );
+}
+
+#[test]
+fn test_audit_links_in_headings() {
+    let mut t = AccessibilityAudit::default();
+    let input = "# See [here](a)\n\nGo to [HTTPS://C.com](https://c.com) or [[x](y) here](z)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let links = t
+        .issues()
+        .iter()
+        .map(|issue| match issue {
+            AuditIssue::UninformativeLinkText { text, url, span } => {
+                (text.as_str(), url.as_str(), span.line)
+            }
+            _ => panic!("Unexpected issue {issue:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        links,
+        [("here", "a", 1), ("HTTPS://C.com", "https://c.com", 3)]
+    );
+}
+
+#[test]
+fn test_audit_heading_jumps() {
+    let mut t = AccessibilityAudit::default();
+    let input = "# A\n\n### B\n\n## C\n\n### D\n\n# E\n\n#### F";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let jumps = t
+        .issues()
+        .iter()
+        .map(|issue| match issue {
+            AuditIssue::HeadingLevelJump { from, to, span } => (*from, *to, span.line),
+            _ => panic!("Unexpected issue {issue:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(jumps, [(1, 3, 3), (1, 4, 11)]);
+}
@@ -0,0 +1,86 @@
+use crate::{
+    transform_markdown_batch, ElementContext, ElementId, MarkdownTransformer, StatefulTransformer,
+    TransformOptions,
+};
+
+#[derive(Default)]
+pub struct DummyTransform {
+    refs: Vec<(String, String)>,
+    ids: Vec<ElementId>,
+}
+
+impl MarkdownTransformer for DummyTransform {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.ids.push(ctx.id());
+    }
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.refs.push((slug, url));
+    }
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        match self.refs.iter().find(|(s, _)| *s == slug) {
+            Some((_, url)) => format!("{text}<{url}>"),
+            None => format!("{text}<?>"),
+        }
+    }
+}
+
+impl StatefulTransformer for DummyTransform {
+    type Snapshot = Vec<(String, String)>;
+
+    fn reset(&mut self) {
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.refs.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.refs = snapshot;
+    }
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_batch_isolated_refs() {
+    let mut t = DummyTransform::default();
+    let inputs = ["[a][r]\n\n[r]: one", "[b][r]", "[c][r]\n\n[r]: two"];
+    let res = transform_markdown_batch(inputs, &mut t, &TransformOptions::default());
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), ["a<one>", "b<?>", "c<two>"]);
+    assert!(t.refs.is_empty());
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_batch_shared_refs() {
+    let mut t = DummyTransform::default();
+    let inputs = ["[a][r]", "[b][s]\n\n[r]: one\n[s]: two"];
+    let options = TransformOptions::default().share_batch_state(true);
+    let res = transform_markdown_batch(inputs, &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), ["a<one>", "b<two>"]);
+    assert!(t.refs.is_empty());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_batch_footnotes_per_document() {
+    for share in [false, true] {
+        let mut t = DummyTransform::default();
+        let inputs = ["a[^x] b[^y]\n\n[^x]: X\n[^y]: Y", "c[^y]\n\n[^y]: Z"];
+        let options = TransformOptions::default().share_batch_state(share);
+        let res = transform_markdown_batch(inputs, &mut t, &options);
+        assert!(res.is_ok(), "Error on transformation: {res:?}");
+        assert_eq!(res.unwrap(), ["a[1] b[2][1]: X\n[2]: Y", "c[1][1]: Z"]);
+    }
+}
+
+#[test]
+fn test_batch_ids_per_document() {
+    let mut t = DummyTransform::default();
+    let res = transform_markdown_batch(["**a**", "**a**"], &mut t, &TransformOptions::default());
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (first, second) = t.ids.split_at(t.ids.len() / 2);
+    assert_eq!(first, second);
+}
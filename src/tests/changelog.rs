@@ -0,0 +1,69 @@
+use crate::Changelog;
+
+const CHANGELOG: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+## [2.0.0] - 2024-05-02 [YANKED]
+
+### Removed
+1. The `legacy` feature
+
+## [1.1.0] - 2024-04-01
+
+### Added
+- Support for **tables**,
+  spanning several lines
+- A `[link](https://example.org)` in an entry
+
+### Fixed
+- Off by one [error](https://example.org/issue/1)
+
+## 1.0.0
+
+[2.0.0]: https://example.org/compare/v1.1.0...v2.0.0
+[1.1.0]: https://example.org/compare/v1.0.0...v1.1.0
+";
+
+#[test]
+fn test_changelog() {
+    let res = Changelog::parse(CHANGELOG);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let changelog = res.unwrap();
+    let versions = changelog
+        .releases
+        .iter()
+        .map(|release| release.version.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(versions, ["2.0.0", "1.1.0", "1.0.0"]);
+
+    let yanked = changelog.release("2.0.0").unwrap();
+    assert!(yanked.yanked);
+    assert_eq!(yanked.date.as_deref(), Some("2024-05-02"));
+    assert_eq!(yanked.changes("removed"), ["The `legacy` feature"]);
+    assert_eq!(yanked.span.line, 5);
+
+    let release = changelog.release("1.1.0").unwrap();
+    assert!(!release.yanked);
+    assert_eq!(
+        release.url.as_deref(),
+        Some("https://example.org/compare/v1.0.0...v1.1.0")
+    );
+    assert_eq!(
+        release.changes("Added"),
+        [
+            "Support for **tables**,\n  spanning several lines",
+            "A `[link](https://example.org)` in an entry"
+        ]
+    );
+    assert_eq!(
+        release.changes("Fixed"),
+        ["Off by one [error](https://example.org/issue/1)"]
+    );
+    assert!(release.changes("Security").is_empty());
+
+    let first = changelog.release("1.0.0").unwrap();
+    assert_eq!((first.date.as_deref(), first.url.as_deref()), (None, None));
+    assert!(first.groups.is_empty());
+    assert!(changelog.release("0.1.0").is_none());
+}
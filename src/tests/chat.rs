@@ -0,0 +1,63 @@
+use crate::transform_markdown_string;
+use crate::transformers::{DiscordRenderer, TelegramRenderer};
+
+#[test]
+fn test_telegram_escaping() {
+    let mut t = TelegramRenderer::default();
+    let input = "# Release 1.2!\n\nFixed **bugs** see [issue #3](https://x.y/a) in `a_b`, v1.0-rc\n\n- one\n- two_three\n\n> quoted *text*";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "*Release 1\\.2\\!*\n\nFixed *bugs* see [issue \\#3](https://x.y/a) in `a_b`, v1\\.0\\-rc\n\n• one\n• two\\_three\n\n>quoted _text_"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_telegram_code() {
+    let mut t = TelegramRenderer::default();
+    let input =
+        "```sh\necho \\`date\\`\n```\n\n| a | b |\n|---|--:|\n| 1 | 2 |\n\n3. three\n4. four";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "```sh\necho \\\\\\`date\\\\\\`\n```\n\n```\n| a   |   b |\n| --- | --: |\n| 1   |   2 |\n```\n\n3\\. three\n4\\. four"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_telegram_table_cells() {
+    let mut t = TelegramRenderer::default();
+    let input = "| v1.0 | a_b |\n|---|---|\n| 1.5 | 2 |";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "```\n| v1.0 | a_b |\n| ---- | --- |\n| 1.5  | 2   |\n```"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_discord() {
+    let mut t = DiscordRenderer::default();
+    let input = "# Title\n\n#### Deep\n\nSome **bold** and snake_case, see https://ex.com/a_b\n\n> [!TIP]\n> hint\n\n- a\n- b";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "# Title\n\n**Deep**\n\nSome **bold** and snake\\_case, see https://ex.com/a_b\n\n> **Tip**\n> hint\n\n- a\n- b"
+    );
+}
+
+#[test]
+fn test_discord_line_start() {
+    let mut t = DiscordRenderer::default();
+    let input = "-# not a subtext\n\n#hashtag and 2.5 stay";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "\\-# not a subtext\n\n#hashtag and 2.5 stay");
+}
@@ -0,0 +1,82 @@
+use crate::{
+    transform_markdown_string, transform_markdown_string_with_options,
+    transformers::MarkdownRenderer, MarkdownTransformer, TransformOptions,
+};
+
+pub struct Bib;
+impl MarkdownTransformer for Bib {
+    fn transform_citation(&mut self, keys: Vec<String>) -> String {
+        format!("({})", keys.join(", "))
+    }
+    fn transform_bibliography(&mut self, keys: Vec<String>) -> String {
+        format!("\nReferences: {}", keys.join(", "))
+    }
+    fn transform_paragraph(&mut self, text: String) -> String {
+        text + "\n"
+    }
+}
+
+#[test]
+fn test_citations_bibliography_at_end() {
+    let input = "As shown [@knuth84; @lamport:latex], and again [@knuth84].\n\nSee [@dijkstra68].";
+    let options = TransformOptions::default().citations(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut Bib, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "As shown (knuth84, lamport:latex), and again (knuth84).\nSee (dijkstra68).\n\nReferences: knuth84, lamport:latex, dijkstra68"
+    );
+}
+
+#[test]
+fn test_citations_bibliography_marker() {
+    // The keys are gathered in the peek pass, the section can come before the citations
+    let input = "[BIBLIOGRAPHY]\n\nText [@a]\n\nMore [@b]";
+    let options = TransformOptions::default().citations(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut Bib, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "\nReferences: a, bText (a)\nMore (b)\n");
+}
+
+#[test]
+fn test_citations_bibliography_marker_twice() {
+    let input = "[BIBLIOGRAPHY]\n\nText [@a]\n\n[BIBLIOGRAPHY]";
+    let options = TransformOptions::default().citations(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut Bib, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "\nReferences: aText (a)\n");
+}
+
+#[test]
+fn test_citations_without_bibliography() {
+    let input = "No citation here";
+    let options = TransformOptions::default().citations(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut Bib, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "No citation here\n");
+}
+
+#[test]
+fn test_citations_markdown_roundtrip() {
+    let input = "Cited [@a; @b].";
+    let options = TransformOptions::default().citations(true);
+    let res = transform_markdown_string_with_options(
+        input.to_string(),
+        &mut MarkdownRenderer::default(),
+        &options,
+    );
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+fn test_citations_disabled() {
+    let input = "[@octocat] said hi\n\n[BIBLIOGRAPHY]";
+    let res = transform_markdown_string(input.to_string(), &mut Bib);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "[@octocat] said hi\n[BIBLIOGRAPHY]\n");
+
+    let res = transform_markdown_string(input.to_string(), &mut MarkdownRenderer::default());
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
@@ -0,0 +1,398 @@
+#[cfg(not(feature = "dialect-v1"))]
+use crate::extract_code_blocks;
+use crate::CodeblockFilter;
+
+#[test]
+fn test_codeblock_filter() {
+    let code = "# use std::io;\n#\nfn main() {\n    # let hidden = 1;\n    ## not hidden\n    #[derive(Debug)]\n}";
+    assert_eq!(CodeblockFilter::default().apply(code), code);
+    assert_eq!(
+        CodeblockFilter::default()
+            .hide_rustdoc_lines(true)
+            .apply(code),
+        "fn main() {\n    # not hidden\n    #[derive(Debug)]\n}"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_extract_code_blocks() {
+    let input = "# Run\n\n```sh\nmake\n```\n\nText\n\n```rust,ignore\nlet a = 1;\n```\n\n```sh\nmake test\n```";
+    let res = extract_code_blocks(input, None);
+    assert!(res.is_ok(), "Error on extraction: {res:?}");
+    let blocks = res.unwrap();
+    assert_eq!(blocks.len(), 3, "{blocks:?}");
+    assert_eq!(blocks[1].lang.as_deref(), Some("rust"));
+    assert_eq!(blocks[1].flags, ["ignore"]);
+
+    let blocks = extract_code_blocks(input, Some("sh")).unwrap();
+    let contents = blocks
+        .iter()
+        .map(|block| (block.content.as_str(), block.span.line))
+        .collect::<Vec<_>>();
+    assert_eq!(contents, [("make", 3), ("make test", 13)]);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_rust_test_harness() {
+    use crate::rust_test_harness;
+
+    let input = "```rust\n# let a = 1;\nassert_eq!(a, 1);\n```\n\n```sh\nls\n```\n\n```rust,should_panic\npanic!();\n```\n\n```rust,no_run\nfn main() {}\n```\n\n```rust,compile_fail\nlet\n```";
+    let res = rust_test_harness(input, "README.md");
+    assert!(res.is_ok(), "Error on generation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "// Generated from the code blocks of README.md
+
+#[test]
+fn readme_md_line_1() {
+    // README.md:1
+    let a = 1;
+    assert_eq!(a, 1);
+}
+
+#[test]
+#[should_panic]
+fn readme_md_line_10() {
+    // README.md:10
+    panic!();
+}
+
+#[allow(dead_code)]
+fn readme_md_line_14() {
+    // README.md:14
+    fn main() {}
+    main();
+}
+"
+    );
+}
+
+#[test]
+fn test_transform_diagram() {
+    use crate::{transform_markdown_string, DiagramKind, MarkdownTransformer};
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_codeblock(
+            &mut self,
+            language: Option<String>,
+            _flags: Vec<String>,
+            text: String,
+        ) -> String {
+            format!("<code {}>{text}</code>", language.unwrap_or_default())
+        }
+        fn transform_diagram(&mut self, kind: DiagramKind, source: String) -> String {
+            match kind {
+                DiagramKind::Mermaid => format!("<pre class=\"mermaid\">{source}</pre>"),
+                _ => self.transform_codeblock(Some(kind.language().to_string()), vec![], source),
+            }
+        }
+    }
+
+    let input = "```mermaid\ngraph TD\n```\n```dot\ndigraph {}\n```\n```rust\nfn a() {}\n```";
+    let res = transform_markdown_string(input.to_string(), &mut DummyTransform);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "<pre class=\"mermaid\">graph TD</pre><code dot>digraph {}</code><code rust>fn a() {}</code>"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+struct Listings;
+#[cfg(not(feature = "dialect-v1"))]
+impl crate::MarkdownTransformer for Listings {
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        flags: Vec<String>,
+        text: String,
+    ) -> String {
+        format!("[{} {flags:?}]\n{text}\n", language.unwrap_or_default())
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_include_file() {
+    use crate::{transform_markdown_string_with_warnings, TransformOptions};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let counter = loads.clone();
+    let options = TransformOptions::default().include_resolver(move |path| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        match path {
+            "src/main.rs" => Ok("use a;\n\nfn main() {\n    a::run();\n}\n".to_string()),
+            _ => Err("not found".to_string()),
+        }
+    });
+    let input = "```rust file=src/main.rs\n```\n\n```rust,ignore file=src/main.rs lines=3-5\n```\n\n```rust file=src/main.rs lines=2\n```";
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut Listings, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(
+        output,
+        "[rust []]\nuse a;\n\nfn main() {\n    a::run();\n}\n[rust [\"ignore\"]]\nfn main() {\n    a::run();\n}\n[rust []]\n\n"
+    );
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_include_failed() {
+    use crate::{transform_markdown_string_with_warnings, TransformOptions, Warning};
+
+    let options = TransformOptions::default().include_resolver(|path| match path {
+        "a.rs" => Ok("a\nb".to_string()),
+        _ => Err("not found".to_string()),
+    });
+    let input = "```rust file=b.rs\nkept\n```\n\n```rust file=a.rs lines=2-4\nkept too\n```\n\n```rust file=a.rs lines=x\n```";
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut Listings, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(
+        output,
+        "[rust []]\nkept\n[rust []]\nkept too\n[rust []]\n\n"
+    );
+    assert_eq!(warnings.len(), 3, "{warnings:?}");
+    assert!(
+        matches!(&warnings[0], Warning::IncludeFailed { path, span, .. } if path == "b.rs" && span.line == 1)
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "1:1: cannot include \"b.rs\": not found"
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "5:1: cannot include \"a.rs\": lines 2-4 are out of the 2 lines of the file"
+    );
+    assert_eq!(
+        warnings[2].to_string(),
+        "9:1: cannot include \"a.rs\": invalid line range \"x\""
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_include_flags_kept_without_resolver() {
+    let blocks = extract_code_blocks(
+        "```rust file=src/main.rs lines=1-2\nfn main() {}\n```",
+        None,
+    );
+    assert!(blocks.is_ok(), "Error on extraction: {blocks:?}");
+    let blocks = blocks.unwrap();
+    assert_eq!(blocks[0].flags, ["file=src/main.rs", "lines=1-2"]);
+    assert_eq!(blocks[0].content, "fn main() {}");
+}
+
+#[test]
+#[cfg(all(feature = "std", not(feature = "dialect-v1")))]
+fn test_include_dir_stays_inside() {
+    use crate::{transform_markdown_string_with_warnings, TransformOptions, Warning};
+
+    let options = TransformOptions::default().include_dir(env!("CARGO_MANIFEST_DIR"));
+    let input = "```toml file=Cargo.toml lines=1\n```\n\n```sh file=../secret\n```";
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut Listings, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(output, "[toml []]\n[package]\n[sh []]\n\n");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(
+        matches!(&warnings[0], Warning::IncludeFailed { error, .. } if error == "path outside of the include directory")
+    );
+}
+
+#[test]
+fn test_extract_anchor() {
+    use crate::extract_anchor;
+
+    let content = "use a;\n// ANCHOR: main\nfn main() {\n    // ANCHOR: call\n    a::run();\n    // ANCHOR_END: call\n}\n// ANCHOR_END: main\n# ANCHOR: tail\nend";
+    assert_eq!(
+        extract_anchor(content, "main").as_deref(),
+        Some("fn main() {\n    a::run();\n}")
+    );
+    assert_eq!(
+        extract_anchor(content, "call").as_deref(),
+        Some("    a::run();")
+    );
+    assert_eq!(extract_anchor(content, "tail").as_deref(), Some("end"));
+    assert_eq!(extract_anchor(content, "mai"), None);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_include_anchor() {
+    use crate::{transform_markdown_string_with_warnings, TransformOptions, Warning};
+
+    let options = TransformOptions::default().include_resolver(|_| {
+        Ok(
+            "// ANCHOR: setup\nlet a = 1;\nlet b = 2;\n// ANCHOR_END: setup\nrun(a, b);"
+                .to_string(),
+        )
+    });
+    let input = "```rust file=a.rs anchor=setup\n```\n\n```rust file=a.rs anchor=setup lines=2\n```\n\n```rust file=a.rs anchor=other\n```";
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut Listings, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(
+        output,
+        "[rust []]\nlet a = 1;\nlet b = 2;\n[rust []]\nlet b = 2;\n[rust []]\n\n"
+    );
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(
+        matches!(&warnings[0], Warning::IncludeFailed { error, .. } if error == "no anchor \"other\" in the file")
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_themed_codeblocks() {
+    use crate::{
+        transform_markdown_string, transform_markdown_string_with_options, CodeblockInfo,
+        MarkdownTransformer, TransformOptions,
+    };
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_codeblock(
+            &mut self,
+            _language: Option<String>,
+            _flags: Vec<String>,
+            text: String,
+        ) -> String {
+            format!("code({text})")
+        }
+        fn transform_codeblock_with_info(
+            &mut self,
+            _language: Option<String>,
+            text: String,
+            info: CodeblockInfo,
+        ) -> String {
+            let lines = info.lines.highlighted.len();
+            if info.themes.is_empty() {
+                return format!("lines({text}|{lines})");
+            }
+            info.themes
+                .into_iter()
+                .map(|theme| format!("{}({text}|{}|{lines})", theme.name(), info.flags.join(",")))
+                .collect::<Vec<String>>()
+                .join("")
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "```rust\na\n```\n\n```rust theme=dark {1}\nb\n```\n\n```mermaid\nc\n```";
+
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "code(a)lines(b|1)code(c)");
+
+    let options = TransformOptions::default().code_themes(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "light(a||0)dark(a||0)dark(b|theme=dark,{1}|1)code(c)"
+    );
+}
+
+#[test]
+fn test_language_aliases() {
+    use crate::{
+        transform_markdown_string_with_warnings, DiagramKind, MarkdownTransformer, TransformOptions,
+    };
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_codeblock(
+            &mut self,
+            language: Option<String>,
+            _flags: Vec<String>,
+            _text: String,
+        ) -> String {
+            format!("[{}]", language.unwrap_or_default())
+        }
+        fn transform_diagram(&mut self, kind: DiagramKind, _source: String) -> String {
+            format!("[{kind:?}]")
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "```JS\na\n```\n\n```sh\nb\n```\n\n```graphviz\nc\n```\n\n```rust\nd\n```";
+
+    let options = TransformOptions::default()
+        .language_alias("graphviz", "dot")
+        .language_alias("sh", "zsh")
+        .common_language_aliases()
+        .known_language("javascript")
+        .known_language("rust");
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(output, "[javascript][zsh][Graphviz][rust]");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_codeblock_lines() {
+    use crate::{
+        transform_markdown_string, transformers::MarkdownRenderer, CodeblockInfo, CodeblockLines,
+        MarkdownTransformer,
+    };
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_codeblock(
+            &mut self,
+            _language: Option<String>,
+            _flags: Vec<String>,
+            text: String,
+        ) -> String {
+            format!("code({text})")
+        }
+        fn transform_codeblock_with_info(
+            &mut self,
+            _language: Option<String>,
+            text: String,
+            info: CodeblockInfo,
+        ) -> String {
+            let (flags, lines) = (info.flags, info.lines);
+            let marks = text
+                .lines()
+                .enumerate()
+                .map(|(n, _)| {
+                    if lines.is_highlighted(n + 1) {
+                        '>'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect::<String>();
+            format!("{}[{marks}]{}", flags.join("|"), lines.numbered)
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "```rust {1,3-4} linenos\na\nb\nc\nd\ne\n```\n\n```rust,{2}\na\nb\n```\n\n```rust ignore\na\n```";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "{1,3-4}|linenos[> >> ]true{2}[ >]falsecode(a)"
+    );
+
+    let lines = CodeblockLines::from_flags(&["{ 2 , 5-3, x, 7-8 }".to_string()]);
+    assert_eq!(lines.highlighted, vec![2..=2, 7..=8]);
+    assert!(CodeblockLines::from_flags(&["linenos=false".to_string()]).is_empty());
+
+    let mut t = MarkdownRenderer::default();
+    let input = "```rust,{1,3-4},linenos\na\n```";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
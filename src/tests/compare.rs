@@ -0,0 +1,29 @@
+use crate::{compare_with_pulldown_cmark, DialectDiff};
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_compare_same_rendering() {
+    let input = "# Title\n\nSome *text* and **bold** with a [link](https://a.b) and `code`.\n\n- a\n- b\n\n> quote\n\n```rust\nfn main() {}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n---\n\n![alt](img.png)";
+    let res = compare_with_pulldown_cmark(input);
+    assert!(res.is_ok(), "Error on comparison: {res:?}");
+    assert_eq!(res.unwrap(), []);
+}
+
+#[test]
+fn test_compare_dialect_gap() {
+    let input = "Intro\n\nTitle\n=====\n\nEnd";
+    let res = compare_with_pulldown_cmark(input);
+    assert!(res.is_ok(), "Error on comparison: {res:?}");
+    let diffs = res.unwrap();
+    assert_eq!(
+        diffs,
+        [DialectDiff {
+            pulldown_cmark: vec!["<h1>Title</h1>".to_string()],
+            mdtrans: vec!["<p>Title =====</p>".to_string()],
+        }]
+    );
+    assert_eq!(
+        diffs[0].to_string(),
+        "- <h1>Title</h1>\n+ <p>Title =====</p>\n"
+    );
+}
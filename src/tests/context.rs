@@ -0,0 +1,182 @@
+use crate::{
+    transform_markdown_string, ElementContext, ElementId, MarkdownTransformer, Rule, Span,
+};
+
+#[test]
+fn test_element_source() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        source: String,
+        span: Option<Span>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.source = ctx.source().to_string();
+            self.span = Some(ctx.span());
+        }
+        fn transform_bold(&mut self, text: String) -> String {
+            format!("[{}]{text}", self.source)
+        }
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            let span = self.span.unwrap();
+            assert_eq!((span.line, span.column), (3, 1));
+            format!("{level}[{}]{text}", self.source)
+        }
+    }
+    let mut t = DummyTransform::default();
+
+    let input = "Some **bold**\n\n## Title **b**";
+    let output = "Some [**bold**]bold2[## Title **b**]Title [**b**]b";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[test]
+fn test_element_rule() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        rules: Vec<Rule>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.rules.push(ctx.rule());
+        }
+    }
+    let mut t = DummyTransform::default();
+
+    let res = transform_markdown_string("a *b*".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let expected = [Rule::italic, Rule::paragraph];
+    assert_eq!(t.rules, [expected, expected].concat());
+}
+
+#[test]
+fn test_element_ids() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        current: Option<ElementId>,
+        peeked: Vec<ElementId>,
+        transformed: Vec<ElementId>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.current = Some(ctx.id());
+        }
+        fn peek_paragraph(&mut self, _text: String) {
+            self.peeked.push(self.current.unwrap());
+        }
+        fn transform_paragraph(&mut self, text: String) -> String {
+            self.transformed.push(self.current.unwrap());
+            text
+        }
+    }
+
+    let mut t = DummyTransform::default();
+    let res = transform_markdown_string("same\n\nsame\n\nother".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(t.peeked, t.transformed);
+    assert_eq!(t.transformed.len(), 3);
+    assert_ne!(t.transformed[0], t.transformed[1]);
+
+    let mut t2 = DummyTransform::default();
+    let res = transform_markdown_string("same\n\nsame\n\nchanged".to_string(), &mut t2);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(t.transformed[..2], t2.transformed[..2]);
+    assert_ne!(t.transformed[2], t2.transformed[2]);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_element_ordinals() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        transform_pass: bool,
+        peeked: Vec<(usize, Span)>,
+        transformed: Vec<(usize, Span)>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            let calls = match self.transform_pass {
+                false => &mut self.peeked,
+                true => &mut self.transformed,
+            };
+            calls.push((ctx.ordinal(), ctx.span()));
+        }
+        fn finished(&mut self, _peek: bool) -> String {
+            self.transform_pass = true;
+            String::new()
+        }
+    }
+    let mut t = DummyTransform::default();
+
+    let input = "# Title *a*\n\nSome **bold *b***, a [link](url)\n\n- one\n- two `c`\n\n  > quote\n\n| x |\n|---|\n| y |\n";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(t.peeked, t.transformed);
+
+    // An element is either after the previous one, or contains it
+    for pair in t.peeked.windows(2) {
+        let ((prev, prev_span), (next, next_span)) = (pair[0], pair[1]);
+        assert!(
+            prev < next || (next_span.start <= prev_span.start && prev_span.end <= next_span.end),
+            "Hooks called out of order: {pair:?}"
+        );
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_element_ordinals_footnotes() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        ordinals: Vec<(Rule, usize)>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.ordinals.push((ctx.rule(), ctx.ordinal()));
+        }
+    }
+    let mut t = DummyTransform::default();
+
+    // The content of the definition is only transformed in the transform pass
+    let input = "A note[^1].\n\n[^1]: With **bold**\n\n**After**\n";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let bolds = t
+        .ordinals
+        .iter()
+        .filter(|(rule, _)| *rule == Rule::bold)
+        .map(|(_, ordinal)| *ordinal)
+        .collect::<Vec<usize>>();
+    assert_eq!(bolds.len(), 3);
+    // Peek of "After", then "After" and the definition content in the transform pass
+    assert_eq!(bolds[0], bolds[1]);
+    assert!(bolds[2] < bolds[1]);
+}
+
+#[test]
+fn test_element_anchor() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        anchor: Option<String>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.anchor = ctx.anchor().map(str::to_string);
+        }
+        fn transform_header(&mut self, _level: usize, text: String) -> String {
+            format!("{text}#{}", self.anchor.as_deref().unwrap_or("-"))
+        }
+        fn transform_paragraph(&mut self, text: String) -> String {
+            format!("{text}#{}", self.anchor.as_deref().unwrap_or("-"))
+        }
+    }
+    let mut t = DummyTransform::default();
+
+    let input = "# A **b** c\n\nText\n\n## A b c";
+    let output = "A b c#a-b-cText#-A b c#a-b-c-1";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
@@ -0,0 +1,120 @@
+// Each `tests/corpus/<name>.md` input is parsed and the elements matched are compared
+// with the trace saved in `tests/corpus/<name>.trace`, the hooks called to transform it
+// with the ones saved in `tests/corpus/<name>.hooks`
+//
+// Running the tests with `UPDATE_CORPUS=1` writes the traces instead of checking them,
+// the changes made to the grammar can then be reviewed in the diff of the trace files.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    transform_markdown_string_with_options, transformers::TraceTransformer, walk_markdown,
+    ElementContext, MarkdownTransformer, MarkdownVisitor, TransformOptions,
+};
+
+// Keeps the default behavior of every hook
+struct Identity;
+impl MarkdownTransformer for Identity {}
+
+// One line per element, indented under its parent, with the source of the elements
+// that contain no other
+#[derive(Default)]
+struct EventTrace {
+    lines: Vec<String>,
+    open: Vec<usize>,
+}
+
+impl MarkdownVisitor for EventTrace {
+    fn enter(&mut self, ctx: &ElementContext) {
+        let indent = "  ".repeat(self.open.len());
+        self.open.push(self.lines.len());
+        self.lines.push(format!("{indent}{:?}", ctx.rule()));
+    }
+
+    fn leave(&mut self, ctx: &ElementContext) {
+        let start = self.open.pop().unwrap();
+        if start == self.lines.len() - 1 {
+            self.lines[start] += &format!(" {:?}", ctx.source());
+        }
+    }
+}
+
+fn event_trace(input: &str) -> String {
+    let mut trace = EventTrace::default();
+    let res = walk_markdown(input, &mut trace);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    trace.lines.join("\n") + "\n"
+}
+
+// NOTE    Cross-references and citations are enabled to keep their hooks in the traces of
+//         the corpus
+fn hooks_trace(input: &str) -> String {
+    let mut trace = TraceTransformer::new(Identity).context(false);
+    let options = TransformOptions::default().crossrefs(true).citations(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut trace, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    trace.pretty() + "\n"
+}
+
+// Compares the trace with the recorded one, or records it when updating the corpus
+fn check_trace(path: &Path, trace: &str, update: bool) -> Result<(), String> {
+    let name = path.file_name().unwrap().to_string_lossy();
+    if update {
+        std::fs::write(path, trace).unwrap();
+        return Ok(());
+    }
+    let Ok(expected) = std::fs::read_to_string(path) else {
+        return Err(format!("{name}: no trace recorded"));
+    };
+    if trace == expected {
+        return Ok(());
+    }
+    let got = trace.lines().collect::<Vec<&str>>();
+    let exp = expected.lines().collect::<Vec<&str>>();
+    let line = (0..got.len().max(exp.len()))
+        .find(|&n| got.get(n) != exp.get(n))
+        .unwrap_or(got.len());
+    Err(format!(
+        "{name}: line {}\n  expected: {}\n  got:      {}",
+        line + 1,
+        exp.get(line).unwrap_or(&""),
+        got.get(line).unwrap_or(&"")
+    ))
+}
+
+fn corpus_inputs() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut inputs = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect::<Vec<PathBuf>>();
+    inputs.sort();
+    inputs
+}
+
+#[test]
+fn test_corpus_event_traces() {
+    let update = std::env::var_os("UPDATE_CORPUS").is_some();
+    let inputs = corpus_inputs();
+    assert!(!inputs.is_empty(), "No input found in the corpus");
+
+    let mut failed = vec![];
+    for input in inputs {
+        let source = std::fs::read_to_string(&input).unwrap();
+        let traces = [
+            (input.with_extension("trace"), event_trace(&source)),
+            (input.with_extension("hooks"), hooks_trace(&source)),
+        ];
+        for (path, trace) in traces {
+            if let Err(diff) = check_trace(&path, &trace, update) {
+                failed.push(diff);
+            }
+        }
+    }
+    assert!(
+        failed.is_empty(),
+        "Traces differ from the corpus (run with UPDATE_CORPUS=1 to record them):\n{}",
+        failed.join("\n")
+    );
+}
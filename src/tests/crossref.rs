@@ -0,0 +1,92 @@
+use crate::{
+    transform_markdown_string, transform_markdown_string_with_options,
+    transform_markdown_string_with_warnings, transformers::MarkdownRenderer, CrossrefKind,
+    MarkdownTransformer, TransformOptions, Warning,
+};
+
+pub struct Refs;
+impl MarkdownTransformer for Refs {
+    fn transform_header(&mut self, _level: usize, text: String) -> String {
+        text + "\n"
+    }
+    fn transform_crossref(&mut self, kind: CrossrefKind, slug: String, resolved: String) -> String {
+        format!("<{kind:?} {slug}: {resolved}>")
+    }
+}
+
+#[test]
+fn test_crossref_disabled() {
+    let input = "# Intro\n\nSee [#intro].";
+    let res = transform_markdown_string(input.to_string(), &mut Refs);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Intro\nSee [#intro].");
+}
+
+#[test]
+fn test_crossref_headers() {
+    let options = TransformOptions::default().crossrefs(true);
+    let input =
+        "See [#usage-mdtrans] and **[#intro-1]**\n\n# Intro\n\n# Intro\n\n## Usage `mdtrans`";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut Refs, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "See <Header usage-mdtrans: Usage mdtrans> and <Header intro-1: Intro>Intro\nIntro\nUsage mdtrans\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_crossref_figures() {
+    let options = TransformOptions::default().crossrefs(true);
+    let input = "![a](a.png)[caption: First]\n\n![b](b.png)[caption: Second, id: arch]\n\nAs shown in [#arch]";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut Refs, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert!(res.unwrap().ends_with("As shown in <Figure arch: 2>"));
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_crossref_figures_localized() {
+    use crate::{CounterKind, Phrase};
+
+    pub struct French;
+    impl MarkdownTransformer for French {
+        fn localize(&mut self, phrase: Phrase) -> String {
+            match phrase {
+                Phrase::Counter(CounterKind::Figure) => "Illustration".to_string(),
+                phrase => phrase.english().to_string(),
+            }
+        }
+    }
+    let options = TransformOptions::default().crossrefs(true);
+    let input = "![b](b.png)[caption: Truite, id: truite]\n\nVoir [#truite]";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut French, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert!(output.ends_with("Voir Illustration 1"), "{output}");
+}
+
+#[test]
+fn test_crossref_unresolved() {
+    let options = TransformOptions::default().crossrefs(true);
+    let input = "# Intro\n\nSee [#nowhere].";
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut Refs, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(output, "Intro\nSee [#nowhere].");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(
+        matches!(&warnings[0], Warning::UnresolvedReference { label, span } if label == "nowhere" && span.line == 3)
+    );
+}
+
+#[test]
+fn test_crossref_markdown_roundtrip() {
+    let options = TransformOptions::default().crossrefs(true);
+    let input = "# Intro\n\nSee [#intro].";
+    let mut renderer = MarkdownRenderer::default();
+    let res = transform_markdown_string_with_options(input.to_string(), &mut renderer, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "# Intro\n\nSee [#intro].");
+}
@@ -0,0 +1,81 @@
+use crate::transformers::{HtmlRenderer, MarkdownRenderer};
+use crate::{transform_inline_markdown, transform_markdown_string, InlineTransformer};
+use crate::{Document, ElementKind, Inline};
+
+const FAQ: &str =
+    "# FAQ\n\n??? Why **this** crate?\n    Because it is small.\n\n    - fast\n    - safe\n\nAfter";
+
+#[test]
+fn test_details_html() {
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string(FAQ.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(
+        html.contains("<details><summary>Why <strong>this</strong> crate?</summary><p>Because it is small.</p>"),
+        "{html}"
+    );
+    assert!(html.contains("<li>safe</li></ul></details>"), "{html}");
+    assert!(html.ends_with("<p>After</p>"), "{html}");
+}
+
+#[test]
+fn test_details_markdown_roundtrip() {
+    let mut t = MarkdownRenderer::default();
+    let res = transform_markdown_string(FAQ.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), FAQ);
+}
+
+#[test]
+fn test_details_tree() {
+    let res = Document::parse("??? Summary only\n\nText");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    let kinds = document
+        .root()
+        .children()
+        .iter()
+        .map(|node| node.kind())
+        .collect::<Vec<ElementKind>>();
+    assert_eq!(kinds, [ElementKind::Details, ElementKind::Paragraph]);
+
+    // NOTE    Without the marker, the indented lines continue the paragraph
+    let res = Document::parse("?? Not a section\n    text");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    assert_eq!(
+        res.unwrap().root().children()[0].kind(),
+        ElementKind::Paragraph
+    );
+}
+
+#[derive(Default)]
+struct Sections {
+    summaries: Vec<Vec<Inline>>,
+}
+
+impl InlineTransformer for Sections {
+    fn transform_details(&mut self, summary: Vec<Inline>, blocks: Vec<String>) -> String {
+        self.summaries.push(summary);
+        blocks.join("|")
+    }
+}
+
+#[test]
+fn test_details_inline() {
+    let mut t = Sections::default();
+    let res = transform_inline_markdown(FAQ, &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "FAQ\n\nBecause it is small.|fast\nsafe\n\nAfter"
+    );
+    assert_eq!(
+        t.summaries,
+        [vec![
+            Inline::Text("Why ".to_string()),
+            Inline::Bold(vec![Inline::Text("this".to_string())]),
+            Inline::Text(" crate?".to_string()),
+        ]]
+    );
+}
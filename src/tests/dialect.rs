@@ -0,0 +1,102 @@
+use super::syntax::has_kind;
+use crate::{Document, ElementKind, GRAMMAR_VERSION};
+
+#[test]
+fn test_dialect_version() {
+    assert_eq!(
+        GRAMMAR_VERSION,
+        if cfg!(feature = "dialect-v1") { 1 } else { 2 }
+    );
+}
+
+#[test]
+fn test_dialect_task_lists() {
+    let res = Document::parse("- [x] done\n- [ ] to do\n");
+    if GRAMMAR_VERSION < 2 {
+        assert!(res.is_err(), "Task list parsed by the dialect 1: {res:?}");
+        return;
+    }
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    let list = &document.root().children()[0];
+    assert_eq!(list.kind(), ElementKind::List);
+    assert_eq!(list.children()[1].as_str(), "- [ ] to do");
+}
+
+#[test]
+fn test_dialect_tables() {
+    let res = Document::parse("| a | **b** |\n|---|---|\n| `c` | d |\n");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    assert_eq!(
+        has_kind(document.root(), ElementKind::Table),
+        GRAMMAR_VERSION >= 2
+    );
+    // Inline markdown of the cells, or of the paragraph the dialect 1 keeps the rows in
+    assert!(has_kind(document.root(), ElementKind::Bold));
+    assert!(has_kind(document.root(), ElementKind::InlineCode));
+}
+
+#[test]
+fn test_dialect_blocks() {
+    let input =
+        "1. one\n2. two\n\n<div>\nhtml\n</div>\n\n{lang=fr} Bonjour {{ name }}\n\n- a\n\n  b";
+    let res = Document::parse(input);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    for kind in [
+        ElementKind::OrderedList,
+        ElementKind::HtmlBlock,
+        ElementKind::Shortcode,
+    ] {
+        assert_eq!(
+            has_kind(document.root(), kind),
+            GRAMMAR_VERSION >= 2,
+            "{kind:?}"
+        );
+    }
+    // Paragraph under the list element, or a paragraph of its own in the dialect 1
+    let list = document
+        .root()
+        .children()
+        .iter()
+        .find(|element| element.kind() == ElementKind::List)
+        .unwrap();
+    assert_eq!(list.as_str().ends_with('b'), GRAMMAR_VERSION >= 2);
+}
+
+#[test]
+fn test_dialect_footnotes_and_crossrefs() {
+    for input in ["Text[^1]\n\n[^1]: The note", "# Intro\n\nSee [#intro]"] {
+        let res = Document::parse(input);
+        assert_eq!(res.is_ok(), GRAMMAR_VERSION >= 2, "{input:?}: {res:?}");
+    }
+}
+
+#[test]
+fn test_dialect_autolinks_and_alerts() {
+    let res = Document::parse("See https://example.com\n\n> [!NOTE]\n> Text\n");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    assert_eq!(
+        has_kind(document.root(), ElementKind::Autolink),
+        GRAMMAR_VERSION >= 2
+    );
+    assert_eq!(
+        has_kind(document.root(), ElementKind::Alert),
+        GRAMMAR_VERSION >= 2
+    );
+}
+
+#[test]
+fn test_dialect_citations() {
+    let res = Document::parse("[@key]\n\n[BIBLIOGRAPHY]\n");
+    if GRAMMAR_VERSION < 2 {
+        assert!(res.is_err(), "Citation parsed by the dialect 1: {res:?}");
+        return;
+    }
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    assert!(has_kind(document.root(), ElementKind::Citation));
+    assert!(has_kind(document.root(), ElementKind::BibliographyMarker));
+}
@@ -0,0 +1,149 @@
+use crate::{
+    transform_markdown_string, Document, ElementKind, Errcode, MarkdownTransformer, ParseMode,
+};
+#[cfg(not(feature = "dialect-v1"))]
+use crate::{transform_markdown_string_with_warnings, TransformOptions, Warning};
+
+pub struct Upper;
+impl MarkdownTransformer for Upper {
+    fn transform_text(&mut self, text: String) -> String {
+        text.to_uppercase()
+    }
+}
+
+pub struct Html;
+impl MarkdownTransformer for Html {
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<b>{text}</b>")
+    }
+    fn transform_paragraph(&mut self, text: String) -> String {
+        format!("<p>{text}</p>")
+    }
+}
+
+#[test]
+fn test_document_same_output() {
+    let input = "# Title\n\nSome **bold** text\n\n- a\n- b\n";
+    let document = Document::parse(input).unwrap();
+    assert_eq!(document.source(), input);
+    for _ in 0..2 {
+        let res = transform_markdown_string(input.to_string(), &mut Html);
+        assert!(res.is_ok(), "Error on transformation: {res:?}");
+        assert_eq!(document.transform(&mut Html), res.unwrap());
+    }
+}
+
+#[test]
+fn test_document_shared_between_threads() {
+    fn assert_shareable<T: Send + Sync + Clone>() {}
+    assert_shareable::<Document>();
+
+    let document = Document::parse("Some **bold** text").unwrap();
+    let (upper, html) = std::thread::scope(|s| {
+        let upper = s.spawn(|| document.transform(&mut Upper));
+        let copy = document.clone();
+        let html = s.spawn(move || copy.transform(&mut Html));
+        (upper.join().unwrap(), html.join().unwrap())
+    });
+    assert_eq!(upper, "SOME BOLD TEXT");
+    assert_eq!(html, "<p>Some <b>bold</b> text</p>");
+}
+
+#[test]
+fn test_document_nodes() {
+    let document = Document::parse("é *a*\n\n## **T**").unwrap();
+    let root = document.root();
+    assert_eq!(root.kind(), ElementKind::Document);
+    let header = root
+        .children()
+        .iter()
+        .find(|node| node.kind() == ElementKind::Header(2))
+        .unwrap();
+    assert_eq!(header.as_str(), "## **T**");
+    assert_eq!((header.span().line, header.span().column), (3, 1));
+
+    let paragraph = &root.children()[0];
+    let italic = paragraph
+        .children()
+        .iter()
+        .find(|node| node.kind() == ElementKind::Italic)
+        .unwrap();
+    assert_eq!(italic.as_str(), "*a*");
+    assert_eq!((italic.span().line, italic.span().column), (1, 3));
+    assert_eq!(italic.span().start, 3);
+}
+
+#[test]
+fn test_document_strict_syntax_error() {
+    let res = Document::parse("# Title\n\nSome `code");
+    let Err(Errcode::SyntaxError { span, expected }) = res else {
+        panic!("Expected a syntax error, got {res:?}");
+    };
+    assert_eq!((span.line, span.column, span.start), (3, 11, 19));
+    assert_eq!(expected, vec![ElementKind::InlineCode]);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_document_lenient() {
+    let input = "Some **bold** text\n\nOpen `code\nstill open\n\n- a\n\n  [b](c\n\n# End";
+    let options = TransformOptions::default().parse_mode(ParseMode::Lenient);
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut Html, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(
+        output,
+        "<p>Some <b>bold</b> text</p><p>Open `code\nstill open</p>a<p>[b](c</p>End"
+    );
+    let spans = warnings
+        .iter()
+        .map(|warning| match warning {
+            Warning::InvalidSyntax { span, .. } => (span.line, span.column, span.end),
+            _ => panic!("Unexpected warning {warning:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(spans, vec![(3, 1, 41), (8, 3, 55)]);
+}
+
+#[test]
+fn test_document_invalid_hook() {
+    #[derive(Default)]
+    pub struct Invalid {
+        line: usize,
+    }
+    impl MarkdownTransformer for Invalid {
+        fn element_context(&mut self, ctx: &crate::ElementContext) {
+            self.line = ctx.span().line;
+        }
+        fn transform_invalid(&mut self, raw: String) -> String {
+            format!("[INVALID {}: {raw}]", self.line)
+        }
+    }
+    let document = Document::parse_with_mode("a\n\n![b](c\n\nd", ParseMode::Lenient).unwrap();
+    let invalid = &document.root().children()[1];
+    assert_eq!(invalid.kind(), ElementKind::Invalid);
+    assert_eq!(invalid.as_str(), "![b](c");
+    assert_eq!(
+        document.transform(&mut Invalid::default()),
+        "a[INVALID 3: ![b](c]d"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_document_line_endings() {
+    use crate::transformers::MarkdownRenderer;
+
+    let input = "# Title\n\nSome **bold**  \ntext[^n]\n\n- a\n\n  nested\n- b\n\n1. c\n2. d\n\n> quote\n> more\n\n> [!NOTE]\n> alert\n\n```rust\nlet a = 1;\nlet b = 2;\n```\n\n<!-- a\ncomment -->\n\n<div>\nhtml\n</div>\n\n| a | b |\n|---|---|\n| c | d |\n\n---\n\n[^n]: note";
+    let expected = Document::parse(input)
+        .unwrap()
+        .transform(&mut MarkdownRenderer::default());
+    for newline in ["\r\n", "\r"] {
+        let document = Document::parse(&input.replace('\n', newline)).unwrap();
+        assert_eq!(document.source(), input);
+        assert_eq!(
+            document.transform(&mut MarkdownRenderer::default()),
+            expected
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::{Cursor, Read};
+
+use crate::transformers::{transform_markdown_docx, DocxTransformer};
+use crate::{Document, TransformOptions};
+
+fn read_archive(docx: Vec<u8>, name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(docx)).expect("Invalid archive");
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .expect("Missing file in archive")
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+#[test]
+fn test_docx_archive() {
+    let res = transform_markdown_docx("# Title\n\nSome *text*");
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let docx = res.unwrap();
+    for name in [
+        "[Content_Types].xml",
+        "_rels/.rels",
+        "word/_rels/document.xml.rels",
+        "word/styles.xml",
+        "word/numbering.xml",
+    ] {
+        assert!(read_archive(docx.clone(), name).starts_with("<?xml"));
+    }
+    let document = read_archive(docx, "word/document.xml");
+    assert!(document.contains("<w:body><w:p><w:pPr><w:pStyle w:val=\"Heading1\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Title</w:t></w:r></w:p>"));
+    assert!(
+        document.contains("<w:r><w:rPr><w:i/></w:rPr><w:t xml:space=\"preserve\">text</w:t></w:r>")
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_docx_body() {
+    let input =
+        "**a & b** [link](https://example.com)\n\n1. one\n2. two\n\n- x\n\n```\nfn main() {}\n```";
+    let document = Document::parse(input).unwrap();
+    let body = document.transform_inline(&mut DocxTransformer::default());
+    assert_eq!(
+        body,
+        concat!(
+            "<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" ",
+            "xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\" ",
+            "xmlns:wp=\"http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing\"><w:body>",
+            "<w:p><w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">a &amp; b</w:t></w:r>",
+            "<w:r><w:t xml:space=\"preserve\"> </w:t></w:r>",
+            "<w:hyperlink r:id=\"rId3\"><w:r><w:rPr><w:rStyle w:val=\"Hyperlink\"/></w:rPr><w:t xml:space=\"preserve\">link</w:t></w:r></w:hyperlink></w:p>",
+            "<w:p><w:pPr><w:pStyle w:val=\"ListParagraph\"/><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"2\"/></w:numPr></w:pPr><w:r><w:t xml:space=\"preserve\">one</w:t></w:r></w:p>",
+            "<w:p><w:pPr><w:pStyle w:val=\"ListParagraph\"/><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"2\"/></w:numPr></w:pPr><w:r><w:t xml:space=\"preserve\">two</w:t></w:r></w:p>",
+            "<w:p><w:pPr><w:pStyle w:val=\"ListParagraph\"/><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"1\"/></w:numPr></w:pPr><w:r><w:t xml:space=\"preserve\">x</w:t></w:r></w:p>",
+            "<w:p><w:pPr><w:pStyle w:val=\"CodeBlock\"/></w:pPr><w:r><w:t xml:space=\"preserve\">fn main() {}</w:t></w:r></w:p>",
+            "</w:body></w:document>",
+        )
+    );
+}
+
+#[test]
+fn test_docx_relationships() {
+    let res = transform_markdown_docx("[a](https://a.org) ![pic](img.png)");
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let docx = res.unwrap();
+    let rels = read_archive(docx.clone(), "word/_rels/document.xml.rels");
+    assert!(rels.contains("Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink\" Target=\"https://a.org\" TargetMode=\"External\""));
+    assert!(rels.contains("Id=\"rId4\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" Target=\"img.png\" TargetMode=\"External\""));
+    let document = read_archive(docx, "word/document.xml");
+    assert!(document.contains("<a:blip r:link=\"rId4\"/>"));
+    assert!(document.contains("descr=\"pic\""));
+}
+
+#[test]
+fn test_docx_options() {
+    let document = Document::parse("# Title\n\n![pic](img.png)[width: 10]").unwrap();
+    let options = TransformOptions::default().heading_offset(1);
+    let body = document.transform_inline_with_options(&mut DocxTransformer::default(), &options);
+    assert!(body.contains("<w:pStyle w:val=\"Heading2\"/>"));
+    assert!(body.contains("<wp:extent cx=\"95250\" cy=\"2743200\"/>"));
+}
@@ -0,0 +1,52 @@
+use crate::{DraftAudit, DraftMarkerKind};
+
+#[test]
+fn test_draft_markers() {
+    let input = concat!(
+        "# Install FIXME\n\n",
+        "Run the script.\nThen TODO: check the [TODO list](todo.md).\n\n",
+        "<!-- TODO rename the section -->\n\n",
+        "```sh\nmake # TODO\n```\n\n",
+        "- TODOS are not markers, nor MY_TODO\n",
+    );
+    let mut audit = DraftAudit::default();
+    let res = audit.add_document("install.md", input);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let found = audit
+        .markers()
+        .iter()
+        .map(|m| {
+            (
+                m.kind,
+                m.span.line,
+                m.span.column,
+                &input[m.span.start..m.span.end],
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (DraftMarkerKind::Fixme, 1, 11, "FIXME"),
+            (DraftMarkerKind::Todo, 4, 6, "TODO"),
+            (DraftMarkerKind::Todo, 4, 23, "TODO"),
+            (DraftMarkerKind::Todo, 6, 6, "TODO"),
+        ]
+    );
+}
+
+#[test]
+fn test_draft_comment() {
+    let mut audit = DraftAudit::default();
+    let res = audit.add_document("a.md", "Done\n\n<!-- a draft of the intro -->");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    assert!(audit.is_clean());
+
+    let res = audit.add_document("b.md", "Intro\n\n<!--  DRAFT -->\n");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let markers = audit.markers();
+    assert_eq!(markers.len(), 1);
+    assert_eq!(markers[0].file, "b.md");
+    assert_eq!(markers[0].kind, DraftMarkerKind::Draft);
+    assert_eq!((markers[0].span.line, markers[0].span.column), (3, 1));
+}
@@ -0,0 +1,65 @@
+use crate::transformers::EmailHtmlRenderer;
+use crate::{transform_markdown_string, transform_markdown_string_with_options, TransformOptions};
+
+#[test]
+fn test_email_html() {
+    let mut t = EmailHtmlRenderer::default();
+    let input = "Some **bold** & [a link](https://a.com?x=1&y=2)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "<p style=\"margin:0 0 16px;font-family:Helvetica,Arial,sans-serif;font-size:16px;color:#222222;line-height:1.5;\">Some <strong>bold</strong> &amp; <a href=\"https://a.com?x=1&amp;y=2\" style=\"color:#1a73e8;text-decoration:underline;\">a link</a></p>"
+    );
+}
+
+#[test]
+fn test_email_html_no_classes() {
+    let mut t = EmailHtmlRenderer::default()
+        .font_family("Georgia,serif")
+        .link_color("#ff0000");
+    let input = "# Title\n\n> quote\n\n- a\n\n```rust\nlet a = b < c;\n```\n\n---\n\n[l](u)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(!html.contains("class="), "{html}");
+    assert!(
+        !html.contains("<div") && !html.contains("<section"),
+        "{html}"
+    );
+    assert!(html.contains("font-family:Georgia,serif;"), "{html}");
+    assert!(html.contains("color:#ff0000;"), "{html}");
+    assert!(html.contains(">let a = b &lt; c;</pre>"), "{html}");
+    for tag in ["<h1 ", "<blockquote ", "<ul ", "<li ", "<pre ", "<hr "] {
+        assert!(
+            html.contains(&format!("{tag}style=\"")),
+            "{tag} not styled: {html}"
+        );
+    }
+}
+
+#[test]
+fn test_email_html_rtl() {
+    let mut t = EmailHtmlRenderer::default();
+    let input = "שלום עולם\n\nHello";
+    let options = TransformOptions::default().text_direction(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(html.starts_with("<p dir=\"rtl\" style=\""), "{html}");
+    assert!(html.contains("text-align:right;\">שלום עולם</p>"), "{html}");
+    assert_eq!(html.matches("dir=").count(), 1, "{html}");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_email_html_lang() {
+    let mut t = EmailHtmlRenderer::default();
+    let input = "{lang=fr} Bonjour\n\nHello";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(html.starts_with("<p lang=\"fr\" style=\""), "{html}");
+    assert!(html.contains("line-height:1.5;\">Bonjour</p>"), "{html}");
+    assert_eq!(html.matches("lang=").count(), 1, "{html}");
+}
@@ -0,0 +1,103 @@
+use crate::{events_jsonl, events_jsonl_with_options, ParseMode, TransformOptions};
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_events_attrs() {
+    let input = concat!(
+        "1. done\n2. next\n\n",
+        "```rust,ignore\nlet a = 1;\n```\n\n",
+        "| a | b |\n|:--|--:|\n| 1 | 2 |\n\n",
+        "> [!WARNING]\n> Careful\n\n",
+        "See [@knuth; @lamport] and {{youtube id \"a title\"}}\n",
+    );
+    let res = events_jsonl(input);
+    assert!(res.is_ok(), "Error on events: {res:?}");
+    let events = res.unwrap();
+    let attrs = events
+        .lines()
+        .filter(|line| !line.contains("\"attrs\":{}"))
+        .map(|line| {
+            let start = line.find("\"attrs\":").unwrap();
+            let end = line.find(",\"span\":").unwrap();
+            &line[start + 8..end]
+        })
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        attrs,
+        [
+            r#"{"start":1}"#,
+            r#"{"number":1}"#,
+            r#"{"number":2}"#,
+            r#"{"language":"rust","flags":["ignore"]}"#,
+            r#"{"align":["left","right"]}"#,
+            r#"{"kind":"warning"}"#,
+            r#"{"keys":["knuth","lamport"]}"#,
+            r#"{"name":"youtube","args":["id","a title"]}"#,
+        ]
+    );
+}
+
+#[test]
+fn test_events_order_and_escaping() {
+    let res = events_jsonl("> \"Quoted\" *text*\n\n---\n");
+    assert!(res.is_ok(), "Error on events: {res:?}");
+    let events = res.unwrap();
+    let lines = events.lines().collect::<Vec<&str>>();
+    let kinds = lines
+        .iter()
+        .map(|line| line.split('"').nth(3).unwrap())
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        kinds,
+        ["quote", "text", "italic", "text", "horizontal_separator"]
+    );
+    assert!(lines[1].contains(r#""text":"\"Quoted\" ""#), "{}", lines[1]);
+    assert!(
+        lines[4].ends_with(r#""span":{"start":19,"end":22,"line":3,"column":1}}"#),
+        "{}",
+        lines[4]
+    );
+    assert!(events.ends_with('\n'));
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_events_task_list() {
+    let res = events_jsonl("- [x] done\n- [ ] next\n- other\n");
+    assert!(res.is_ok(), "Error on events: {res:?}");
+    let events = res.unwrap();
+    let items = events
+        .lines()
+        .filter(|line| line.contains("\"list_item\""))
+        .map(|line| line.contains(r#""attrs":{"checked":true}"#))
+        .collect::<Vec<bool>>();
+    assert_eq!(items, [true, false, false]);
+    assert!(events.contains(r#""text":"- [ ] next","attrs":{"checked":false}"#));
+}
+
+#[cfg(feature = "image-tags")]
+#[test]
+fn test_events_image_tags() {
+    let res = events_jsonl("![A cat](cat.png)[width: 200, credit: \"Bob\", lazy: true]");
+    assert!(res.is_ok(), "Error on events: {res:?}");
+    let events = res.unwrap();
+    let image = events
+        .lines()
+        .find(|line| line.contains("\"image\""))
+        .unwrap();
+    let attrs = concat!(
+        r#""attrs":{"alt":"A cat","url":"cat.png","#,
+        r#""tags":{"width":200,"credit":"Bob","lazy":true}}"#,
+    );
+    assert!(image.contains(attrs), "{image}");
+}
+
+#[test]
+fn test_events_lenient() {
+    let input = "Some **unclosed\n";
+    assert!(events_jsonl(input).is_err());
+    let options = TransformOptions::default().parse_mode(ParseMode::Lenient);
+    let res = events_jsonl_with_options(input, &options);
+    assert!(res.is_ok(), "Error on events: {res:?}");
+    assert!(res.unwrap().starts_with(r#"{"kind":"invalid","#));
+}
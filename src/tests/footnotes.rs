@@ -0,0 +1,67 @@
+use crate::{transform_markdown_string, Footnote, MarkdownTransformer};
+
+pub struct DummyTransform;
+impl MarkdownTransformer for DummyTransform {
+    fn transform_footnote_ref(&mut self, label: String, number: usize) -> String {
+        format!("<{number}:{label}>")
+    }
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let entries = entries
+            .into_iter()
+            .map(|f| format!("{}. {}", f.number, f.text))
+            .collect::<Vec<String>>();
+        format!("\nFOOTNOTES\n{}", entries.join("\n"))
+    }
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("BOLD {text} BOLD")
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_footnotes_renumbered() {
+    let mut t = DummyTransform;
+    let input = "[^b]: second **note**\n[^a]: first note\n\nSome text[^a] and more[^b]";
+    let output = "Some text<1:a> and more<2:b>\nFOOTNOTES\n1. first note\n2. second BOLD note BOLD";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_footnotes_marker() {
+    let mut t = DummyTransform;
+    let input = "A[^x]\n\n[FOOTNOTES]\n\nEnd\n\n[^x]: note\n[^unused]: other";
+    let output = "A<1:x>\nFOOTNOTES\n1. note\n2. otherEnd";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_footnotes_marker_twice() {
+    use crate::{transform_markdown_string_with_warnings, ElementKind, TransformOptions, Warning};
+
+    let mut t = DummyTransform;
+    let input = "Hi[^a]\n\n[^a]: note\n\n[FOOTNOTES]\n\n[FOOTNOTES]";
+    let options = TransformOptions::default();
+    let res = transform_markdown_string_with_warnings(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(output, "Hi<1:a>\nFOOTNOTES\n1. note");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(matches!(
+        &warnings[0],
+        Warning::DuplicateMarker { kind: ElementKind::FootnotesMarker, span } if span.line == 7
+    ));
+}
+
+#[test]
+fn test_no_footnotes_no_section() {
+    let mut t = DummyTransform;
+    let res = transform_markdown_string("Nothing here".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Nothing here");
+}
@@ -0,0 +1,253 @@
+use crate::transformers::{HtmlEscaping, HtmlRenderer};
+use crate::{transform_markdown_string, transform_markdown_string_with_options, TransformOptions};
+
+#[test]
+fn test_html() {
+    let mut t = HtmlRenderer::default();
+    let input = "# Title\n\nSome **bold** & [a link](https://a.com?x=1&y=2)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(!html.contains("style="), "{html}");
+    assert!(
+        html.starts_with("<h1>Title</h1><p>Some <strong>bold</strong> &amp; <a href=\"https://a.com?x=1&amp;y=2\">a link</a></p>"),
+        "{html}"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_html_alert() {
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string("> [!NOTE]\n> Careful".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "<div class=\"md-alert md-alert-note\"><p class=\"md-alert-title\">Note</p><p>Careful</p></div>"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_html_lang() {
+    let mut t = HtmlRenderer::default();
+    let input = "{lang=fr} Bonjour\n\nHello";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(html.starts_with("<p lang=\"fr\">Bonjour</p>"), "{html}");
+    assert_eq!(html.matches("lang=").count(), 1, "{html}");
+}
+
+#[test]
+fn test_html_escaping() {
+    let input = "Tom's `a=\"b\"` [l](https://a.com/x?y=1)";
+    let mut t = HtmlRenderer::default().escaping(HtmlEscaping::Strict);
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(html.contains(">Tom&#39;s <code>"), "{html}");
+    assert!(html.contains(">a&#61;&quot;b&quot;</code>"), "{html}");
+    assert!(
+        html.contains("href=\"https:&#47;&#47;a.com&#47;x?y&#61;1\""),
+        "{html}"
+    );
+
+    let mut t = HtmlRenderer::default().escaping(HtmlEscaping::Attribute);
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(html.contains(">Tom&#x27;s <code>"), "{html}");
+    assert!(
+        html.contains("href=\"https&#x3A;&#x2F;&#x2F;a&#x2E;com"),
+        "{html}"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_html_raw_html() {
+    let input = "<div>\n<b>hi</b>\n</div>\n\n<details>\nmore\n</details>";
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert!(res.unwrap().starts_with("<div>\n<b>hi</b>\n</div>"));
+
+    let mut t = HtmlRenderer::default()
+        .escape_raw_html(true)
+        .allow_html_tag("DETAILS");
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(
+        html.starts_with("<p>&lt;div&gt;<br>&lt;b&gt;hi&lt;/b&gt;<br>&lt;/div&gt;</p>"),
+        "{html}"
+    );
+    assert!(html.contains("<details>\nmore\n</details>"), "{html}");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_html_raw_html_nested_tags() {
+    let mut t = HtmlRenderer::default()
+        .escape_raw_html(true)
+        .allow_html_tag("div")
+        .allow_html_tag("b");
+    let kept = |t: &mut HtmlRenderer, input: &str| {
+        let res = transform_markdown_string(input.to_string(), t);
+        assert!(res.is_ok(), "Error on transformation: {res:?}");
+        res.unwrap().starts_with(input)
+    };
+    assert!(kept(
+        &mut t,
+        "<div class=\"a\">\n<b title='x > y'>hi</b> 1 < 2\n</div>"
+    ));
+    assert!(!kept(&mut t, "<div>\n<script>alert(1)</script>\n</div>"));
+    assert!(!kept(
+        &mut t,
+        "<div>\n<b onclick=\"alert(1)\">hi</b>\n</div>"
+    ));
+    assert!(!kept(
+        &mut t,
+        "<div>\n<b style=x ONload=alert(1)>hi</b>\n</div>"
+    ));
+    assert!(!kept(
+        &mut t,
+        "<div>\n<a href=\" java\tscript:alert(1)\">hi</a>\n</div>"
+    ));
+    t = t.allow_html_tag("a");
+    assert!(!kept(
+        &mut t,
+        "<div>\n<a href=\" java\tscript:alert(1)\">hi</a>\n</div>"
+    ));
+    assert!(kept(
+        &mut t,
+        "<div>\n<a href=\"https://a.com\">hi</a>\n</div>"
+    ));
+    assert!(!kept(
+        &mut t,
+        "<div>\n<a href=\"&#106;avascript:alert(1)\">hi</a>\n</div>"
+    ));
+    assert!(!kept(
+        &mut t,
+        "<div>\n<a href=\"java&Tab;script&colon;alert(1)\">hi</a>\n</div>"
+    ));
+    assert!(!kept(
+        &mut t,
+        "<div>\n<a href='&#x76;bscript:msgbox(1)'>hi</a>\n</div>"
+    ));
+}
+
+#[test]
+fn test_html_unsafe_urls() {
+    let input = "[x](javascript:alert(1)) [y](JavaScript:alert(1)) \
+        ![z](data:image/png) [ok](https://a.com)";
+    for escaping in [
+        HtmlEscaping::Minimal,
+        HtmlEscaping::Strict,
+        HtmlEscaping::Attribute,
+    ] {
+        let mut t = HtmlRenderer::default().escaping(escaping);
+        let res = transform_markdown_string(input.to_string(), &mut t);
+        assert!(res.is_ok(), "Error on transformation: {res:?}");
+        let html = res.unwrap();
+        assert_eq!(html.matches("<a href").count(), 1, "{html}");
+        assert!(!html.contains("<img"), "{html}");
+        assert!(html.starts_with("<p>x y z <a href="), "{html}");
+    }
+}
+
+#[cfg(feature = "reflinks")]
+#[test]
+fn test_html_unsafe_reflink() {
+    let mut t = HtmlRenderer::default().escaping(HtmlEscaping::Strict);
+    let input = "[r][s]\n\n[s]: VBScript:msgbox(1)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert!(res.unwrap().starts_with("<p>r</p>"));
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_html_style_nonce() {
+    let input = "# Title\n\nSome `code`\n\n| a |\n|:-:|\n| b |";
+    let mut t = HtmlRenderer::default().style_nonce("r4nd0m");
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    let (body, stylesheet) = html.split_once("<style nonce=\"r4nd0m\">").unwrap();
+    assert!(!body.contains("style="), "{body}");
+    assert!(body.starts_with("<h1>Title</h1>"), "{body}");
+    assert!(
+        body.contains("<th class=\"md-align-center\">a</th>"),
+        "{body}"
+    );
+    assert!(
+        stylesheet.contains(".md-align-center { text-align:center; }"),
+        "{stylesheet}"
+    );
+    assert!(stylesheet.ends_with("</style>"), "{stylesheet}");
+
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert!(!res.unwrap().contains("<style"));
+    assert!(t
+        .stylesheet()
+        .contains(".md-alert-note { border-color:#0969da; }"));
+}
+
+#[test]
+fn test_html_responsive_image() {
+    let mut t = HtmlRenderer::default();
+    let options = TransformOptions::default().image_resolver(|url| {
+        vec![
+            crate::ImageCandidate::new(format!("{url}?w=480"), 480),
+            crate::ImageCandidate::new(format!("{url}?w=960"), 960),
+        ]
+    });
+    let input = "![A cat](cat.png)";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(
+        html.contains("<img src=\"cat.png\" alt=\"A cat\" srcset=\"cat.png?w=480 480w, cat.png?w=960 960w\" loading=\"lazy\">"),
+        "{html}"
+    );
+}
+
+#[test]
+fn test_html_permalinks() {
+    let mut t = HtmlRenderer::default().permalinks("#");
+    let input = "# Getting *started*\n\nText\n\n## Getting started";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(
+        html.starts_with("<h1 id=\"getting-started\">Getting <em>started</em> <a class=\"anchor\" href=\"#getting-started\">#</a></h1>"),
+        "{html}"
+    );
+    assert!(html.contains("<h2 id=\"getting-started-1\">"), "{html}");
+    assert!(html.contains("href=\"#getting-started-1\""), "{html}");
+}
+
+#[cfg(feature = "image-tags")]
+#[test]
+fn test_html_figure() {
+    let mut t = HtmlRenderer::default();
+    let input = "![alt](img.png)[width: 300, caption: \"Cap\"]";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "<figure><img src=\"img.png\" alt=\"alt\" width=\"300\"><figcaption>Cap</figcaption></figure>"
+    );
+
+    let input = "See ![a](a.png)[caption: \"Tom & Jerry\"] below";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "<p>See </p><figure><img src=\"a.png\" alt=\"a\"><figcaption>Tom &amp; Jerry</figcaption></figure><p> below</p>"
+    );
+}
@@ -0,0 +1,96 @@
+#[cfg(not(feature = "dialect-v1"))]
+use crate::transformers::MarkdownRenderer;
+use crate::{
+    transform_markdown_string, AttrValue, ImageTagAudit, ImageTagSchema, ImageTags,
+    MarkdownTransformer, TransformOptions,
+};
+
+#[test]
+fn test_image_tags_listing() {
+    let mut audit = ImageTagAudit::default().schema(ImageTagSchema::default().tag("credit"));
+    let input = "# Title\n\n![a](a.png)[width: 20, credit: \"Bob Smith\"]\n\n- ![b](b.png)[caption: \"B\", credit: Alice, licence: MIT]";
+    let res = audit.add_document("docs/a.md", input);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+
+    let tags = audit.tags();
+    assert_eq!(tags.len(), 5);
+    assert_eq!(tags[1].key, "credit");
+    assert_eq!(tags[1].value, "Bob Smith");
+    assert_eq!((tags[1].span.line, tags[1].span.column), (3, 24));
+
+    let keys = audit.keys();
+    assert_eq!(keys.get("credit"), Some(&2));
+    assert_eq!(keys.get("licence"), Some(&1));
+    assert!(!keys.contains_key("width") && !keys.contains_key("caption"));
+
+    let issues = audit.check();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].tag.key, "licence");
+    assert_eq!(issues[0].tag.file, "docs/a.md");
+    assert_eq!(issues[0].reason, "unknown tag");
+}
+
+#[test]
+fn test_image_tags_invalid_document() {
+    let mut audit = ImageTagAudit::default();
+    assert!(audit.add_document("a.md", "```\nunclosed").is_err());
+
+    let options = TransformOptions::default().parse_mode(crate::ParseMode::Lenient);
+    let mut audit = ImageTagAudit::default().options(options);
+    let res = audit.add_document("a.md", "```\nunclosed\n\n![a](a.png)[kind: photo]");
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+}
+
+#[test]
+fn test_image_tags_typed_values() {
+    #[derive(Default)]
+    pub struct Values {
+        values: Vec<Option<AttrValue>>,
+    }
+    impl MarkdownTransformer for Values {
+        fn transform_image(&mut self, alt: String, _url: String, add_tags: ImageTags) -> String {
+            for key in ["lazy", "width", "zoom", "title", "count", "missing"] {
+                self.values.push(add_tags.value(key));
+            }
+            alt
+        }
+    }
+    let mut t = Values::default();
+    let input = "![a](a.png)[lazy: false, width: 300, zoom: 2x, title: \"12\", count: 4]";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        t.values,
+        [
+            Some(AttrValue::Bool(false)),
+            Some(AttrValue::Int(300)),
+            Some(AttrValue::Str("2x".to_string())),
+            Some(AttrValue::Str("12".to_string())),
+            Some(AttrValue::Int(4)),
+            None,
+        ]
+    );
+    assert_eq!(t.values[1].as_ref().and_then(AttrValue::as_int), Some(300));
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_image_tags_signed_values() {
+    let input = "![a](a.png)[offset: -2, shift: +3]\n\nText";
+    let mut audit = ImageTagAudit::default();
+    let res = audit.add_document("a.md", input);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let values = audit
+        .tags()
+        .iter()
+        .map(|tag| (tag.key.as_str(), AttrValue::parse(&tag.value)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        [("offset", AttrValue::Int(-2)), ("shift", AttrValue::Int(3))]
+    );
+
+    let res = transform_markdown_string(input.to_string(), &mut MarkdownRenderer::default());
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
@@ -0,0 +1,197 @@
+use crate::{transform_inline_markdown, Inline, InlineTransformer, ListItemInfo};
+#[cfg(not(feature = "dialect-v1"))]
+use crate::{transform_inline_markdown_with_options, Alignment, TransformOptions};
+
+#[derive(Default)]
+pub struct Collector {
+    blocks: Vec<(String, Vec<Inline>)>,
+}
+
+impl InlineTransformer for Collector {
+    fn transform_header(&mut self, level: usize, content: Vec<Inline>) -> String {
+        self.blocks.push((format!("h{level}"), content));
+        String::new()
+    }
+    fn transform_paragraph(&mut self, content: Vec<Inline>) -> String {
+        self.blocks.push(("p".to_string(), content));
+        String::new()
+    }
+    fn transform_quote(&mut self, content: Vec<Inline>) -> String {
+        self.blocks.push(("quote".to_string(), content));
+        String::new()
+    }
+    fn transform_list_element(
+        &mut self,
+        content: Vec<Inline>,
+        _blocks: Vec<String>,
+        info: ListItemInfo,
+    ) -> String {
+        self.blocks.push((format!("li{}", info.index), content));
+        String::new()
+    }
+}
+
+fn text(t: &str) -> Inline {
+    Inline::Text(t.to_string())
+}
+
+#[test]
+fn test_inline_runs() {
+    let mut t = Collector::default();
+    let input = "# Title **bold *both***\n\nSome `code` and [a *link*](url)\nnext line\n\n> first\n> second\n\n- item ~~old~~";
+    let res = transform_inline_markdown(input, &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        t.blocks,
+        vec![
+            (
+                "h1".to_string(),
+                vec![
+                    text("Title "),
+                    Inline::Bold(vec![text("bold "), Inline::Italic(vec![text("both")])])
+                ]
+            ),
+            (
+                "p".to_string(),
+                vec![
+                    text("Some "),
+                    Inline::Code("code".to_string()),
+                    text(" and "),
+                    Inline::Link {
+                        text: vec![text("a "), Inline::Italic(vec![text("link")])],
+                        url: "url".to_string()
+                    },
+                    text(" next line"),
+                ]
+            ),
+            (
+                "quote".to_string(),
+                vec![text("first"), Inline::LineBreak, text("second")]
+            ),
+            (
+                "li0".to_string(),
+                vec![text("item "), Inline::Strike(vec![text("old")])]
+            ),
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_inline_reflinks() {
+    let mut t = Collector::default();
+    let input = "See [the docs][docs]\n\n[docs]: https://docs.rs";
+    let res = transform_inline_markdown(input, &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        t.blocks,
+        vec![(
+            "p".to_string(),
+            vec![
+                text("See "),
+                Inline::Link {
+                    text: vec![text("the docs")],
+                    url: "https://docs.rs".to_string()
+                }
+            ]
+        )]
+    );
+}
+
+#[test]
+fn test_inline_default_hooks() {
+    pub struct Plain;
+    impl InlineTransformer for Plain {}
+    let input = "# Title\n\nSome **bold** text\n\n- a\n- b";
+    let res = transform_inline_markdown(input, &mut Plain);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Title\n\nSome bold text\n\na\nb");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[derive(Default)]
+struct Blocks {
+    codeblocks: Vec<(Option<String>, Vec<String>)>,
+    cells: Vec<Vec<Inline>>,
+    headers: Vec<usize>,
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+impl InlineTransformer for Blocks {
+    fn transform_header(&mut self, level: usize, _content: Vec<Inline>) -> String {
+        self.headers.push(level);
+        String::new()
+    }
+    fn transform_codeblock(
+        &mut self,
+        lang: Option<String>,
+        flags: Vec<String>,
+        _code: String,
+    ) -> String {
+        self.codeblocks.push((lang, flags));
+        String::new()
+    }
+    fn transform_table(
+        &mut self,
+        header: Vec<Vec<Inline>>,
+        _alignments: Vec<Alignment>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    ) -> String {
+        self.cells.extend(header);
+        self.cells.extend(rows.into_iter().flatten());
+        String::new()
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_inline_options() {
+    let mut t = Blocks::default();
+    let input = "# Title\n\n```rust,ignore\nfn main() {}\n```";
+    let options = TransformOptions::default().heading_offset(1);
+    let res = transform_inline_markdown_with_options(input, &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(t.headers, [2]);
+    assert_eq!(
+        t.codeblocks,
+        [(Some("rust".to_string()), vec!["ignore".to_string()])]
+    );
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_inline_image_tags() {
+    let mut t = Collector::default();
+    let input = "![logo](logo.png)[width: 20, caption: \"The logo\"]";
+    let res = transform_inline_markdown(input, &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    use crate::ImageTags;
+
+    let tags = ImageTags::from_iter([
+        ("width".to_string(), "20".to_string()),
+        ("caption".to_string(), "The logo".to_string()),
+    ]);
+    let image = Inline::Image {
+        alt: "logo".to_string(),
+        url: "logo.png".to_string(),
+        tags,
+    };
+    assert_eq!(t.blocks, vec![("p".to_string(), vec![image])]);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_inline_table_cells() {
+    let mut t = Blocks::default();
+    let res = transform_inline_markdown("| **a** | b |\n|---|---|\n| `c` | d |", &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        t.cells,
+        [
+            vec![Inline::Bold(vec![text("a")])],
+            vec![text("b")],
+            vec![Inline::Code("c".to_string())],
+            vec![text("d")],
+        ]
+    );
+}
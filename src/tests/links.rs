@@ -0,0 +1,72 @@
+use crate::{extract_links, LinkKind};
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_extract_links() {
+    let input = "See [the **docs**](/docs#intro), https://example.com.\n\n![logo](img/logo.png) [![badge](b.svg)](#top)";
+    let res = extract_links(input);
+    assert!(res.is_ok(), "Error on link extraction: {res:?}");
+    let links = res.unwrap();
+    let summary = links
+        .iter()
+        .map(|l| (l.text.as_str(), l.url.as_str(), l.kind, l.span.line))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("the docs", "/docs#intro", LinkKind::Internal, 1),
+            (
+                "https://example.com",
+                "https://example.com",
+                LinkKind::External,
+                1
+            ),
+            ("logo", "img/logo.png", LinkKind::Internal, 3),
+            ("badge", "b.svg", LinkKind::Internal, 3),
+            ("badge", "#top", LinkKind::Anchor, 3),
+        ]
+    );
+    assert_eq!(links[1].span.start, 33);
+}
+
+#[test]
+fn test_extract_links_in_heading() {
+    let res = extract_links("# See [x](https://a.b)\n\n> quote [y](z)");
+    assert!(res.is_ok(), "Error on link extraction: {res:?}");
+    let links = res.unwrap();
+    let summary = links
+        .iter()
+        .map(|l| (l.text.as_str(), l.url.as_str(), l.span.line))
+        .collect::<Vec<_>>();
+    assert_eq!(summary, vec![("x", "https://a.b", 1), ("y", "z", 3)]);
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_extract_reflinks() {
+    let input = "[a][site] and [b][missing]\n\n[site]: https://site.org \"Site\"";
+    let res = extract_links(input);
+    assert!(res.is_ok(), "Error on link extraction: {res:?}");
+    let links = res.unwrap();
+    assert_eq!(links.len(), 1, "{links:?}");
+    assert_eq!(links[0].text, "a");
+    assert_eq!(links[0].url, "https://site.org");
+    assert_eq!(links[0].kind, LinkKind::External);
+    assert_eq!((links[0].span.start, links[0].span.end), (0, 9));
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_extract_links_angle_autolink() {
+    let res = extract_links("Go to <https://example.org>.");
+    assert!(res.is_ok(), "Error on link extraction: {res:?}");
+    let links = res.unwrap();
+    let summary = links
+        .iter()
+        .map(|l| (l.text.as_str(), l.url.as_str(), l.span.start))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![("https://example.org", "https://example.org", 7)]
+    );
+}
@@ -0,0 +1,213 @@
+use crate::transformers::MarkdownRenderer;
+use crate::{transform_markdown_string, transform_markdown_string_with_options};
+use crate::{ParseMode, TransformOptions};
+
+#[test]
+fn test_render_markdown() {
+    let mut t = MarkdownRenderer::default();
+    let input = "# Title\n\nSome **bold** and *italic* text\n\n- a\n- `b`\n\n> quote\n\n```rust\nlet a = 1;\n```";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+fn test_render_paragraph_lang() {
+    let mut t = MarkdownRenderer::default();
+    let input = "{lang=fr} Bonjour *le* monde\n\n{lang=de}\nHallo";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "{lang=fr} Bonjour *le* monde\n\n{lang=de} Hallo"
+    );
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_render_links_preserved() {
+    let mut t = MarkdownRenderer::default();
+    let input = "A [link](https://a.com) and [ref][r]\n\n[r]: https://b.com";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_render_links_to_reference() {
+    use crate::transformers::LinkStyle;
+
+    let mut t = MarkdownRenderer::default().link_style(LinkStyle::Reference);
+    let input =
+        "A [link](https://a.com), [ref][1] and [again](https://a.com)\n\n[1]: https://b.com";
+    let output = "A [link][2], [ref][1] and [again][2]\n\n[2]: https://a.com\n[1]: https://b.com";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_render_links_to_inline() {
+    use crate::transformers::LinkStyle;
+
+    let mut t = MarkdownRenderer::default().link_style(LinkStyle::Inline);
+    let input = "A [link](https://a.com) and [ref][r]\n\n[r]: https://b.com";
+    let output = "A [link](https://a.com) and [ref](https://b.com)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_render_lists_normalized() {
+    let mut t = MarkdownRenderer::default().bullet('*');
+    let input = "- a\n+ b\n* c\n\n3. one\n3) two\n3. three";
+    let output = "* a\n* b\n* c\n\n3. one\n4. two\n5. three";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+
+    let mut t = MarkdownRenderer::default().renumber_lists(true);
+    let input = "7. one\n7. two";
+    let output = "1. one\n2. two";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[test]
+fn test_render_wrapped() {
+    let mut t = MarkdownRenderer::default().wrap(20);
+    let input =
+        "Some long paragraph containing a [link to a site](https://example.com) and `some code`";
+    let output =
+        "Some long paragraph\ncontaining a\n[link to a site](https://example.com)\nand `some code`";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_render_table() {
+    let mut t = MarkdownRenderer::default();
+    let input = "|a|long header|\n|-|-:|\n|some text|1|";
+    let output =
+        "| a         | long header |\n| --------- | ----------: |\n| some text |           1 |";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_render_codeblock_flags() {
+    let mut t = MarkdownRenderer::default();
+    let input = "```rust ignore\nlet a = 1;\n```";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "```rust,ignore\nlet a = 1;\n```");
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_render_figure() {
+    let mut t = MarkdownRenderer::default();
+    let input = "![a](u)[width: 20, caption: \"A nice view\"]\n\n![b](v)[caption: c]";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_render_image_tags_order() {
+    let mut t = MarkdownRenderer::default();
+    let input = "![a](u)[width: 20, credit: Bob, align: left]";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_render_reference_title() {
+    let mut t = MarkdownRenderer::default();
+    let input = "A [ref][r]\n\n[r]: https://b.com \"The B site\"";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_render_list_blocks() {
+    let mut t = MarkdownRenderer::default();
+    let input =
+        "- a\n\n  second\n- b\n\n  ```rust\n  let a = 1;\n  ```\n\n1. c\n\n   - nested\n\nend";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_render_task_list() {
+    let mut t = MarkdownRenderer::default();
+    let input = "- [x] done\n- [ ] todo\n\n  with text\n- plain\n\n1. [ ] first";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_render_alert() {
+    let mut t = MarkdownRenderer::default();
+    let input = "> [!NOTE]\n> Some **note**  \n> on two lines\n\n> quote";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+fn test_render_autolink() {
+    let mut t = MarkdownRenderer::default();
+    let input = "Bare (https://example.com/a_(b)). and [text](https://example.com)";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+fn test_render_invalid() {
+    let mut t = MarkdownRenderer::default();
+    let options = TransformOptions::default().parse_mode(ParseMode::Lenient);
+    let input = "# Title\n\nSome `open code\n\nend **bold**";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+fn test_render_twice() {
+    for input in [
+        "```\n```",
+        "```rust\n```",
+        "```rust\nlet a = 1;\n```",
+        "text  \nmore",
+        "**a**  \n*b*  \nc",
+        "> a  \n> b",
+    ] {
+        let once = transform_markdown_string(input.to_string(), &mut MarkdownRenderer::default());
+        assert!(once.is_ok(), "Error on transformation: {once:?}");
+        let once = once.unwrap();
+        assert_eq!(once, input);
+        let twice = transform_markdown_string(once.clone(), &mut MarkdownRenderer::default());
+        assert!(twice.is_ok(), "Error on transformation: {twice:?}");
+        assert_eq!(twice.unwrap(), once);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::mdast_json;
+
+#[test]
+fn test_mdast_json() {
+    let res = mdast_json("# Hi\n\nA **b**\n");
+    assert!(res.is_ok(), "Error on conversion: {res:?}");
+    let position = |start: (usize, usize, usize), end: (usize, usize, usize)| {
+        format!(
+            "\"position\":{{\"start\":{{\"line\":{},\"column\":{},\"offset\":{}}},\"end\":{{\"line\":{},\"column\":{},\"offset\":{}}}}}",
+            start.0, start.1, start.2, end.0, end.1, end.2
+        )
+    };
+    let expected = format!(
+        concat!(
+            "{{\"type\":\"root\",\"children\":[",
+            "{{\"type\":\"heading\",\"depth\":1,\"children\":[",
+            "{{\"type\":\"text\",\"value\":\"Hi\",{}}}],{}}},",
+            "{{\"type\":\"paragraph\",\"children\":[",
+            "{{\"type\":\"text\",\"value\":\"A \",{}}},",
+            "{{\"type\":\"strong\",\"children\":[{{\"type\":\"text\",\"value\":\"b\",{}}}],{}}}",
+            "],{}}}],{}}}"
+        ),
+        position((1, 3, 2), (1, 5, 4)),
+        position((1, 1, 0), (1, 5, 4)),
+        position((3, 1, 6), (3, 3, 8)),
+        position((3, 5, 10), (3, 6, 11)),
+        position((3, 3, 8), (3, 8, 13)),
+        position((3, 1, 6), (3, 8, 13)),
+        position((1, 1, 0), (4, 1, 14)),
+    );
+    assert_eq!(res.unwrap(), expected);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_mdast_json_blocks() {
+    let input = "> [!NOTE]\n> a \"b\"\n\n```rust,ignore\nlet a;\n```\n\n3. c\n\n  d\n\n| a |\n|:-:|\n| b |\n\n---";
+    let res = mdast_json(input);
+    assert!(res.is_ok(), "Error on conversion: {res:?}");
+    let json = res.unwrap();
+    for fragment in [
+        "{\"type\":\"blockquote\",\"children\":[{\"type\":\"paragraph\",\"children\":[{\"type\":\"text\",\"value\":\"[!NOTE]\\na \\\"b\\\"\"",
+        "{\"type\":\"code\",\"lang\":\"rust\",\"meta\":\"ignore\",\"value\":\"let a;\"",
+        "{\"type\":\"list\",\"ordered\":true,\"start\":3,\"spread\":false,\"children\":[{\"type\":\"listItem\"",
+        "{\"type\":\"table\",\"align\":[\"center\"],\"children\":[{\"type\":\"tableRow\"",
+        "{\"type\":\"thematicBreak\",\"position\"",
+    ] {
+        assert!(json.contains(fragment), "{fragment} not found in {json}");
+    }
+}
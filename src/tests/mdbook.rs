@@ -0,0 +1,66 @@
+use crate::{MarkdownTransformer, MdbookPreprocessor, Warning};
+
+struct Shout;
+impl MarkdownTransformer for Shout {
+    fn transform_text(&mut self, text: String) -> String {
+        text.to_uppercase()
+    }
+}
+
+const INPUT: &str = r#"[
+    {"root": "/book", "config": {}, "renderer": "html", "mdbook_version": "0.4.40"},
+    {"sections": [
+        {"Chapter": {
+            "name": "Intro",
+            "content": "hello ![](a.png)",
+            "number": [1],
+            "sub_items": [
+                {"Chapter": {"name": "Sub", "content": "sub", "number": [1, 1], "sub_items": [], "path": "intro/sub.md", "source_path": "intro/sub.md", "parent_names": ["Intro"]}}
+            ],
+            "path": "intro.md",
+            "source_path": "intro.md",
+            "parent_names": []
+        }},
+        "Separator",
+        {"PartTitle": "part"}
+    ], "__non_exhaustive": null}
+]"#;
+
+#[test]
+fn test_mdbook_preprocess() {
+    let mut preprocessor = MdbookPreprocessor::new(Shout);
+    let mut output = vec![];
+    let res = preprocessor.preprocess(INPUT.as_bytes(), &mut output);
+    assert!(res.is_ok(), "Error on preprocessing: {res:?}");
+    let warnings = res.unwrap();
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(
+        matches!(&warnings[0], (path, Warning::MissingAltText { url, .. }) if path == "intro.md" && url == "a.png")
+    );
+
+    let book: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let intro = &book["sections"][0]["Chapter"];
+    assert_eq!(intro["content"], "HELLO ");
+    assert_eq!(intro["sub_items"][0]["Chapter"]["content"], "SUB");
+    assert_eq!(intro["number"], serde_json::json!([1]));
+    assert_eq!(book["sections"][1], "Separator");
+    assert_eq!(book["sections"][2]["PartTitle"], "part");
+}
+
+#[test]
+fn test_mdbook_invalid_input() {
+    let mut preprocessor = MdbookPreprocessor::new(Shout);
+    let res = preprocessor.preprocess("{}".as_bytes(), vec![]);
+    assert!(res.is_err());
+    let res = preprocessor.preprocess("[{}, ".as_bytes(), vec![]);
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_mdbook_supports() {
+    let preprocessor = MdbookPreprocessor::new(Shout);
+    assert!(preprocessor.supports("pdf"));
+    let preprocessor = preprocessor.renderer("html");
+    assert!(preprocessor.supports("html"));
+    assert!(!preprocessor.supports("pdf"));
+}
@@ -1,16 +1,80 @@
-// TODO    Generate parametric tests
+// NOTE    The free functions are deprecated in favor of `Pipeline`, but still tested
+#![allow(deprecated)]
 
+mod anchors;
+mod audit;
+mod batch;
+// NOTE    The changelogs list their changes in ordered lists, not part of the dialect 1
+#[cfg(not(feature = "dialect-v1"))]
+mod changelog;
+mod chat;
+// NOTE    Citations are not part of the dialect 1
+#[cfg(not(feature = "dialect-v1"))]
+mod citations;
+mod codeblock;
+#[cfg(feature = "compare")]
+mod compare;
+mod context;
+// NOTE    The traces are recorded with the default features, which change the grammar
+#[cfg(all(
+    feature = "reflinks",
+    feature = "image-tags",
+    not(feature = "dialect-v1")
+))]
+mod corpus;
+// NOTE    Cross-references are not part of the dialect 1
+#[cfg(not(feature = "dialect-v1"))]
+mod crossref;
+#[cfg(feature = "details")]
+mod details;
+mod dialect;
+mod document;
+#[cfg(feature = "docx")]
+mod docx;
+mod drafts;
+mod email_html;
+mod events;
+mod footnotes;
 mod headers;
+mod html;
+#[cfg(feature = "image-tags")]
+mod image_tags;
+mod inline;
+mod links;
+mod markdown_renderer;
+mod mdast;
+#[cfg(feature = "mdbook")]
+mod mdbook;
+mod numbering;
+mod options;
+mod outline;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod passthrough;
 mod peek;
+mod pipeline;
+mod plain_text;
+mod reflow;
+mod rewrite;
+mod ssml;
+mod state;
+mod syntax;
+mod table;
+#[cfg(all(feature = "table-spans", not(feature = "dialect-v1")))]
+mod table_spans;
+#[cfg(feature = "tabs")]
+mod tabs;
+mod trace;
 mod transform;
-
-use pest::Parser;
-
-use crate::MarkdownParser;
-use crate::Rule;
+mod visitor;
+mod warnings;
 
 #[test]
+#[cfg(feature = "reflinks")]
 fn test_parse_markdown_input() {
+    use crate::{MarkdownParser, Rule};
+    use pest::Parser;
+
     let input = "
 # Je suis un titre h1
 ## Je suis un titre h2
@@ -0,0 +1,143 @@
+use crate::{transform_markdown_string, ElementContext, ElementNumber, MarkdownTransformer};
+#[cfg(not(feature = "dialect-v1"))]
+use crate::{
+    transform_markdown_string_with_options, Alignment, CounterKind, CounterReset, TransformOptions,
+};
+
+#[cfg(not(feature = "dialect-v1"))]
+#[derive(Default)]
+struct Captions {
+    number: Option<ElementNumber>,
+    peeked: Vec<ElementNumber>,
+    transformed: Vec<ElementNumber>,
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+impl Captions {
+    fn caption(&mut self, text: String) -> String {
+        let number = self.number.expect("Element not numbered");
+        self.transformed.push(number);
+        format!("{} {number}: {text}", number.kind.label())
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+impl MarkdownTransformer for Captions {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.number = ctx.number();
+    }
+    fn peek_codeblock(&mut self, _language: Option<String>, _flags: Vec<String>, _text: String) {
+        self.peeked.extend(self.number);
+    }
+    fn peek_table(
+        &mut self,
+        _header: Vec<String>,
+        _alignments: Vec<Alignment>,
+        _rows: Vec<Vec<String>>,
+    ) {
+        self.peeked.extend(self.number);
+    }
+    fn transform_header(&mut self, _level: usize, text: String) -> String {
+        text + "\n"
+    }
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        _flags: Vec<String>,
+        _text: String,
+    ) -> String {
+        self.caption(language.unwrap_or_default()) + "\n"
+    }
+    fn transform_diagram(&mut self, kind: crate::DiagramKind, _source: String) -> String {
+        self.caption(kind.language().to_string()) + "\n"
+    }
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        _alignments: Vec<Alignment>,
+        _rows: Vec<Vec<String>>,
+    ) -> String {
+        self.caption(header.join(",")) + "\n"
+    }
+    fn transform_paragraph(&mut self, text: String) -> String {
+        text
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+const INPUT: &str = "# One
+
+```rust
+a
+```
+
+| x | y |
+|---|---|
+| 1 | 2 |
+
+```mermaid
+graph
+```
+
+# Two
+
+```csvtable
+z,w
+3,4
+```
+
+```
+b
+```
+";
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_numbering_whole_document() {
+    let mut t = Captions::default();
+    let res = transform_markdown_string(INPUT.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "One\nListing 1: rust\nTable 1: x,y\nFigure 1: mermaid\nTwo\nTable 2: z,w\nListing 2: \n"
+    );
+    assert_eq!(t.peeked, t.transformed);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_numbering_reset_by_headers() {
+    let options = TransformOptions::default()
+        .reset_counter(CounterKind::Listing, CounterReset::Header(1))
+        .reset_counter(CounterKind::Table, CounterReset::Header(1));
+    let mut t = Captions::default();
+    let res = transform_markdown_string_with_options(INPUT.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "One\nListing 1.1: rust\nTable 1.1: x,y\nFigure 1: mermaid\nTwo\nTable 2.1: z,w\nListing 2.1: \n"
+    );
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_numbering_figures() {
+    #[derive(Default)]
+    struct Figures {
+        number: Option<ElementNumber>,
+    }
+    impl MarkdownTransformer for Figures {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.number = ctx.number();
+        }
+        fn transform_figure(&mut self, image: String, caption: String) -> String {
+            format!("[{image}] Figure {}: {caption}", self.number.unwrap())
+        }
+    }
+    let mut t = Figures::default();
+
+    let input = "![a](a.png)[caption: First]\n\n![b](b.png)\n\n![c](c.png)[caption: Second]";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "[a] Figure 1: Firstb[c] Figure 2: Second");
+}
@@ -0,0 +1,426 @@
+use crate::transformers::MarkdownRenderer;
+use crate::{
+    transform_markdown_string, transform_markdown_string_with_options, Directive, LineEnding,
+    LinkKind, MarkdownTransformer, ParagraphInfo, Rule, TextDirection, TransformOptions,
+};
+#[cfg(not(feature = "dialect-v1"))]
+use crate::{ElementKind, EmptyElements, UnknownVariables};
+
+#[test]
+fn test_heading_offset() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            format!("h{level}: {text}")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let options = TransformOptions::default().heading_offset(1);
+    let res = transform_markdown_string_with_options("# header".to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h2: header");
+
+    let options = TransformOptions::default().heading_offset(-2);
+    let res = transform_markdown_string_with_options("### header".to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h1: header");
+
+    let res = transform_markdown_string_with_options("# header".to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h1: header");
+}
+
+#[test]
+fn test_max_heading_level() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn peek_header(&mut self, level: usize, _text: String) {
+            assert!(level <= 4);
+        }
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            format!("h{level}: {text}")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let options = TransformOptions::default()
+        .heading_offset(2)
+        .max_heading_level(4);
+    let res = transform_markdown_string_with_options("# a\n### b".to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h3: ah4: b");
+}
+
+#[test]
+fn test_link_classification() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_link(&mut self, text: String, _url: String, kind: LinkKind) -> String {
+            format!("{text}={kind:?}")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let options = TransformOptions::default().site_host("example.com");
+    let input =
+        "[a](https://example.com/post) [b](https://rust-lang.org) [c](#intro) [d](other/page.html)";
+    let output = "a=Internal b=External c=Anchor d=Internal";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+
+    let res = transform_markdown_string("[a](https://example.com/post)".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "a=External");
+}
+
+#[test]
+fn test_link_kind_classify() {
+    assert_eq!(
+        LinkKind::classify("mailto:me@example.com", Some("example.com")),
+        LinkKind::External
+    );
+    assert_eq!(
+        LinkKind::classify("//cdn.net/lib.js", Some("example.com")),
+        LinkKind::External
+    );
+    assert_eq!(
+        LinkKind::classify("http://user@example.com:8080/", Some("example.com")),
+        LinkKind::Internal
+    );
+    assert_eq!(LinkKind::classify("/abs/path", None), LinkKind::Internal);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_shortcodes() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_shortcode(&mut self, name: String, args: Vec<String>) -> String {
+            format!("<{name}:{}>", args.join("|"))
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input =
+        "Watch {{youtube abc123}}\nor {{ tweet \"some url\" 2 }} and {{unknown x}} {{ not closed";
+    let options = TransformOptions::default()
+        .shortcode("youtube")
+        .shortcode("tweet");
+    let output = "Watch <youtube:abc123> or <tweet:some url|2> and {{unknown x}} {{ not closed";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+
+    let res = transform_markdown_string("{{youtube abc123}}".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "{{youtube abc123}}");
+}
+
+#[test]
+fn test_directives() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_paragraph(&mut self, text: String) -> String {
+            format!("p({text})")
+        }
+        fn transform_comment(&mut self, text: String) -> String {
+            format!("c({text})")
+        }
+        fn transform_directive(&mut self, directive: Directive) -> String {
+            format!("[{}]", directive.name())
+        }
+        fn transform_raw_block(&mut self, source: String) -> String {
+            format!("raw({source})")
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "Intro\n\n<!-- mdtrans: skip-start -->\n\nSecret\n\n<!-- mdtrans: raw -->\n\n- hidden\n\n<!--mdtrans:skip-end-->\n<!-- mdtrans: raw -->\n**kept**\n\n<!-- other -->\n\nEnd";
+
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert!(
+        output.contains("c(mdtrans: skip-start)p(Secret)"),
+        "{output}"
+    );
+
+    let options = TransformOptions::default().directives(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "p(Intro)[skip-start][skip-end][raw]raw(**kept**)c(other)p(End)"
+    );
+    assert_eq!(Directive::from_comment(" mdtrans: skip "), None);
+}
+
+#[test]
+fn test_strip_comments() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        peeked: Vec<String>,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn peek_comment(&mut self, text: String) {
+            self.peeked.push(text);
+        }
+        fn transform_comment(&mut self, text: String) -> String {
+            format!("c({text})")
+        }
+        fn transform_directive(&mut self, directive: Directive) -> String {
+            format!("[{}]", directive.name())
+        }
+    }
+    let mut t = DummyTransform::default();
+    let input =
+        "Intro\n\n<!-- TODO: reword -->\n\n- a\n<!-- note -->\n\n<!-- mdtrans: raw -->\nEnd";
+
+    let options = TransformOptions::default().strip_comments(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "IntroaEnd");
+    assert_eq!(t.peeked, ["TODO: reword", "note", "mdtrans: raw"]);
+
+    let options = options.directives(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Introa[raw]End");
+
+    let mut t = MarkdownRenderer::default();
+    let options = TransformOptions::default().strip_comments(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Intro\n\n- a\n\nEnd");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_variables() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_text(&mut self, text: String) -> String {
+            text.replace('<', "&lt;")
+        }
+        fn transform_shortcode(&mut self, name: String, _args: Vec<String>) -> String {
+            format!("<{name}>")
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "# {{ page.title }}\n\nBy {{page.author}} {{toc}} {{ page.date }} {{ youtube x }}";
+
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "{{ page.title }}By {{page.author}} {{toc}} {{ page.date }} {{ youtube x }}"
+    );
+
+    let options = TransformOptions::default()
+        .shortcode("toc")
+        .variables([("page.title", "<Intro>"), ("page.author", "Ann")])
+        .variables([("toc", "unused")]);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "&lt;Intro>By Ann <toc> {{ page.date }} {{ youtube x }}"
+    );
+
+    let options = options.unknown_variables(UnknownVariables::Remove);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "&lt;Intro>By Ann <toc>  {{ youtube x }}");
+}
+
+#[test]
+fn test_rule_renderer() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_bold(&mut self, text: String) -> String {
+            format!("BOLD {text} BOLD")
+        }
+        fn transform_quote(&mut self, _text: String) -> String {
+            unreachable!("Quote rendering is overridden")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let options = TransformOptions::default().render_rule(Rule::quote, |raw, inners| {
+        format!("RAW({}) INNER({})", raw.trim(), inners.join("|"))
+    });
+    let input = "> a **b**\n> c";
+    let output = "RAW(> a **b**\n> c) INNER(a BOLD b BOLD|c)";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_element_renderer() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            format!("h{level}: {text}\n")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let options = TransformOptions::default()
+        .render_element(ElementKind::Header(2), |raw, _| {
+            format!("[{}]\n", raw.trim())
+        })
+        .render_element(ElementKind::ListItem, |_, inners| inners.concat())
+        .render_rule(Rule::list_element, |_, _| "*".to_string());
+    let input = "# a\n## b\n\n- x\n- y\n\n1. z";
+    let output = "h1: a\n[## b]\n*, *1z";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_empty_elements() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            format!("h{level}({text})")
+        }
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+            format!("a({text}|{url})")
+        }
+        fn transform_list(&mut self, elements: Vec<String>) -> String {
+            format!("ul({})", elements.join(","))
+        }
+        fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+            format!("ol{start}({})", elements.join(","))
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "#\n## \n\n- a\n-\n- b\n\n1. \n2.\n\nx [](u)";
+
+    let options = TransformOptions::default();
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h1()h2()ul(a,,b)ol1(,)x a(|u)");
+
+    let options = TransformOptions::default().empty_elements(EmptyElements::Skip);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "ul(a,b)x ");
+}
+
+#[test]
+fn test_text_direction() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn enter_paragraph_with_info(&mut self, info: ParagraphInfo) -> String {
+            format!("<{}>", info.direction.html())
+        }
+        fn transform_paragraph(&mut self, text: String) -> String {
+            format!("p({text})")
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "**שלום** world\n\n(12) hello مرحبا\n\n«مرحبا» world\n\n42";
+
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert!(!res.unwrap().contains('<'));
+
+    let options = TransformOptions::default().text_direction(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert!(output.contains("<rtl>p(שלום world)"), "{output}");
+    assert!(output.contains("<rtl>p(«مرحبا» world)"), "{output}");
+    assert!(output.contains("<ltr>p((12) hello مرحبا)"), "{output}");
+    assert!(output.contains("<ltr>p(42)"), "{output}");
+    assert_eq!(TextDirection::detect("- 12 hello"), TextDirection::Ltr);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_paragraph_lang() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) -> String {
+            format!("{:?}/{:?}({text})", info.lang, info.direction)
+        }
+        fn transform_paragraph(&mut self, text: String) -> String {
+            format!("p({text})")
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "{lang=fr} Bonjour\n\n{lang=he}\nשלום עולם\n\n{lang} Hello";
+
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert!(output.contains("Some(\"fr\")/Ltr(Bonjour)"), "{output}");
+    assert!(output.contains("Some(\"he\")/Ltr(שלום עולם)"), "{output}");
+    assert!(output.contains("p({lang} Hello)"), "{output}");
+
+    let options = TransformOptions::default().text_direction(true);
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert!(output.contains("Some(\"he\")/Rtl(שלום עולם)"), "{output}");
+    assert!(output.contains("None/Ltr({lang} Hello)"), "{output}");
+}
+
+#[test]
+fn test_line_ending() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_paragraph(&mut self, text: String) -> String {
+            format!("{text}\r\n")
+        }
+        fn transform_codeblock(
+            &mut self,
+            _language: Option<String>,
+            _flags: Vec<String>,
+            text: String,
+        ) -> String {
+            format!("{text}\n")
+        }
+    }
+    let input = "Text\n\n```\na\nb\n```\n\nEnd".to_string();
+
+    let res = transform_markdown_string(input.clone(), &mut DummyTransform);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Text\r\na\nb\nEnd\r\n");
+
+    let options = TransformOptions::default().line_ending(LineEnding::Crlf);
+    let res = transform_markdown_string_with_options(input.clone(), &mut DummyTransform, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Text\r\na\r\nb\r\nEnd\r\n");
+
+    let options = TransformOptions::default().line_ending(LineEnding::Lf);
+    let res = transform_markdown_string_with_options(input, &mut DummyTransform, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Text\na\nb\nEnd\n");
+}
+
+#[test]
+fn test_final_newline() {
+    let mut t = MarkdownRenderer::default();
+    let options = TransformOptions::default()
+        .final_newline(true)
+        .line_ending(LineEnding::Crlf);
+    let res =
+        transform_markdown_string_with_options("# Title\n\nText".to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "# Title\r\n\r\nText\r\n");
+
+    let res = transform_markdown_string_with_options("Text\n".to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert!(!res.unwrap().ends_with("\r\n\r\n"));
+
+    let res = transform_markdown_string_with_options(String::new(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "");
+}
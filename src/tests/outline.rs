@@ -0,0 +1,178 @@
+use crate::{
+    extract_section, outline, outline_with_options, Errcode, Heading, HeadingRewrite, SlugCharset,
+    SlugDedup, TransformOptions,
+};
+
+#[test]
+fn test_outline() {
+    let input = "# Intro\n\nText\n\n## Some **bold** part\n\n- a\n\n## Intro\n\n### Intro";
+    let res = outline(input);
+    assert!(res.is_ok(), "Error on outline: {res:?}");
+    let headings = res.unwrap();
+    let summary = headings
+        .iter()
+        .map(|h| (h.level, h.text.as_str(), h.slug.as_str(), h.span.line))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            (1, "Intro", "intro", 1),
+            (2, "Some bold part", "some-bold-part", 5),
+            (2, "Intro", "intro-1", 9),
+            (3, "Intro", "intro-2", 11),
+        ]
+    );
+    let Heading { span, .. } = &headings[1];
+    assert_eq!(&input[span.start..span.end], "## Some **bold** part");
+}
+
+#[test]
+fn test_outline_unique_slugs() {
+    let slugs = |input: &str| {
+        let res = outline(input);
+        assert!(res.is_ok(), "Error on outline: {res:?}");
+        res.unwrap().into_iter().map(|h| h.slug).collect::<Vec<_>>()
+    };
+    assert_eq!(slugs("# A\n\n# A\n\n# A-1"), ["a", "a-1", "a-1-1"]);
+    assert_eq!(
+        slugs("# A-1\n\n# A\n\n# A\n\n# A"),
+        ["a-1", "a", "a-2", "a-3"]
+    );
+}
+
+#[test]
+fn test_outline_slug_options() {
+    let input = "# Café Noël\n\n## Straße\n\n## Café Noël\n\n## 日本";
+    let slugs = |options: &TransformOptions| {
+        let res = outline_with_options(input, options);
+        assert!(res.is_ok(), "Error on outline: {res:?}");
+        res.unwrap().into_iter().map(|h| h.slug).collect::<Vec<_>>()
+    };
+
+    let options = TransformOptions::default();
+    assert_eq!(
+        slugs(&options),
+        ["café-noël", "straße", "café-noël-1", "日本"]
+    );
+
+    let options = TransformOptions::default()
+        .slug_charset(SlugCharset::Ascii)
+        .slug_dedup(SlugDedup::Hash);
+    let res = slugs(&options);
+    assert_eq!(res[..2], ["cafe-noel", "strasse"]);
+    assert!(
+        res[2].starts_with("cafe-noel-") && res[2].len() == 16,
+        "{res:?}"
+    );
+    assert_eq!(res[3], "");
+    assert_eq!(slugs(&options), res);
+
+    let options = TransformOptions::default().slug_dedup(SlugDedup::Error);
+    let res = outline_with_options(input, &options);
+    assert!(
+        matches!(&res, Err(Errcode::DuplicateSlug { slug, span }) if slug == "café-noël" && span.line == 5),
+        "{res:?}"
+    );
+}
+
+#[test]
+fn test_extract_section() {
+    let input = "# Intro\n\nText\n\n## Some **bold** part\n\n- a\n\n### Sub\n\nb\n\n## Intro\n\n### Intro\n";
+    let res = extract_section(input, "Some bold part");
+    assert!(res.is_ok(), "Error on extraction: {res:?}");
+    assert_eq!(
+        res.unwrap().as_deref(),
+        Some("## Some **bold** part\n\n- a\n\n### Sub\n\nb")
+    );
+
+    let res = extract_section(input, "intro-1");
+    assert!(res.is_ok(), "Error on extraction: {res:?}");
+    assert_eq!(res.unwrap().as_deref(), Some("## Intro\n\n### Intro"));
+
+    // NOTE    The first heading with the text is the one extracted
+    let res = extract_section(input, "Intro");
+    assert!(res.is_ok(), "Error on extraction: {res:?}");
+    assert_eq!(res.unwrap().as_deref(), Some(input.trim_end()));
+
+    assert!(matches!(extract_section(input, "Missing"), Ok(None)));
+    assert!(extract_section("```\nunclosed", "Intro").is_err());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_task_progress() {
+    use crate::task_progress;
+
+    let input = "- [x] a\n\n# Plan\n\nText\n\n## Build\n\n- [ ] b\n- [x] c\n  - [ ] d\n- e\n\n## Empty\n\n- f\n\n## Ship\n\n1. [ ] g";
+    let res = task_progress(input);
+    assert!(res.is_ok(), "Error on task progress: {res:?}");
+    let summary = res
+        .unwrap()
+        .into_iter()
+        .map(|p| (p.heading.map(|h| h.text), p.checked, p.unchecked))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            (None, 1, 0),
+            (Some("Build".to_string()), 1, 2),
+            (Some("Ship".to_string()), 0, 1),
+        ]
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_heading_rewrite() {
+    let input = concat!(
+        "# 2024 review\n\n",
+        "## 1.2.3 Setup *now*\n\n",
+        "- item\n\n  ### 4) Nested\n\n",
+        "###### 1.5x faster\n\n",
+        "```md\n# Code\n```\n",
+    );
+    let res = HeadingRewrite::default().shift(1).rewrite(input);
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        concat!(
+            "## 2024 review\n\n",
+            "### 1.2.3 Setup *now*\n\n",
+            "- item\n\n  #### 4) Nested\n\n",
+            "###### 1.5x faster\n\n",
+            "```md\n# Code\n```\n",
+        )
+    );
+
+    let res = HeadingRewrite::default()
+        .shift(-1)
+        .strip_numbers(true)
+        .rewrite(input);
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        concat!(
+            "# 2024 review\n\n",
+            "# Setup *now*\n\n",
+            "- item\n\n  ## Nested\n\n",
+            "##### 1.5x faster\n\n",
+            "```md\n# Code\n```\n",
+        )
+    );
+}
+
+#[test]
+fn test_heading_rewrite_section() {
+    let input = "# A\n\n## B\n\n### C\n\n## D\n\n### E\n";
+    let res = outline(input);
+    assert!(res.is_ok(), "Error on outline: {res:?}");
+    let headings = res.unwrap();
+    let section = headings[1].span.start..headings[3].span.start;
+    let res = HeadingRewrite::default()
+        .shift(-1)
+        .levels(3..=6)
+        .within(section)
+        .rewrite(input);
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(res.unwrap(), "# A\n\n## B\n\n## C\n\n## D\n\n### E\n");
+}
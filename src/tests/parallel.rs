@@ -0,0 +1,160 @@
+use crate::{
+    transform_markdown_string_parallel, transform_markdown_string_with_options, ElementContext,
+    MarkdownTransformer, Pipeline, TransformOptions,
+};
+
+#[derive(Clone, Default)]
+pub struct DummyTransform {
+    context: String,
+}
+
+impl MarkdownTransformer for DummyTransform {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        let span = ctx.span();
+        self.context = format!("{}@{}:{}", ctx.id(), span.line, span.start);
+    }
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        format!("<h{level} {}>{text}</h{level}>\n", self.context)
+    }
+    fn transform_paragraph(&mut self, text: String) -> String {
+        format!("<p {}>{text}</p>\n", self.context)
+    }
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<b {}>{text}</b>", self.context)
+    }
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        format!("<ul {}>{}</ul>\n", self.context, elements.join(""))
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_parallel_same_as_sequential() {
+    let mut input = String::new();
+    for n in 0..20 {
+        input += format!("# Title {n}\n\nSome **bold** text[^{n}]\nsame line\n\n- a\n- **b**\n\n")
+            .as_str();
+        input += format!("[^{n}]: note {n}\n\n").as_str();
+        if n == 10 {
+            input += "[FOOTNOTES]\n\n";
+        }
+    }
+    let options = TransformOptions::default();
+
+    let mut t = DummyTransform::default();
+    let sequential = transform_markdown_string_with_options(input.clone(), &mut t, &options);
+    assert!(
+        sequential.is_ok(),
+        "Error on transformation: {sequential:?}"
+    );
+
+    let mut t = DummyTransform::default();
+    let parallel = transform_markdown_string_parallel(input, &mut t, &options);
+    assert!(parallel.is_ok(), "Error on transformation: {parallel:?}");
+    assert_eq!(parallel.unwrap(), sequential.unwrap());
+}
+
+#[test]
+#[cfg(all(feature = "reflinks", not(feature = "dialect-v1")))]
+fn test_parallel_reference_titles() {
+    #[derive(Clone)]
+    pub struct TitleTransform;
+    impl MarkdownTransformer for TitleTransform {
+        fn transform_reflink(
+            &mut self,
+            text: String,
+            _slug: String,
+            title: Option<String>,
+        ) -> String {
+            format!("{text}({})", title.unwrap_or_default())
+        }
+    }
+
+    let input = "[a][r]\n\n[r]: url \"T\"".to_string();
+    let options = TransformOptions::default();
+    let res = transform_markdown_string_parallel(input, &mut TitleTransform, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "a(T)");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_parallel_numbering_and_crossrefs() {
+    #[derive(Clone)]
+    pub struct Listings {
+        number: Option<crate::ElementNumber>,
+    }
+    impl MarkdownTransformer for Listings {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.number = ctx.number();
+        }
+        fn transform_codeblock(
+            &mut self,
+            _language: Option<String>,
+            _flags: Vec<String>,
+            text: String,
+        ) -> String {
+            format!("Listing {}: {text}\n", self.number.unwrap())
+        }
+    }
+
+    let input = "# Code\n\n```\na\n```\n\n```\nb\n```\n\nSee [#code]".to_string();
+    let options = TransformOptions::default().crossrefs(true);
+    let mut t = Listings { number: None };
+    let res = transform_markdown_string_parallel(input, &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "CodeListing 1: a\nListing 2: b\nSee Code");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_parallel_bibliography() {
+    #[derive(Clone)]
+    pub struct Bib;
+    impl MarkdownTransformer for Bib {
+        fn transform_bibliography(&mut self, keys: Vec<String>) -> String {
+            format!("[{}]", keys.join(","))
+        }
+    }
+
+    let input = "A [@a]\n\n[BIBLIOGRAPHY]\n\nB [@b]".to_string();
+    let options = TransformOptions::default().citations(true);
+    let res = transform_markdown_string_parallel(input.clone(), &mut Bib, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "A [a][a,b]B [b]");
+
+    let res = transform_markdown_string_parallel(input, &mut Bib, &TransformOptions::default());
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "A [@a][BIBLIOGRAPHY]B [@b]");
+}
+
+#[test]
+fn test_pipeline_run_parallel() {
+    let input = "# Title\n\nSome **bold** text\n\n- a\n- b";
+    let mut pipeline = Pipeline::new().transformer(DummyTransform::default());
+    let sequential = pipeline.run(input);
+    let parallel = pipeline.run_parallel(input);
+    assert!(parallel.is_ok(), "Error on transformation: {parallel:?}");
+    assert_eq!(parallel.unwrap(), sequential.unwrap());
+}
+
+#[test]
+fn test_parallel_directives() {
+    let input = "A\n\n<!-- mdtrans: skip-start -->\n\nB\n\n- c\n\n<!-- mdtrans: skip-end -->\n\n<!-- mdtrans: raw -->\n\n**D**\n\nE";
+    let options = TransformOptions::default().directives(true);
+
+    let mut t = DummyTransform::default();
+    let sequential = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(
+        sequential.is_ok(),
+        "Error on transformation: {sequential:?}"
+    );
+    let sequential = sequential.unwrap();
+    assert!(!sequential.contains("<ul"), "{sequential}");
+    assert!(sequential.contains("**D**"), "{sequential}");
+
+    let mut t = DummyTransform::default();
+    let parallel = transform_markdown_string_parallel(input.to_string(), &mut t, &options);
+    assert!(parallel.is_ok(), "Error on transformation: {parallel:?}");
+    assert_eq!(parallel.unwrap(), sequential);
+}
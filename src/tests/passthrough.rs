@@ -0,0 +1,47 @@
+use crate::transformers::{MarkdownRenderer, PassthroughTransformer};
+use crate::{LinkKind, MarkdownTransformer, Pipeline, TransformOptions};
+
+#[derive(Default)]
+struct Secure {
+    renderer: MarkdownRenderer,
+}
+
+impl PassthroughTransformer for Secure {
+    fn renderer(&mut self) -> &mut MarkdownRenderer {
+        &mut self.renderer
+    }
+
+    fn transform_link(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        let url = url.replace("http://", "https://");
+        self.renderer.transform_link(text, url, kind)
+    }
+}
+
+#[test]
+fn test_passthrough_defaults() {
+    let input = "# Title\n\nSome **bold**, *italic* and `code`\n\n- a\n- b\n\n> quote\n\n```rust\nlet a = 1;\n```";
+    let res = Pipeline::new().transformer(Secure::default()).run(input);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
+
+#[test]
+fn test_passthrough_override() {
+    let input = "## Links\n\nSee **[this](http://a.com)** and [that](https://b.com)";
+    let output = "## Links\n\nSee **[this](https://a.com)** and [that](https://b.com)";
+    let res = Pipeline::new().transformer(Secure::default()).run(input);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[test]
+fn test_passthrough_badge() {
+    let input = "[![CI](http://img.shields.io/ci.svg)](http://a.com) done";
+    let output = "[![CI](http://img.shields.io/ci.svg)](https://a.com) done";
+    let res = Pipeline::new()
+        .options(TransformOptions::default().badges(true))
+        .transformer(Secure::default())
+        .run(input);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
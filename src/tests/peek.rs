@@ -1,22 +1,36 @@
-use std::collections::HashMap;
-
 use crate::{transform_markdown_string, MarkdownTransformer};
 
 #[test]
+#[cfg(all(feature = "reflinks", not(feature = "dialect-v1")))]
 fn test_peek_reflink() {
+    use std::collections::HashMap;
+
     pub struct DummyTransform {
         refs: HashMap<String, String>,
     }
     impl MarkdownTransformer for DummyTransform {
-        fn transform_reflink(&mut self, text: String, slug: String) -> String {
+        fn transform_reflink(
+            &mut self,
+            text: String,
+            slug: String,
+            title: Option<String>,
+        ) -> String {
             let url = self.refs.get(&slug);
             assert!(url.is_some());
-            format!("<a href=\"{}\">{text}</a>", url.unwrap())
+            match title {
+                Some(title) => format!("<a href=\"{}\" title=\"{title}\">{text}</a>", url.unwrap()),
+                None => format!("<a href=\"{}\">{text}</a>", url.unwrap()),
+            }
         }
-        fn transform_refurl(&mut self, _slug: String, _url: String) -> String {
+        fn transform_refurl(
+            &mut self,
+            _slug: String,
+            _url: String,
+            _title: Option<String>,
+        ) -> String {
             "".to_string()
         }
-        fn peek_refurl(&mut self, slug: String, url: String) {
+        fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
             self.refs.insert(slug, url);
         }
     }
@@ -31,6 +45,13 @@ fn test_peek_reflink() {
     let res = transform_markdown_string("[a][b]\n[b]: site_(c)".to_string(), &mut t);
     assert!(res.is_ok());
     assert_eq!(res.unwrap(), "<a href=\"site_(c)\">a</a>".to_string());
+
+    let res = transform_markdown_string("[a][b]\n[b]: c \"The C\"".to_string(), &mut t);
+    assert!(res.is_ok());
+    assert_eq!(
+        res.unwrap(),
+        "<a href=\"c\" title=\"The C\">a</a>".to_string()
+    );
 }
 
 #[test]
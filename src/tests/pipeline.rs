@@ -0,0 +1,108 @@
+use crate::{
+    transformers::{MarkdownRenderer, TraceTransformer},
+    MarkdownTransformer, Pipeline,
+};
+#[cfg(not(feature = "dialect-v1"))]
+use crate::{Document, Extension, TransformOptions};
+
+#[derive(Clone, Default)]
+struct Shout;
+impl MarkdownTransformer for Shout {
+    fn transform_text(&mut self, text: String) -> String {
+        text.to_uppercase()
+    }
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        format!("h{level}: {text}\n")
+    }
+    fn transform_shortcode(&mut self, name: String, args: Vec<String>) -> String {
+        format!("<{name}:{}>", args.join("|"))
+    }
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_pipeline_run() {
+    let mut pipeline = Pipeline::new()
+        .options(TransformOptions::default().heading_offset(1))
+        .extension(Extension::Shortcode("youtube".to_string()))
+        .transformer(Shout);
+    let res = pipeline.run("# Title\n\nWatch {{youtube abc}} {{other x}}");
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h2: TITLE\nWATCH <youtube:abc> {{OTHER X}}");
+
+    let document = Document::parse("# Again").unwrap();
+    assert_eq!(pipeline.run_document(&document), "h2: AGAIN\n");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_pipeline_extensions_after_options() {
+    let input = "# Intro\n\nSee [#intro]";
+    let res = Pipeline::new()
+        .transformer(Shout)
+        .extension(Extension::Crossrefs)
+        .run_with_warnings(input);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let (output, warnings) = res.unwrap();
+    assert_eq!(output, "h1: INTRO\nSEE Intro");
+    assert!(warnings.is_empty(), "{warnings:?}");
+
+    // NOTE    Options given afterwards keep the extensions enabled before
+    let res = Pipeline::new()
+        .extension(Extension::Crossrefs)
+        .options(TransformOptions::default().heading_offset(1))
+        .transformer(Shout)
+        .run(input);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h2: INTRO\nSEE Intro");
+}
+
+#[test]
+fn test_pipeline_cache() {
+    let mut pipeline = Pipeline::new()
+        .cache(true)
+        .transformer(Shout)
+        .decorate(|inner| TraceTransformer::new(inner).context(false));
+    assert_eq!(pipeline.run("a").unwrap(), "A");
+    let calls = pipeline.transformer_mut().calls().len();
+    assert_eq!(pipeline.run("a").unwrap(), "A");
+    assert_eq!(pipeline.transformer_mut().calls().len(), calls);
+    assert_eq!(pipeline.run("b").unwrap(), "B");
+    assert!(pipeline.transformer_mut().calls().len() > calls);
+}
+
+#[test]
+fn test_pipeline_decorate() {
+    let mut pipeline = Pipeline::new()
+        .transformer(Shout)
+        .decorate(|inner| TraceTransformer::new(inner).context(false));
+    let res = pipeline.run("a");
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "A");
+    let calls = pipeline.transformer_mut().calls().len();
+    assert!(calls > 0);
+    pipeline.transformer_mut().clear();
+    assert!(pipeline.into_transformer().calls().is_empty());
+}
+
+#[test]
+fn test_pipeline_batch() {
+    let mut pipeline = Pipeline::new().transformer(MarkdownRenderer::default());
+    let res = pipeline.run_batch(["# A", "*b*"]);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), ["# A", "*b*"]);
+
+    let res = pipeline.run_isolated("*x*");
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "*x*");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_pipeline_run_io() {
+    let mut pipeline = Pipeline::new().transformer(MarkdownRenderer::default());
+    let mut output = vec![];
+    let res = pipeline.run_io(&mut "\u{feff}**c**".as_bytes(), &mut output);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(String::from_utf8(output).unwrap(), "**c**");
+}
@@ -0,0 +1,41 @@
+use crate::transform_markdown_string;
+use crate::transformers::PlainTextRenderer;
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_plain_text() {
+    let mut t = PlainTextRenderer::default();
+    let input = "# Welcome\n\nHello **there**, see [the docs](https://docs.rs) or https://rust-lang.org\n\n> quoted\n> text\n\n1. one\n2. `two`";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "Welcome\n=======\n\nHello there, see the docs <https://docs.rs> or <https://rust-lang.org>\n\n> quoted text\n\n1. one\n2. two"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_plain_text_wrap() {
+    let mut t = PlainTextRenderer::default().width(20);
+    let input = "A paragraph long enough to be wrapped\n\n> a quote long enough to be wrapped\n\n- an element long enough to be wrapped\n\n  - nested and wrapped as well";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "A paragraph long\nenough to be wrapped\n\n> a quote long\n> enough to be\n> wrapped\n\n- an element long\n  enough to be\n  wrapped\n\n  - nested and\n    wrapped as well"
+    );
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_plain_text_reflinks() {
+    let mut t = PlainTextRenderer::default();
+    let input = "Read [the book][book]\n\n[book]: https://doc.rust-lang.org/book";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "Read the book <https://doc.rust-lang.org/book>"
+    );
+}
@@ -0,0 +1,28 @@
+use crate::reflow;
+
+#[test]
+fn test_reflow_simple() {
+    let res = reflow("aaa bbb ccc ddd", 7);
+    assert_eq!(res, "aaa bbb\nccc ddd");
+
+    let res = reflow("a\nb c", 80);
+    assert_eq!(res, "a b c");
+}
+
+#[test]
+fn test_reflow_atoms() {
+    let res = reflow("see `some inline code` here", 10);
+    assert_eq!(res, "see\n`some inline code`\nhere");
+
+    let res = reflow("a [link with text](https://url.com/(x)) b", 5);
+    assert_eq!(res, "a\n[link with text](https://url.com/(x))\nb");
+
+    let res = reflow("a [ref link][slug] b", 5);
+    assert_eq!(res, "a\n[ref link][slug]\nb");
+}
+
+#[test]
+fn test_reflow_hard_break() {
+    let res = reflow("aaa bbb  \nccc ddd", 5);
+    assert_eq!(res, "aaa\nbbb  \nccc\nddd");
+}
@@ -0,0 +1,121 @@
+use crate::{
+    link_changes, rewrite_links, Document, ElementKind, Errcode, LinkChange, Rewriter, Span,
+};
+
+fn span(start: usize, end: usize) -> Span {
+    Span {
+        start,
+        end,
+        line: 1,
+        column: start + 1,
+    }
+}
+
+#[test]
+fn test_rewriter_edits() {
+    let input = "0123456789";
+    let mut rewriter = Rewriter::new(input);
+    assert!(rewriter.is_empty());
+    rewriter.replace(span(2, 4), "ab");
+    rewriter.insert(6, "(");
+    rewriter.delete(span(6, 8));
+    rewriter.insert(6, "[");
+    rewriter.insert(10, "!");
+    let res = rewriter.apply();
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(res.unwrap(), "01ab45([89!");
+
+    rewriter.replace(span(3, 5), "x");
+    let res = rewriter.apply();
+    assert!(
+        matches!(res, Err(Errcode::InvalidEdit { start: 3, end: 5 })),
+        "{res:?}"
+    );
+
+    let mut rewriter = Rewriter::new("é");
+    rewriter.insert(1, "x");
+    assert!(rewriter.apply().is_err());
+}
+
+#[test]
+fn test_rewriter_line_endings() {
+    let input = "- a\r\n- b\r\n\r\nText\r\n";
+    let res = Document::parse(input);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    let mut rewriter = Rewriter::new(input);
+    for block in document.root().children() {
+        if block.kind() == ElementKind::List {
+            rewriter.replace(block.children()[1].span(), "- c");
+        }
+        if block.kind() == ElementKind::Paragraph {
+            rewriter.replace(block.span(), "Other text");
+        }
+    }
+    let res = rewriter.apply();
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(res.unwrap(), "- a\r\n- c\r\n\r\nOther text\r\n");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_rewrite_links() {
+    let input = concat!(
+        "# [Setup](setup.md)\n\n",
+        "- see\n  [the guide](guide.md)\n\n",
+        "| page | link |\n|---|---|\n| a | [guide](guide.md) |\n\n",
+        "Visit https://old.example.com/guide.md or `[code](guide.md)`\n",
+    );
+    let res = rewrite_links(input, |url| {
+        let url = url.replace("guide.md", "manual.md");
+        Some(url.replace("old.example.com", "example.com"))
+    });
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    // NOTE    The code is left as it is
+    let output = concat!(
+        "# [Setup](setup.md)\n\n",
+        "- see\n  [the guide](manual.md)\n\n",
+        "| page | link |\n|---|---|\n| a | [guide](manual.md) |\n\n",
+        "Visit https://example.com/manual.md or `[code](guide.md)`\n",
+    );
+    let (res, changes) = res.unwrap();
+    assert_eq!(res, output);
+    assert_eq!(changes.len(), 3);
+}
+
+#[cfg(feature = "reflinks")]
+#[test]
+fn test_rewrite_reference_definitions() {
+    let input = "See [the guide][guide].\n\n[guide]: guide.md \"Guide\"\n";
+    let res = rewrite_links(input, |url| Some(format!("/docs/{url}")));
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(
+        res.unwrap().0,
+        "See [the guide][guide].\n\n[guide]: /docs/guide.md \"Guide\"\n"
+    );
+}
+
+#[test]
+fn test_link_changes_dry_run() {
+    let input = "[a](a.md)\r\n\r\n[b](b.md) and [c](c.md)\r\n";
+    let res = link_changes(input, |url| match url {
+        "a.md" => Some("a.md".to_string()),
+        "c.md" => Some("docs/c.md".to_string()),
+        _ => None,
+    });
+    assert!(res.is_ok(), "Error on rewriting: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        [LinkChange {
+            old_url: "c.md".to_string(),
+            new_url: "docs/c.md".to_string(),
+            span: Span {
+                start: 31,
+                end: 35,
+                line: 3,
+                column: 19,
+            },
+        }]
+    );
+    assert_eq!(&input[31..35], "c.md");
+}
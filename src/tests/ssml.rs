@@ -0,0 +1,67 @@
+use crate::transform_markdown_string;
+use crate::transformers::{CodeBlockSpeech, SsmlRenderer};
+
+#[test]
+fn test_ssml() {
+    let mut t = SsmlRenderer::default();
+    let input = "# Title\n\nSome **bold** & *italic* [text](url)\n\n- one\n- two\n\n> quoted\n\n```rust\nfn main() {}\n```\n\n---";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        concat!(
+            "<speak><p><emphasis level=\"strong\">Title</emphasis></p><break time=\"500ms\"/>",
+            "<p>Some <emphasis level=\"strong\">bold</emphasis> &amp; <emphasis>italic</emphasis> text</p>",
+            "<s>one.</s><s>two.</s>",
+            "<p>Quote.</p><p>quoted.</p><p>End of quote.</p>",
+            "<p>Code sample in rust, omitted.</p>",
+            "<break time=\"1s\"/></speak>"
+        )
+    );
+}
+
+#[test]
+fn test_ssml_skip_code() {
+    let mut t = SsmlRenderer::default().code_blocks(CodeBlockSpeech::Skip);
+    let input = "Before\n\n```\nlet a = 1;\n```\n\nAfter";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "<speak><p>Before</p><p>After</p></speak>");
+
+    let res = transform_markdown_string(String::new(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "<speak></speak>");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_ssml_localized() {
+    use crate::{AlertKind, Localizer, Phrase};
+
+    let french = Localizer::new(|phrase| {
+        let text = match phrase {
+            Phrase::Alert(AlertKind::Tip) => "Astuce",
+            Phrase::QuoteStart => "Citation",
+            Phrase::QuoteEnd => "Fin de la citation",
+            Phrase::CodeOmittedIn => "Exemple de code en {} omis",
+            Phrase::Footnote => "Note",
+            Phrase::Footnotes => "Notes de bas de page",
+            _ => return None,
+        };
+        Some(text.to_string())
+    });
+    let mut t = SsmlRenderer::default().localizer(french);
+    let input = "> Je suis une truite\n\n> [!TIP]\n> Un saumon\n\n```rust\nfn main() {}\n```\n\nTexte[^1]\n\n[^1]: Une note";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        concat!(
+            "<speak><p>Citation.</p><p>Je suis une truite.</p><p>Fin de la citation.</p>",
+            "<p><emphasis level=\"strong\">Astuce:</emphasis> Un saumon.</p>",
+            "<p>Exemple de code en rust omis.</p>",
+            "<p>Texte</p>",
+            "<p>Notes de bas de page.</p><p><s>Note 1: Une note.</s></p></speak>"
+        )
+    );
+}
@@ -0,0 +1,61 @@
+use crate::{
+    transform_markdown_string, transform_markdown_string_isolated, MarkdownTransformer,
+    StatefulTransformer, TransformOptions,
+};
+
+#[derive(Default)]
+pub struct DummyTransform {
+    labels: Vec<String>,
+}
+
+impl MarkdownTransformer for DummyTransform {
+    fn peek_header(&mut self, _level: usize, text: String) {
+        self.labels.push(text);
+    }
+    fn transform_header(&mut self, _level: usize, text: String) -> String {
+        format!("[{}]{text}", self.labels.join(","))
+    }
+}
+
+impl StatefulTransformer for DummyTransform {
+    type Snapshot = Vec<String>;
+
+    fn reset(&mut self) {
+        self.labels.clear();
+    }
+
+    fn snapshot(&self) -> Vec<String> {
+        self.labels.clone()
+    }
+
+    fn restore(&mut self, snapshot: Vec<String>) {
+        self.labels = snapshot;
+    }
+}
+
+#[test]
+fn test_state_leaks_without_isolation() {
+    let mut t = DummyTransform::default();
+    transform_markdown_string("# a".to_string(), &mut t).unwrap();
+    let res = transform_markdown_string("# b".to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "[a,b]b");
+
+    t.reset();
+    let res = transform_markdown_string("# c".to_string(), &mut t);
+    assert_eq!(res.unwrap(), "[c]c");
+}
+
+#[test]
+fn test_state_isolated() {
+    let mut t = DummyTransform {
+        labels: vec!["global".to_string()],
+    };
+    let options = TransformOptions::default();
+    for (input, output) in [("# a", "[global,a]a"), ("# b", "[global,b]b")] {
+        let res = transform_markdown_string_isolated(input.to_string(), &mut t, &options);
+        assert!(res.is_ok(), "Error on transformation: {res:?}");
+        assert_eq!(res.unwrap(), output);
+    }
+    assert_eq!(t.labels, ["global"]);
+}
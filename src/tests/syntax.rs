@@ -0,0 +1,47 @@
+use crate::transformers::TraceTransformer;
+use crate::{syntax, Document, ElementKind, MarkdownTransformer, Node, TransformOptions};
+
+struct Noop;
+impl MarkdownTransformer for Noop {}
+
+pub(super) fn has_kind(node: &Node, kind: ElementKind) -> bool {
+    node.kind() == kind || node.children().iter().any(|child| has_kind(child, kind))
+}
+
+#[test]
+fn test_syntax_examples() {
+    let entries = syntax();
+    assert_eq!(
+        entries.iter().any(|entry| entry.name == "Tab group"),
+        cfg!(feature = "tabs")
+    );
+    for entry in entries {
+        let options = match entry.option {
+            None => TransformOptions::default(),
+            Some("badges") => TransformOptions::default().badges(true),
+            Some("citations") => TransformOptions::default().citations(true),
+            Some("crossrefs") => TransformOptions::default().crossrefs(true),
+            Some("directives") => TransformOptions::default().directives(true),
+            Some("shortcode") => TransformOptions::default().shortcode("youtube"),
+            Some("variables") => TransformOptions::default().variables([("name", "value")]),
+            Some(option) => panic!("Unknown option {option}"),
+        };
+        let res = Document::parse(entry.example);
+        assert!(res.is_ok(), "Error on parsing {}: {res:?}", entry.name);
+        let document = res.unwrap();
+        assert!(
+            has_kind(document.root(), entry.kind),
+            "No {:?} in {}",
+            entry.kind,
+            entry.name
+        );
+        let mut trace = TraceTransformer::new(Noop).context(false);
+        document.transform_with_options(&mut trace, &options);
+        assert!(
+            trace.calls().iter().any(|call| call.name == entry.hook),
+            "{} not called for {}",
+            entry.hook,
+            entry.name
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{format_table, transform_markdown_string, Alignment, MarkdownTransformer};
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_table() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_table(
+            &mut self,
+            header: Vec<String>,
+            alignments: Vec<Alignment>,
+            rows: Vec<Vec<String>>,
+        ) -> String {
+            assert_eq!(header, vec!["a", "b", "c"]);
+            assert_eq!(
+                alignments,
+                vec![Alignment::Left, Alignment::Center, Alignment::None]
+            );
+            assert_eq!(rows, vec![vec!["1", "", "3"], vec!["4", "5", ""]]);
+            "TABLE".to_string()
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "start\n\n| a | b | c |\n|:--|:-:|---|\n| 1 || 3 |\n|4|5|\n\nend";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "startTABLEend");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_table_inline_cells() {
+    use crate::{transformers::HtmlRenderer, Document};
+
+    let input = "| Name | Link |\n|:-----|-----:|\n| **bold** *it* | [docs](https://a.b) |\n| `a|b` |\n| [not inline | x |";
+    let res = Document::parse(input);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(html.contains("<strong>bold</strong> <em>it</em>"), "{html}");
+    assert!(html.contains("href=\"https://a.b\""), "{html}");
+    // NOTE    The pipes delimit the cells before their content is parsed, and a cell
+    //         that is not valid inline markdown is kept as is
+    assert!(html.contains("`a"), "{html}");
+    assert!(html.contains("[not inline"), "{html}");
+}
+
+#[test]
+fn test_format_table() {
+    let rows = vec![
+        vec!["Name".to_string(), "Qty".to_string(), "Note".to_string()],
+        vec!["apple".to_string(), "3".to_string(), "ok".to_string()],
+        vec!["kiwi".to_string(), "12".to_string()],
+    ];
+    let alignments = [Alignment::Left, Alignment::Right, Alignment::Center];
+    let output = "| Name  | Qty | Note |
+| :---- | --: | :--: |
+| apple |   3 |  ok  |
+| kiwi  |  12 |      |";
+    assert_eq!(format_table(&rows, &alignments), output);
+}
+
+#[test]
+fn test_csv_table() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_table(
+            &mut self,
+            header: Vec<String>,
+            alignments: Vec<Alignment>,
+            rows: Vec<Vec<String>>,
+        ) -> String {
+            format_table(&[vec![header], rows].concat(), &alignments)
+        }
+    }
+
+    let input = "```csvtable\nName, Quote\nAda, \"Hello, \"\"world\"\"\"\nBob\n```";
+    let res = transform_markdown_string(input.to_string(), &mut DummyTransform);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "| Name | Quote          |\n| ---- | -------------- |\n| Ada  | Hello, \"world\" |\n| Bob  |                |"
+    );
+
+    let input = "```tsvtable\na\tb\n1\t2\n```";
+    let res = transform_markdown_string(input.to_string(), &mut DummyTransform);
+    assert_eq!(res.unwrap(), "| a   | b   |\n| --- | --- |\n| 1   | 2   |");
+}
@@ -0,0 +1,104 @@
+use crate::transformers::{HtmlRenderer, MarkdownRenderer};
+use crate::{transform_markdown_string, Alignment, MarkdownTransformer, TableCell};
+
+const TABLE: &str = "| Name | Description ||\n|:--|---|--:|\n| a | long text | \\\n|   | on two lines | 1 |\n| wide ||| \n| b | c |";
+
+#[test]
+fn test_table_spans_hooks() {
+    #[derive(Default)]
+    struct Spans {
+        rows: Vec<Vec<TableCell>>,
+    }
+    impl MarkdownTransformer for Spans {
+        fn transform_table_with_spans(
+            &mut self,
+            header: Vec<TableCell>,
+            _alignments: Vec<Alignment>,
+            rows: Vec<Vec<TableCell>>,
+        ) -> String {
+            self.rows.push(header);
+            self.rows.extend(rows);
+            String::new()
+        }
+    }
+    let mut t = Spans::default();
+    let res = transform_markdown_string(TABLE.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let rows = t
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| (cell.text.as_str(), cell.colspan))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            vec![("Name", 1), ("Description", 2)],
+            vec![("a", 1), ("long text\non two lines", 1), ("1", 1)],
+            vec![("wide", 3)],
+            vec![("b", 1), ("c", 1), ("", 1)],
+        ]
+    );
+}
+
+#[test]
+fn test_table_spans_fallback() {
+    struct Grid;
+    impl MarkdownTransformer for Grid {
+        fn transform_table(
+            &mut self,
+            header: Vec<String>,
+            _alignments: Vec<Alignment>,
+            rows: Vec<Vec<String>>,
+        ) -> String {
+            let mut lines = vec![header.join(",")];
+            lines.extend(rows.iter().map(|row| row.join(",")));
+            lines.join(";")
+        }
+    }
+    let res = transform_markdown_string(TABLE.to_string(), &mut Grid);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "Name,Description,;a,long text\non two lines,1;wide,,;b,c,"
+    );
+}
+
+#[test]
+fn test_table_spans_html() {
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string(TABLE.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(
+        html.contains("<th colspan=\"2\">Description</th>"),
+        "{html}"
+    );
+    assert!(
+        html.contains(">long text<br>on two lines</td><td class=\"md-align-right\">1</td>"),
+        "{html}"
+    );
+    assert!(html.contains("<td colspan=\"3\""), "{html}");
+}
+
+#[test]
+fn test_table_spans_markdown_roundtrip() {
+    let mut t = MarkdownRenderer::default();
+    let input = "| a | b ||\n| --- | :-- | --: |\n| c | \\\n| d | e | f |";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert_eq!(
+        output,
+        "| a | b ||\n| --- | :-- | --: |\n| c | e | f | \\\n| d |  |  |"
+    );
+
+    // NOTE    Tables without spans are still aligned
+    let input = "|a|b|\n|-|-|\n|c|d|";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "| a   | b   |\n| --- | --- |\n| c   | d   |");
+}
@@ -0,0 +1,72 @@
+use crate::transformers::{HtmlRenderer, MarkdownRenderer, TraceTransformer};
+use crate::{transform_inline_markdown, transform_markdown_string, InlineTransformer};
+use crate::{Document, ElementKind};
+
+const EXAMPLES: &str =
+    "=== \"Rust\"\n    ```rust\n    let a = 1;\n    ```\n\n=== \"Python & co\"\n    a = 1\n\nAfter";
+
+#[test]
+fn test_tab_group_html() {
+    let mut t = HtmlRenderer::default();
+    let res = transform_markdown_string(EXAMPLES.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let html = res.unwrap();
+    assert!(
+        html.starts_with("<div class=\"md-tabs\"><div class=\"md-tab\"><p class=\"md-tab-title\">Rust</p><pre><code class=\"language-rust\">let a = 1;</code></pre></div><div class=\"md-tab\"><p class=\"md-tab-title\">Python &amp; co</p><p>a = 1</p></div></div>"),
+        "{html}"
+    );
+}
+
+#[test]
+fn test_tab_group_markdown_roundtrip() {
+    let mut t = MarkdownRenderer::default();
+    let res = transform_markdown_string(EXAMPLES.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), EXAMPLES);
+}
+
+#[test]
+fn test_tab_group_tree() {
+    let res = Document::parse(EXAMPLES);
+    assert!(res.is_ok(), "Error on parsing: {res:?}");
+    let document = res.unwrap();
+    let blocks = document.root().children();
+    assert_eq!(blocks[0].kind(), ElementKind::TabGroup);
+    assert_eq!(blocks[0].children().len(), 2);
+    assert_eq!(blocks[1].kind(), ElementKind::Paragraph);
+
+    let mut t = TraceTransformer::new(MarkdownRenderer::default());
+    let res = transform_markdown_string(EXAMPLES.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let call = t
+        .calls()
+        .iter()
+        .find(|call| call.name == "transform_tab_group");
+    assert!(call.is_some_and(|call| call.args[0].starts_with("[(\"Rust\", \"\\n\\n```rust")));
+}
+
+#[derive(Default)]
+struct Titles {
+    tabs: Vec<(String, usize)>,
+}
+
+impl InlineTransformer for Titles {
+    fn transform_tab_group(&mut self, tabs: Vec<(String, Vec<String>)>) -> String {
+        self.tabs.extend(
+            tabs.into_iter()
+                .map(|(title, blocks)| (title, blocks.len())),
+        );
+        String::new()
+    }
+}
+
+#[test]
+fn test_tab_group_inline() {
+    let mut t = Titles::default();
+    let res = transform_inline_markdown(EXAMPLES, &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        t.tabs,
+        [("Rust".to_string(), 1), ("Python & co".to_string(), 1)]
+    );
+}
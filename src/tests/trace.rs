@@ -0,0 +1,97 @@
+use crate::{
+    transform_markdown_string,
+    transformers::{HookCall, TraceTransformer},
+    MarkdownTransformer,
+};
+
+struct Bold;
+impl MarkdownTransformer for Bold {
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<b>{text}</b>")
+    }
+}
+
+#[test]
+fn test_trace_hook_calls() {
+    let mut trace = TraceTransformer::new(Bold).context(false);
+    let res = transform_markdown_string("Some **bold**".to_string(), &mut trace);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "Some <b>bold</b>");
+
+    let names = trace.calls().iter().map(|c| c.name).collect::<Vec<&str>>();
+    assert_eq!(
+        names,
+        vec![
+            "peek_text",
+            "peek_text",
+            "peek_bold",
+            "peek_paragraph",
+            "finished",
+            "enter_paragraph",
+            "transform_text",
+            "transform_text",
+            "transform_bold",
+            "transform_paragraph",
+            "exit_paragraph",
+            "finished",
+        ]
+    );
+    assert_eq!(
+        trace.calls()[8],
+        HookCall {
+            name: "transform_bold",
+            args: vec!["\"bold\"".to_string()],
+            output: Some("<b>bold</b>".to_string()),
+        }
+    );
+    assert_eq!(
+        trace.pretty().lines().skip(5).collect::<Vec<&str>>(),
+        vec![
+            "enter_paragraph() -> \"\"",
+            "  transform_text(\"Some \") -> \"Some \"",
+            "  transform_text(\"bold\") -> \"bold\"",
+            "  transform_bold(\"bold\") -> \"<b>bold</b>\"",
+            "  transform_paragraph(\"Some <b>bold</b>\") -> \"Some <b>bold</b>\"",
+            "exit_paragraph() -> \"\"",
+            "finished(false) -> \"\"",
+        ]
+    );
+}
+
+#[test]
+fn test_trace_element_context() {
+    let mut trace = TraceTransformer::new(Bold);
+    let res = transform_markdown_string("# Title".to_string(), &mut trace);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let contexts = trace
+        .calls()
+        .iter()
+        .filter(|c| c.name == "element_context")
+        .map(|c| c.to_string())
+        .collect::<Vec<String>>();
+    // Once for the peek pass, once for the transform pass
+    assert_eq!(contexts, vec!["element_context(h1, 1:1)"; 2]);
+
+    trace.clear();
+    assert!(trace.calls().is_empty());
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_trace_image_tags_order() {
+    let mut trace = TraceTransformer::new(Bold).context(false);
+    let res = transform_markdown_string(
+        "![alt](img.png)[width: 300, height: 200, align: left]".to_string(),
+        &mut trace,
+    );
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let image = trace
+        .calls()
+        .iter()
+        .find(|c| c.name == "transform_image")
+        .unwrap();
+    assert_eq!(
+        image.args[2],
+        "{\"width\": \"300\", \"height\": \"200\", \"align\": \"left\"}"
+    );
+}
@@ -1,4 +1,7 @@
-use crate::{transform_markdown_string, MarkdownTransformer};
+use crate::{
+    transform_markdown_string, transform_markdown_string_with_options, ImageCandidate, ImageTags,
+    LinkKind, ListItemInfo, MarkdownTransformer, TransformOptions,
+};
 
 #[test]
 fn test_trait_impl() {
@@ -22,7 +25,7 @@ fn test_trait_impl() {
 fn test_empty_rich_text() {
     pub struct DummyTransform;
     impl MarkdownTransformer for DummyTransform {
-        fn transform_link(&mut self, text: String, url: String) -> String {
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
             format!("{text}: {url}")
         }
     }
@@ -147,7 +150,7 @@ fn test_transform_bold() {
 fn test_transform_link() {
     pub struct DummyTransform;
     impl MarkdownTransformer for DummyTransform {
-        fn transform_link(&mut self, text: String, url: String) -> String {
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
             format!("{text}: {url}")
         }
         fn transform_bold(&mut self, text: String) -> String {
@@ -165,6 +168,94 @@ fn test_transform_link() {
     assert_eq!(res.unwrap(), "a bold c: b".to_string());
 }
 
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_autolink() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+            format!("<{url}|{text}>")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "(see https://example.com). Also www.a.org/x_(y), **https://b.io/c?d=1**; awww.no";
+    let output = "(see <https://example.com|https://example.com>). Also <http://www.a.org/x_(y)|www.a.org/x_(y)>, <https://b.io/c?d=1|https://b.io/c?d=1>; awww.no";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_angle_autolink() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+            format!("[{url}|{text}]")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "See <https://example.org>, <www.a.org/b> and https://c.io>d";
+    let output = "See [https://example.org|https://example.org], [http://www.a.org/b|www.a.org/b] and [https://c.io|https://c.io]>d";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[test]
+fn test_transform_image_link() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+            format!("<a {url}>{text}</a>")
+        }
+        fn transform_image(&mut self, alt: String, url: String, _add_tags: ImageTags) -> String {
+            format!("<img {url} {alt}>")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "[![build status](https://ci.io/b.svg?branch=main)](https://ci.io) [![docs](d.svg)](https://docs.rs)\n\n- [see ![logo](l.png) here](/home)";
+    let output = "<a https://ci.io><img https://ci.io/b.svg?branch=main build status></a> <a https://docs.rs><img d.svg docs></a><a /home>see <img l.png logo> here</a>";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[test]
+fn test_transform_badge() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+            format!("<a {url}>{text}</a>")
+        }
+        fn transform_badge(
+            &mut self,
+            alt: String,
+            img_url: String,
+            target_url: String,
+            kind: LinkKind,
+        ) -> String {
+            format!("<badge {alt}|{img_url}|{target_url}|{kind:?}>")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "[![CI](https://github.com/a/b/actions/workflows/ci.yml/badge.svg)](https://github.com/a/b) [![crates](https://img.shields.io/crates/v/b.svg)](/b) [![logo](logo.png)](/) [![x](https://my.ci/x.svg)](#x)";
+    let output = "<a https://github.com/a/b>CI</a> <a /b>crates</a> <a />logo</a> <a #x>x</a>";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+
+    let options = TransformOptions::default().badges(true).badge_host("my.ci");
+    let output = "<badge CI|https://github.com/a/b/actions/workflows/ci.yml/badge.svg|https://github.com/a/b|External> <badge crates|https://img.shields.io/crates/v/b.svg|/b|Internal> <a />logo</a> <badge x|https://my.ci/x.svg|#x|Anchor>";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
 #[test]
 fn test_transform_quote() {
     pub struct DummyTransform;
@@ -182,15 +273,47 @@ fn test_transform_quote() {
     assert_eq!(res.unwrap(), output.to_string());
 }
 
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_alert() {
+    use crate::AlertKind;
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_quote(&mut self, text: String) -> String {
+            format!("QUOTE {text}")
+        }
+        fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+            format!("{kind:?} {body}")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "> [!WARNING]\n> Be careful\n\n> [!tip]\n> Hint\n\n> Just a quote";
+    let output = "Warning Be carefulTip HintQUOTE Just a quote";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
 #[test]
 fn test_transform_codeblock() {
     pub struct DummyTransform;
     impl MarkdownTransformer for DummyTransform {
-        fn transform_codeblock(&mut self, lang: Option<String>, text: String) -> String {
+        fn transform_codeblock(
+            &mut self,
+            lang: Option<String>,
+            flags: Vec<String>,
+            text: String,
+        ) -> String {
             let mut buffer = "\nCODEBLOCK".to_string();
             if let Some(l) = lang {
                 buffer += format!(" {l}").as_str();
             }
+            for flag in flags {
+                buffer += format!(" +{flag}").as_str();
+            }
             buffer += format!("\n{text}\nCODEBLOCK\n").as_str();
             buffer
         }
@@ -208,6 +331,13 @@ fn test_transform_codeblock() {
     let res = transform_markdown_string(input.to_string(), &mut t);
     assert!(res.is_ok(), "Error on transformation: {res:?}");
     assert_eq!(res.unwrap(), output.to_string());
+
+    let input = "start\n```rust,ignore, should_panic edition2021 \nsome\ncode\n```\nend";
+    let output =
+        "start\nCODEBLOCK rust +ignore +should_panic +edition2021\nsome\ncode\nCODEBLOCK\nend";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
 }
 
 #[test]
@@ -248,7 +378,7 @@ fn test_transform_horiz_sep() {
 fn test_transform_list() {
     pub struct DummyTransform;
     impl MarkdownTransformer for DummyTransform {
-        fn transform_list_element(&mut self, element: String) -> String {
+        fn transform_list_element(&mut self, element: String, _info: ListItemInfo) -> String {
             element
         }
 
@@ -273,22 +403,103 @@ fn test_transform_list() {
     assert_eq!(res.unwrap(), output.to_string());
 }
 
+#[cfg(not(feature = "dialect-v1"))]
 #[test]
-fn test_transform_image() {
+fn test_transform_list_item_info() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+            let kind = if info.ordered { "o" } else { "u" };
+            format!(
+                "[{kind}{} {} {}]{element}",
+                info.depth, info.index, info.marker
+            )
+        }
+        fn transform_list(&mut self, elements: Vec<String>) -> String {
+            elements.join("")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "- a\n* b\n\n  3) c\n  4) d\n\n     + e";
+    let output = "[u0 0 -]a[u0 1 *]b[o1 0 3)]c[o1 1 4)]d[u2 0 +]e";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_task_list() {
     pub struct DummyTransform;
     impl MarkdownTransformer for DummyTransform {
-        fn transform_image(
+        fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+            format!("{:?}:{element}|", info.checked)
+        }
+        fn transform_list(&mut self, elements: Vec<String>) -> String {
+            elements.join("")
+        }
+        fn transform_ordered_list(&mut self, _start: usize, elements: Vec<String>) -> String {
+            elements.join("")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "- [x] done\n- [ ] todo\n- [X]\n- plain\n\n1. [ ] first";
+    let output = "Some(true):done|Some(false):todo|Some(true):|None:plain|Some(false):first|";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_list_blocks() {
+    #[derive(Default)]
+    pub struct DummyTransform {
+        line: usize,
+    }
+    impl MarkdownTransformer for DummyTransform {
+        fn element_context(&mut self, ctx: &crate::ElementContext) {
+            self.line = ctx.span().line;
+        }
+        fn transform_list_element(&mut self, element: String, _info: ListItemInfo) -> String {
+            format!("<li>{element}</li>")
+        }
+        fn transform_list(&mut self, elements: Vec<String>) -> String {
+            format!("<ul>{}</ul>", elements.join(""))
+        }
+        fn transform_paragraph(&mut self, text: String) -> String {
+            format!("<p {}>{text}</p>", self.line)
+        }
+        fn transform_quote(&mut self, text: String) -> String {
+            format!("<q>{text}</q>")
+        }
+        fn transform_codeblock(
             &mut self,
-            alt: String,
-            url: String,
-            add_tags: std::collections::HashMap<String, String>,
+            _language: Option<String>,
+            _flags: Vec<String>,
+            text: String,
         ) -> String {
-            let mut upper = false;
-            if let Some(t) = add_tags.get("upper") {
-                if t == "true" {
-                    upper = true;
-                }
-            }
+            format!("<pre {}>{text}</pre>", self.line)
+        }
+    }
+    let mut t = DummyTransform::default();
+
+    let input = "- a\n\n  second\n- b\n  ```\n  code\n  ```\n- c\n  > quote\n\n  - nested\n\nend";
+    let output = "<ul><li>a<p 3>second</p></li><li>b<pre 5>code</pre></li><li>c<q>quote</q><ul><li>nested</li></ul></li></ul><p 13>end</p>";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[test]
+fn test_transform_image() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+            let upper = add_tags.value("upper").and_then(|v| v.as_bool());
+            let upper = upper.unwrap_or(false);
             format!(
                 "{} -> {}",
                 if upper { alt.to_uppercase() } else { alt },
@@ -303,6 +514,24 @@ fn test_transform_image() {
     let res = transform_markdown_string(input.to_string(), &mut t);
     assert!(res.is_ok(), "Error on transformation: {res:?}");
     assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_transform_image_tags() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+            let upper = add_tags.value("upper").and_then(|v| v.as_bool());
+            let upper = upper.unwrap_or(false);
+            format!(
+                "{} -> {}",
+                if upper { alt.to_uppercase() } else { alt },
+                if upper { url.to_uppercase() } else { url }
+            )
+        }
+    }
+    let mut t = DummyTransform;
 
     let input = "start\n![image alt](url)[a: b, c:   d, upper: true, d  : e]\nend";
     let output = "start IMAGE ALT -> URL end";
@@ -310,3 +539,251 @@ fn test_transform_image() {
     assert!(res.is_ok(), "Error on transformation: {res:?}");
     assert_eq!(res.unwrap(), output.to_string());
 }
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_ordered_list() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+            format!("FROM {start}: {}", elements.join(", "))
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "2. a\n3. b\n4. c";
+    let output = "FROM 2: a, b, c";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+
+    // Defaults to the unordered list hook
+    pub struct ListTransform;
+    impl MarkdownTransformer for ListTransform {}
+    let res = transform_markdown_string(input.to_string(), &mut ListTransform);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "a, b, c".to_string());
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_html_block() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_html_block(&mut self, tag: String, html: String) -> String {
+            format!("HTML {tag}\n{html}\nHTML")
+        }
+        fn transform_bold(&mut self, text: String) -> String {
+            format!("BOLD {text} BOLD")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "start\n\n<table class=\"x\">\n  <tr><td>**not bold**</td></tr>\n\n  <table><tr>\n- a\n</tr></table>\n</table>\n\n**end**";
+    let output = "startHTML table\n<table class=\"x\">\n  <tr><td>**not bold**</td></tr>\n\n  <table><tr>\n- a\n</tr></table>\n</table>\nHTMLBOLD end BOLD";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output.to_string());
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_transform_figure() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+            let width = add_tags.get("width").cloned().unwrap_or_default();
+            format!("<img src=\"{url}\" alt=\"{alt}\" width=\"{width}\">")
+        }
+        fn transform_figure(&mut self, image: String, caption: String) -> String {
+            format!("<figure>{image}<figcaption>{caption}</figcaption></figure>")
+        }
+    }
+    let mut t = DummyTransform;
+
+    let input = "![a](u)[width: \"200\", caption: \"A nice view\"]\n\n![b](v)[width: 10]";
+    let output = "<figure><img src=\"u\" alt=\"a\" width=\"200\"><figcaption>A nice view</figcaption></figure><img src=\"v\" alt=\"b\" width=\"10\">";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_transform_io_encoding() {
+    use crate::{transform_markdown_with_options, Errcode, TransformOptions};
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            format!("h{level}: {text}")
+        }
+    }
+    let transform = |input: &[u8], options: &TransformOptions| {
+        let mut output = vec![];
+        let mut input = input;
+        transform_markdown_with_options(&mut input, &mut output, &mut DummyTransform, options)
+            .map(|_| String::from_utf8(output).unwrap())
+    };
+    let options = TransformOptions::default();
+
+    let res = transform(b"\xEF\xBB\xBF# Title", &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "h1: Title");
+
+    let res = transform(b"\xEF\xBB\xBFab\xFFc", &options);
+    assert!(
+        matches!(res, Err(Errcode::InvalidUtf8 { offset: 5 })),
+        "{res:?}"
+    );
+
+    let res = transform(b"ab\xFFc", &options.lossy_utf8(true));
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), "ab\u{FFFD}c");
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_enter_exit() {
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn enter_header(&mut self, level: usize) -> String {
+            format!("<h{level}>")
+        }
+        fn exit_header(&mut self, level: usize) -> String {
+            format!("</h{level}>")
+        }
+        fn enter_paragraph(&mut self) -> String {
+            "<p>".to_string()
+        }
+        fn exit_paragraph(&mut self) -> String {
+            "</p>".to_string()
+        }
+        fn enter_quote(&mut self) -> String {
+            "<blockquote>".to_string()
+        }
+        fn exit_quote(&mut self) -> String {
+            "</blockquote>".to_string()
+        }
+        fn enter_list(&mut self) -> String {
+            "<ul>".to_string()
+        }
+        fn exit_list(&mut self) -> String {
+            "</ul>".to_string()
+        }
+        fn enter_ordered_list(&mut self, start: usize) -> String {
+            format!("<ol start=\"{start}\">")
+        }
+        fn exit_ordered_list(&mut self, _start: usize) -> String {
+            "</ol>".to_string()
+        }
+        fn enter_list_element(&mut self, _info: ListItemInfo) -> String {
+            "<li>".to_string()
+        }
+        fn exit_list_element(&mut self, _info: ListItemInfo) -> String {
+            "</li>".to_string()
+        }
+    }
+    let mut t = DummyTransform;
+    let input = "# Title\n\nSome text\n\n> quoted\n\n- a\n- b\n\n3. c";
+    let res = transform_markdown_string(input.to_string(), &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        "<h1>Title</h1><p>Some text</p><blockquote>quoted</blockquote><ul><li>a</li>, <li>b</li></ul><ol start=\"3\"><li>c</li></ol>"
+    );
+}
+
+#[test]
+fn test_transform_responsive_image() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    pub struct DummyTransform;
+    impl MarkdownTransformer for DummyTransform {
+        fn transform_image(&mut self, _alt: String, url: String, _add_tags: ImageTags) -> String {
+            format!("img({url})")
+        }
+        fn transform_responsive_image(
+            &mut self,
+            _alt: String,
+            url: String,
+            candidates: Vec<ImageCandidate>,
+            _add_tags: ImageTags,
+        ) -> String {
+            format!("responsive({url}|{})", crate::srcset(&candidates))
+        }
+    }
+    let mut t = DummyTransform;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let options = TransformOptions::default().image_resolver(move |url| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        match url.strip_suffix(".png") {
+            Some(name) => vec![
+                ImageCandidate::new(format!("{name}-640.webp"), 640),
+                ImageCandidate::new(format!("{name}-1280.webp"), 1280),
+            ],
+            None => vec![],
+        }
+    });
+    let input = "![a](cat.png) ![b](logo.svg) ![c](cat.png)";
+    let output = "responsive(cat.png|cat-640.webp 640w, cat-1280.webp 1280w) img(logo.svg) responsive(cat.png|cat-640.webp 640w, cat-1280.webp 1280w)";
+    let res = transform_markdown_string_with_options(input.to_string(), &mut t, &options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), output);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_transform_filtered() {
+    use crate::{transform_filtered, ElementKind};
+
+    #[derive(Default)]
+    pub struct Upper {
+        peeked: usize,
+    }
+    impl MarkdownTransformer for Upper {
+        fn peek_bold(&mut self, _text: String) {
+            self.peeked += 1;
+        }
+        fn transform_bold(&mut self, text: String) -> String {
+            format!("**{}**", text.to_uppercase())
+        }
+        fn transform_header(&mut self, level: usize, text: String) -> String {
+            format!("<h{level}>{text}</h{level}>")
+        }
+    }
+    let input = concat!(
+        "# Title *kept*\n\n",
+        "Some **bold** and *italic*\n\n",
+        "- an **item**\n",
+        "  continued\n\n",
+        "| a | **b** |\n|---|---|\n| c |  d  |\n",
+    );
+    let mut t = Upper::default();
+    let res = transform_filtered(input, &[ElementKind::Bold], &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(
+        res.unwrap(),
+        input
+            .replace("bold", "BOLD")
+            .replace("item", "ITEM")
+            .replace("**b**", "**B**")
+    );
+    assert_eq!(t.peeked, 3);
+
+    // NOTE    The content of the selected elements is transformed with them
+    let res = transform_filtered(input, &[ElementKind::Header(1)], &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let output = res.unwrap();
+    assert!(
+        output.starts_with("<h1>Title kept</h1>\n\nSome **bold**"),
+        "{output}"
+    );
+
+    let res = transform_filtered(input, &[], &mut t);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    assert_eq!(res.unwrap(), input);
+}
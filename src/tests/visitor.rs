@@ -0,0 +1,93 @@
+use crate::{
+    transform_markdown_string, walk_markdown, Document, ElementContext, ElementId, ElementKind,
+    MarkdownTransformer, MarkdownVisitor, Rule,
+};
+
+#[derive(Default)]
+pub struct Events {
+    events: Vec<String>,
+    ids: Vec<(Rule, ElementId)>,
+}
+
+impl MarkdownVisitor for Events {
+    fn enter(&mut self, ctx: &ElementContext) {
+        self.events.push(format!("+{:?}", ctx.rule()));
+        self.ids.push((ctx.rule(), ctx.id()));
+    }
+    fn leave(&mut self, ctx: &ElementContext) {
+        self.events.push(format!("-{:?}", ctx.rule()));
+    }
+}
+
+#[test]
+fn test_walk_markdown() {
+    let mut visitor = Events::default();
+    let res = walk_markdown("# T\n\na **b**", &mut visitor);
+    assert!(res.is_ok(), "Error on walk: {res:?}");
+    assert_eq!(
+        visitor.events.join(" "),
+        "+file +h1 +rich_txt +text -text -rich_txt -h1 +paragraph +text -text +bold +NO_INLINE_TEXT -NO_INLINE_TEXT -bold -paragraph -file"
+    );
+}
+
+#[test]
+fn test_walk_markdown_ids() {
+    #[derive(Default)]
+    pub struct Ids {
+        ids: Vec<(Rule, ElementId)>,
+    }
+    impl MarkdownTransformer for Ids {
+        fn element_context(&mut self, ctx: &ElementContext) {
+            self.ids.push((ctx.rule(), ctx.id()));
+        }
+    }
+    let input = "# T\n\n- a **b**\n- a **b**\n\n> **b**";
+    let mut transformer = Ids::default();
+    let res = transform_markdown_string(input.to_string(), &mut transformer);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    let mut visitor = Events::default();
+    walk_markdown(input, &mut visitor).unwrap();
+    // NOTE    The transformer is given the contexts of both the peek and transform passes
+    let transform_pass = &transformer.ids[transformer.ids.len() / 2..];
+    for id in transform_pass {
+        assert!(visitor.ids.contains(id), "{id:?} not visited");
+    }
+}
+
+#[test]
+fn test_element_kinds() {
+    #[derive(Default)]
+    pub struct Kinds {
+        kinds: Vec<ElementKind>,
+    }
+    impl MarkdownVisitor for Kinds {
+        fn enter(&mut self, ctx: &ElementContext) {
+            if ctx.kind() != ElementKind::Part {
+                self.kinds.push(ctx.kind());
+            }
+        }
+    }
+    let input = "## T\n\n- [a](b)\n\n> q";
+    let mut visitor = Kinds::default();
+    let res = walk_markdown(input, &mut visitor);
+    assert!(res.is_ok(), "Error on walk: {res:?}");
+    assert_eq!(
+        visitor.kinds,
+        [
+            ElementKind::Document,
+            ElementKind::Header(2),
+            ElementKind::Text,
+            ElementKind::Text,
+            ElementKind::List,
+            ElementKind::ListItem,
+            ElementKind::Text,
+            ElementKind::Link,
+            ElementKind::Quote,
+            ElementKind::Text,
+        ]
+    );
+
+    let doc = Document::parse(input).unwrap();
+    assert_eq!(doc.root().kind(), ElementKind::Document);
+    assert_eq!(doc.root().children()[0].kind(), ElementKind::Header(2));
+}
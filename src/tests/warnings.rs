@@ -0,0 +1,121 @@
+use crate::{
+    transform_markdown_string_with_warnings, MarkdownTransformer, TransformOptions, Warning,
+};
+#[cfg(not(feature = "dialect-v1"))]
+use crate::{ElementKind, EmptyElements};
+
+pub struct DummyTransform;
+impl MarkdownTransformer for DummyTransform {}
+
+fn warnings(input: &str, options: &TransformOptions) -> Vec<Warning> {
+    let res =
+        transform_markdown_string_with_warnings(input.to_string(), &mut DummyTransform, options);
+    assert!(res.is_ok(), "Error on transformation: {res:?}");
+    res.unwrap().1
+}
+
+#[test]
+fn test_warn_empty_link_and_alt() {
+    let res = warnings(
+        "[](a) [b](b)\n\n![](c) ![d](d)",
+        &TransformOptions::default(),
+    );
+    assert_eq!(res.len(), 2, "{res:?}");
+    assert!(matches!(&res[0], Warning::EmptyLinkText { url, .. } if url == "a"));
+    assert!(
+        matches!(&res[1], Warning::MissingAltText { url, span } if url == "c" && span.line == 3)
+    );
+    assert_eq!(
+        res[1].to_string(),
+        "3:1: image \"c\" has no alternative text"
+    );
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_warn_empty_elements() {
+    let input = "## \n\n- a\n-\n\n[](u)";
+    assert_eq!(warnings(input, &TransformOptions::default()).len(), 1);
+
+    let options = TransformOptions::default().empty_elements(EmptyElements::Warn);
+    let res = warnings(input, &options);
+    assert_eq!(res.len(), 3, "{res:?}");
+    assert!(matches!(
+        &res[0],
+        Warning::EmptyElement {
+            kind: ElementKind::Header(2),
+            ..
+        }
+    ));
+    assert_eq!(res[1].to_string(), "4:1: list element has no content");
+    assert!(matches!(&res[2], Warning::EmptyLinkText { url, .. } if url == "u"));
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_warn_unresolved_footnote() {
+    let res = warnings("a[^x] b[^y]\n\n[^x]: X", &TransformOptions::default());
+    assert_eq!(res.len(), 1, "{res:?}");
+    assert!(matches!(&res[0], Warning::UnresolvedReference { label, .. } if label == "y"));
+}
+
+#[test]
+#[cfg(feature = "reflinks")]
+fn test_warn_unresolved_reflink() {
+    let res = warnings("[a][x] [b][y]\n\n[x]: url", &TransformOptions::default());
+    assert_eq!(res.len(), 1, "{res:?}");
+    assert!(matches!(&res[0], Warning::UnresolvedReference { label, .. } if label == "y"));
+}
+
+#[test]
+fn test_warn_unknown_language() {
+    let input = "```rust\na\n```\n\n```Python\nb\n```\n\n```\nc\n```";
+    assert!(warnings(input, &TransformOptions::default()).is_empty());
+
+    let options = TransformOptions::default()
+        .known_language("rust")
+        .known_language("python");
+    assert!(warnings(input, &options).is_empty());
+
+    let options = TransformOptions::default().known_language("rust");
+    let res = warnings(input, &options);
+    assert_eq!(res.len(), 1, "{res:?}");
+    assert!(matches!(&res[0], Warning::UnknownLanguage { language, .. } if language == "Python"));
+}
+
+#[cfg(not(feature = "dialect-v1"))]
+#[test]
+fn test_warn_unknown_variable() {
+    let input = "{{ page.title }}\n\n{{ page.date }}";
+    assert!(warnings(input, &TransformOptions::default()).is_empty());
+
+    let options = TransformOptions::default().variables([("page.title", "Intro")]);
+    let res = warnings(input, &options);
+    assert_eq!(res.len(), 1, "{res:?}");
+    assert_eq!(
+        res[0].to_string(),
+        "3:1: variable \"page.date\" has no value"
+    );
+}
+
+#[test]
+#[cfg(feature = "image-tags")]
+fn test_warn_invalid_image_tag() {
+    let input = "![a](a.png)[widht: 300, credit: Bob]\n\n![b](b.png)[width: 30, loading: later]";
+    assert!(warnings(input, &TransformOptions::default()).is_empty());
+
+    let schema = crate::ImageTagSchema::default()
+        .tag("credit")
+        .tag_with("loading", |value| match value {
+            "lazy" | "eager" => Ok(()),
+            _ => Err(format!("expected lazy or eager, got {value}")),
+        });
+    let options = TransformOptions::default().image_tag_schema(schema);
+    let res = warnings(input, &options);
+    assert_eq!(res.len(), 2, "{res:?}");
+    assert_eq!(res[0].to_string(), "1:13: image tag \"widht\": unknown tag");
+    assert_eq!(
+        res[1].to_string(),
+        "3:24: image tag \"loading\": expected lazy or eager, got later"
+    );
+}
@@ -1,13 +1,44 @@
-use pest::{
-    iterators::{Pair, Pairs},
-    Parser,
-};
-use std::{collections::HashMap, unimplemented};
+use alloc::sync::Arc;
+use core::{slice::Iter, unimplemented};
 
-use crate::{errors::Errcode, MarkdownParser, Rule};
+#[cfg(feature = "table-spans")]
+use crate::table::{fit_cells, is_simple_grid};
+use crate::{
+    citations::Bibliography,
+    codeblock::{extract_anchor, select_lines},
+    context::IdGenerator,
+    crossref::Crossrefs,
+    document::Tree,
+    errors::Errcode,
+    footnotes::FootnoteTable,
+    links::is_badge,
+    numbering::Numbering,
+    prelude::*,
+    table::{expand_cells, parse_delimited},
+    AlertKind, Alignment, CodeTheme, CodeblockInfo, CodeblockLines, CounterKind, CrossrefKind,
+    DiagramKind, Directive, ElementContext, ElementKind, EmptyElements, Footnote, ImageCandidate,
+    ImageTags, LinkKind, ListItemInfo, Node, ParagraphInfo, Phrase, Rule, Span, TableCell,
+    TransformOptions, UnknownVariables, Warning,
+};
 
+/// Hooks called for the elements of a document, in two passes: the peek pass (`peek_*` hooks)
+/// gathering information, then the transform pass (`transform_*` hooks) building the output
+///
+/// Within a pass, hooks are called in source order, the hooks of the elements contained in
+/// another one being called before its own. The only exception is the content of the footnote
+/// definitions, transformed where the footnotes section is emitted. `ElementContext::ordinal`
+/// gives the position of each element in source order, to number them deterministically.
 #[allow(unused_variables)]
 pub trait MarkdownTransformer {
+    /// Called right before the `peek_*` or `transform_*` hook of every element
+    fn element_context(&mut self, ctx: &ElementContext) {}
+
+    /// Text of a phrase generated rather than written in the document, in English unless
+    /// overridden, see `Localizer`
+    fn localize(&mut self, phrase: Phrase) -> String {
+        phrase.english().to_string()
+    }
+
     fn peek_text(&mut self, text: String) {}
     fn transform_text(&mut self, text: String) -> String {
         text
@@ -28,36 +59,147 @@ pub trait MarkdownTransformer {
         text
     }
 
+    /// `title` is the one of the reference definition (`[slug]: url "Title"`)
     fn peek_reflink(&mut self, text: String, slug: String) {}
-    fn transform_reflink(&mut self, text: String, slug: String) -> String {
+    fn transform_reflink(&mut self, text: String, slug: String, title: Option<String>) -> String {
         text
     }
 
-    fn peek_refurl(&mut self, slug: String, url: String) {}
-    fn transform_refurl(&mut self, slug: String, url: String) -> String {
+    fn peek_refurl(&mut self, slug: String, url: String, title: Option<String>) {}
+    fn transform_refurl(&mut self, slug: String, url: String, title: Option<String>) -> String {
         String::new()
     }
 
-    fn peek_link(&mut self, text: String, url: String) {}
-    fn transform_link(&mut self, text: String, url: String) -> String {
+    fn peek_link(&mut self, text: String, url: String, kind: LinkKind) {}
+    fn transform_link(&mut self, text: String, url: String, kind: LinkKind) -> String {
         text
     }
 
-    fn peek_image(&mut self, alt: String, url: String, add_tags: HashMap<String, String>) {}
-    fn transform_image(
+    /// Bare URLs in the text, `text` being the URL as written (`www.` URLs get an `http://` scheme)
+    fn peek_autolink(&mut self, text: String, url: String, kind: LinkKind) {
+        self.peek_link(text, url, kind)
+    }
+    fn transform_autolink(&mut self, text: String, url: String, kind: LinkKind) -> String {
+        self.transform_link(text, url, kind)
+    }
+
+    /// Links whose only content is a badge image, see `TransformOptions::badges`,
+    /// `kind` being the one of `target_url`
+    fn peek_badge(&mut self, alt: String, img_url: String, target_url: String, kind: LinkKind) {
+        self.peek_image(alt, img_url, ImageTags::default());
+        self.peek_link(String::new(), target_url, kind)
+    }
+    fn transform_badge(
         &mut self,
         alt: String,
-        url: String,
-        add_tags: HashMap<String, String>,
+        img_url: String,
+        target_url: String,
+        kind: LinkKind,
     ) -> String {
+        let image = self.transform_image(alt, img_url, ImageTags::default());
+        self.transform_link(image, target_url, kind)
+    }
+
+    fn peek_footnote_ref(&mut self, label: String, number: usize) {}
+    fn transform_footnote_ref(&mut self, label: String, number: usize) -> String {
+        format!("[{number}]")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        entries
+            .into_iter()
+            .map(|f| format!("[{}]: {}", f.number, f.text))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// `[#slug]` references, `resolved` being the text of a heading or the number of a figure
+    /// (`TransformOptions::crossrefs`)
+    fn peek_crossref(&mut self, kind: CrossrefKind, slug: String, resolved: String) {}
+    fn transform_crossref(&mut self, kind: CrossrefKind, slug: String, resolved: String) -> String {
+        match kind {
+            CrossrefKind::Header => resolved,
+            CrossrefKind::Figure => {
+                let label = self.localize(Phrase::Counter(CounterKind::Figure));
+                format!("{label} {resolved}")
+            }
+        }
+    }
+
+    /// `[@key]` or `[@key; @other]` citations (`TransformOptions::citations`)
+    fn peek_citation(&mut self, keys: Vec<String>) {}
+    fn transform_citation(&mut self, keys: Vec<String>) -> String {
+        format!("[{}]", keys.join("; "))
+    }
+
+    /// Keys cited in the document in order of first citation, at the `[BIBLIOGRAPHY]` marker
+    /// or at the end of the document
+    fn transform_bibliography(&mut self, keys: Vec<String>) -> String {
+        String::new()
+    }
+
+    fn peek_shortcode(&mut self, name: String, args: Vec<String>) {}
+    fn transform_shortcode(&mut self, name: String, args: Vec<String>) -> String {
+        String::new()
+    }
+
+    fn peek_image(&mut self, alt: String, url: String, add_tags: ImageTags) {}
+    fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
         alt
     }
 
+    /// Images with the variants returned by `TransformOptions::image_resolver`, called
+    /// instead of the `*_image` hooks when there is any
+    fn peek_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) {
+        self.peek_image(alt, url, add_tags)
+    }
+    fn transform_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) -> String {
+        self.transform_image(alt, url, add_tags)
+    }
+
+    /// Image with a `caption` tag, `image` being the output of `transform_image`
+    fn peek_figure(&mut self, caption: String) {}
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        image
+    }
+
     fn peek_comment(&mut self, text: String) {}
     fn transform_comment(&mut self, text: String) -> String {
         text
     }
 
+    /// Comments `<!-- mdtrans: name -->` once `TransformOptions::directives` is enabled,
+    /// falling back to the comment hooks
+    fn peek_directive(&mut self, directive: Directive) {
+        self.peek_comment(format!("mdtrans: {}", directive.name()))
+    }
+    fn transform_directive(&mut self, directive: Directive) -> String {
+        self.transform_comment(format!("mdtrans: {}", directive.name()))
+    }
+
+    /// Markdown source of the block following a `raw` directive
+    fn peek_raw_block(&mut self, source: String) {}
+    fn transform_raw_block(&mut self, source: String) -> String {
+        source
+    }
+
+    fn peek_html_block(&mut self, tag: String, html: String) {}
+    fn transform_html_block(&mut self, tag: String, html: String) -> String {
+        html
+    }
+
     // TODO    Strikethrough
     fn peek_strikethrough(&mut self, text: String) {}
     fn transform_strikethrough(&mut self, text: String) -> String {
@@ -69,11 +211,79 @@ pub trait MarkdownTransformer {
         text
     }
 
-    fn peek_codeblock(&mut self, language: Option<String>, text: String) {}
-    fn transform_codeblock(&mut self, language: Option<String>, text: String) -> String {
+    /// Quotes starting with a `[!KIND]` line, falling back to the quote hooks
+    fn peek_alert(&mut self, kind: AlertKind, body: String) {
+        self.peek_quote(body)
+    }
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        self.transform_quote(body)
+    }
+
+    /// Collapsible sections `??? summary` (feature `details`), `body` being their transformed
+    /// blocks, falling back to the summary as a paragraph followed by the body
+    fn peek_details(&mut self, summary: String, body: String) {
+        self.peek_paragraph(summary)
+    }
+    fn transform_details(&mut self, summary: String, body: String) -> String {
+        let mut text = self.transform_paragraph(summary);
+        text += &body;
         text
     }
 
+    /// Groups of tabs (feature `tabs`), as their titles and transformed blocks, falling back
+    /// to the title of each tab as a paragraph followed by its blocks
+    fn peek_tab_group(&mut self, tabs: Vec<(String, String)>) {
+        for (title, _) in tabs {
+            self.peek_paragraph(title);
+        }
+    }
+    fn transform_tab_group(&mut self, tabs: Vec<(String, String)>) -> String {
+        let mut text = String::new();
+        for (title, body) in tabs {
+            text += &self.transform_paragraph(title);
+            text += &body;
+        }
+        text
+    }
+
+    /// `flags` are the words following the language on the opening fence (```` ```rust,ignore ````)
+    fn peek_codeblock(&mut self, language: Option<String>, flags: Vec<String>, text: String) {}
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        flags: Vec<String>,
+        text: String,
+    ) -> String {
+        text
+    }
+
+    /// Code blocks with lines to highlight or to number, or to render in several color themes
+    /// (`TransformOptions::code_themes`), called instead of the code block hooks
+    fn peek_codeblock_with_info(
+        &mut self,
+        language: Option<String>,
+        text: String,
+        info: CodeblockInfo,
+    ) {
+        self.peek_codeblock(language, info.flags, text)
+    }
+    fn transform_codeblock_with_info(
+        &mut self,
+        language: Option<String>,
+        text: String,
+        info: CodeblockInfo,
+    ) -> String {
+        self.transform_codeblock(language, info.flags, text)
+    }
+
+    /// Code blocks written in a diagram language, falling back to the code block hooks
+    fn peek_diagram(&mut self, kind: DiagramKind, source: String) {
+        self.peek_codeblock(Some(kind.language().to_string()), vec![], source)
+    }
+    fn transform_diagram(&mut self, kind: DiagramKind, source: String) -> String {
+        self.transform_codeblock(Some(kind.language().to_string()), vec![], source)
+    }
+
     fn peek_inline_code(&mut self, text: String) {}
     fn transform_inline_code(&mut self, text: String) -> String {
         text
@@ -89,11 +299,121 @@ pub trait MarkdownTransformer {
         elements.join(", ")
     }
 
-    fn peek_list_element(&mut self, element: String) {}
-    fn transform_list_element(&mut self, element: String) -> String {
+    fn peek_ordered_list(&mut self, start: usize, elements: Vec<String>) {
+        self.peek_list(elements)
+    }
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        self.transform_list(elements)
+    }
+
+    fn peek_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) {
+    }
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let mut lines = vec![header.join(" | ")];
+        lines.extend(rows.into_iter().map(|row| row.join(" | ")));
+        lines.join("\n")
+    }
+
+    /// Tables with cells spanning several columns or lines (feature `table-spans`), the other
+    /// tables being given to `transform_table`. Falls back to `transform_table` with the
+    /// columns spanned left empty
+    fn peek_table_with_spans(
+        &mut self,
+        header: Vec<TableCell>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<TableCell>>,
+    ) {
+        let rows = rows.into_iter().map(expand_cells).collect();
+        self.peek_table(expand_cells(header), alignments, rows)
+    }
+    fn transform_table_with_spans(
+        &mut self,
+        header: Vec<TableCell>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<TableCell>>,
+    ) -> String {
+        let rows = rows.into_iter().map(expand_cells).collect();
+        self.transform_table(expand_cells(header), alignments, rows)
+    }
+
+    fn peek_list_element(&mut self, element: String, info: ListItemInfo) {}
+    fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
         element
     }
 
+    /// Blocks that couldn't be parsed (`ParseMode::Lenient`), falling back to a plain text paragraph
+    fn peek_invalid(&mut self, raw: String) {
+        self.peek_text(raw.clone());
+        self.peek_paragraph(raw)
+    }
+    fn transform_invalid(&mut self, raw: String) -> String {
+        let text = self.transform_text(raw);
+        self.transform_paragraph(text)
+    }
+
+    /// Wrappers emitted before and after the content of container elements, the content
+    /// still being passed to their `transform_*` hook
+    ///
+    /// They are only called during the transform pass, without any element context.
+    fn enter_header(&mut self, level: usize) -> String {
+        String::new()
+    }
+    fn exit_header(&mut self, level: usize) -> String {
+        String::new()
+    }
+
+    fn enter_paragraph(&mut self) -> String {
+        String::new()
+    }
+    fn exit_paragraph(&mut self) -> String {
+        String::new()
+    }
+
+    fn enter_quote(&mut self) -> String {
+        String::new()
+    }
+    fn exit_quote(&mut self) -> String {
+        String::new()
+    }
+
+    fn enter_list(&mut self) -> String {
+        String::new()
+    }
+    fn exit_list(&mut self) -> String {
+        String::new()
+    }
+
+    fn enter_ordered_list(&mut self, start: usize) -> String {
+        self.enter_list()
+    }
+    fn exit_ordered_list(&mut self, start: usize) -> String {
+        self.exit_list()
+    }
+
+    fn enter_list_element(&mut self, info: ListItemInfo) -> String {
+        String::new()
+    }
+    fn exit_list_element(&mut self, info: ListItemInfo) -> String {
+        String::new()
+    }
+
+    fn enter_table(&mut self) -> String {
+        String::new()
+    }
+    fn exit_table(&mut self) -> String {
+        String::new()
+    }
+
     fn peek_vertical_space(&mut self) {}
     fn transform_vertical_space(&mut self) -> String {
         "\n".to_string()
@@ -104,11 +424,41 @@ pub trait MarkdownTransformer {
         text
     }
 
+    /// Paragraphs along with their direction and language, called instead of the `*_paragraph`
+    /// hooks once `TransformOptions::text_direction` is enabled, and for the paragraphs
+    /// starting with a `{lang=fr}` attribute
+    fn enter_paragraph_with_info(&mut self, info: ParagraphInfo) -> String {
+        self.enter_paragraph()
+    }
+    fn exit_paragraph_with_info(&mut self, info: ParagraphInfo) -> String {
+        self.exit_paragraph()
+    }
+    fn peek_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) {
+        self.peek_paragraph(text)
+    }
+    fn transform_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) -> String {
+        self.transform_paragraph(text)
+    }
+
     fn finished(&mut self, peek: bool) -> String {
         "".to_string()
     }
 }
 
+/// Transformer keeping state across elements (reference maps, counters, ...),
+/// that can be reused for several documents
+pub trait StatefulTransformer: MarkdownTransformer {
+    type Snapshot;
+
+    /// Forget everything gathered from the previous documents, keeping the configuration
+    fn reset(&mut self);
+
+    fn snapshot(&self) -> Self::Snapshot;
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
+
+#[cfg(feature = "std")]
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run_io` instead")]
 pub fn transform_markdown<F, O, T>(
     input: &mut F,
     output: &mut O,
@@ -119,48 +469,214 @@ where
     F: std::io::Read,
     O: std::io::Write,
 {
-    let mut md_string = String::new();
-    input.read_to_string(&mut md_string)?;
-    let Some(parsed) = MarkdownParser::parse(Rule::file, &md_string)?.next() else {
-        return Err(Errcode::ParsingError(
-            "Parsed input returned an empty tree".to_string(),
-        ));
-    };
+    transform_io(input, output, transformer, &TransformOptions::default())
+}
 
-    let mut parser = TransformFramework::new(transformer);
-    parser.act_on_pair(&mut ParseState::peek(), parsed.clone());
-    parser.transformer.finished(true);
-    let mut result = parser.act_on_pair(&mut ParseState::default(), parsed);
-    result += parser.transformer.finished(false).as_str();
-    Ok(output.write(result.as_bytes())?)
+#[cfg(feature = "std")]
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run_io` instead")]
+pub fn transform_markdown_with_options<F, O, T>(
+    input: &mut F,
+    output: &mut O,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<usize, Errcode>
+where
+    T: MarkdownTransformer,
+    F: std::io::Read,
+    O: std::io::Write,
+{
+    transform_io(input, output, transformer, options)
 }
 
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run` instead")]
 pub fn transform_markdown_string<T>(input: String, transformer: &mut T) -> Result<String, Errcode>
 where
     T: MarkdownTransformer,
 {
-    let Some(parsed) = MarkdownParser::parse(Rule::file, &input)?.next() else {
-        return Err(Errcode::ParsingError(
-            "Parsed input returned an empty tree".to_string(),
-        ));
+    transform_input(&input, transformer, &TransformOptions::default())
+}
+
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run` instead")]
+pub fn transform_markdown_string_with_options<T>(
+    input: String,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<String, Errcode>
+where
+    T: MarkdownTransformer,
+{
+    transform_input(&input, transformer, options)
+}
+
+/// Same as `transform_markdown_string_with_options`, but the state of the transformer is
+/// restored once the document is transformed, so nothing gathered from it leaks into the next one
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run_isolated` instead")]
+pub fn transform_markdown_string_isolated<T>(
+    input: String,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<String, Errcode>
+where
+    T: StatefulTransformer,
+{
+    Ok(Tree::parse(&input, options.parse_mode)?.transform_isolated(transformer, options))
+}
+
+/// Same as `transform_markdown_string_with_options`, also returning the content issues
+/// found in the document
+#[deprecated(since = "0.1.6", note = "use `Pipeline::run_with_warnings` instead")]
+pub fn transform_markdown_string_with_warnings<T>(
+    input: String,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<(String, Vec<Warning>), Errcode>
+where
+    T: MarkdownTransformer,
+{
+    Ok(Tree::parse(&input, options.parse_mode)?.transform_with_warnings(transformer, options))
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn transform_io<F, O, T>(
+    input: &mut F,
+    output: &mut O,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<usize, Errcode>
+where
+    T: MarkdownTransformer,
+    F: std::io::Read,
+    O: std::io::Write,
+{
+    let mut bytes = vec![];
+    input.read_to_end(&mut bytes)?;
+    // NOTE    Files written by Windows tools often start with a byte order mark
+    let bom = if bytes.starts_with(b"\xEF\xBB\xBF") {
+        3
+    } else {
+        0
     };
+    let md_string = match core::str::from_utf8(&bytes[bom..]) {
+        Ok(text) => std::borrow::Cow::Borrowed(text),
+        Err(_) if options.lossy_utf8 => String::from_utf8_lossy(&bytes[bom..]),
+        Err(err) => {
+            return Err(Errcode::InvalidUtf8 {
+                offset: bom + err.valid_up_to(),
+            })
+        }
+    };
+    let result = transform_input(&md_string, transformer, options)?;
+    Ok(output.write(result.as_bytes())?)
+}
 
-    let mut parser = TransformFramework::new(transformer);
-    parser.act_on_pair(&mut ParseState::peek(), parsed.clone());
-    parser.transformer.finished(true);
-    let res = parser.act_on_pair(&mut ParseState::default(), parsed);
-    parser.transformer.finished(false);
-    Ok(res)
+pub(crate) fn transform_input<T>(
+    input: &str,
+    transformer: &mut T,
+    options: &TransformOptions,
+) -> Result<String, Errcode>
+where
+    T: MarkdownTransformer,
+{
+    Ok(Tree::parse(input, options.parse_mode)?.transform_with_options(transformer, options))
 }
 
-fn next_inner_string(inner: &mut Pairs<Rule>) -> Option<String> {
+/// Transform only the elements of the given kinds, the rest of the document being kept as it
+/// was written
+///
+/// The elements of the kinds are given to the transformer with everything they contain, the
+/// elements around them are left untouched, without any hook called for them. Their output
+/// replaces their markdown, so a targeted rewrite doesn't need a full `PassthroughTransformer`.
+///
+/// ```
+/// # use mdtrans::{transform_filtered, ElementKind, ImageTags, MarkdownTransformer};
+/// struct Cdn;
+/// impl MarkdownTransformer for Cdn {
+///     fn transform_image(&mut self, alt: String, url: String, _: ImageTags) -> String {
+///         format!("![{alt}](https://cdn.example.com/{url})")
+///     }
+/// }
+///
+/// let input = "# *Cats*\n\nA ![cat](cat.png) and a [link](cat.html).";
+/// let output = transform_filtered(input, &[ElementKind::Image], &mut Cdn).unwrap();
+/// assert_eq!(
+///     output,
+///     "# *Cats*\n\nA ![cat](https://cdn.example.com/cat.png) and a [link](cat.html)."
+/// );
+/// ```
+pub fn transform_filtered<T>(
+    input: &str,
+    kinds: &[ElementKind],
+    transformer: &mut T,
+) -> Result<String, Errcode>
+where
+    T: MarkdownTransformer,
+{
+    let options = TransformOptions::default();
+    Ok(Tree::parse(input, options.parse_mode)?.transform_filtered(kinds, transformer, &options))
+}
+
+fn next_inner_string(inner: &mut Iter<'_, Node>) -> Option<String> {
     inner.next().map(|p| p.as_str().to_string())
 }
 
+// NOTE    The checkbox of a task list item is given in the `ListItemInfo`, not as text
+fn skip_checkbox(inner: &mut Iter<'_, Node>) {
+    if inner
+        .as_slice()
+        .first()
+        .is_some_and(|child| child.rule() == Rule::task_checkbox)
+    {
+        inner.next();
+    }
+}
+
+// Paragraphs starting with a `{lang=fr}` attribute
+fn has_lang(paragraph: &Node) -> bool {
+    let first = paragraph.children().first();
+    first.is_some_and(|child| child.rule() == Rule::paragraph_lang)
+}
+
+// Alternative text and URL of the image of a link made of a badge image only
+fn badge_image(link: &Node, options: &TransformOptions) -> Option<(String, String)> {
+    let [image, _url] = link.children() else {
+        return None;
+    };
+    if image.rule() != Rule::image {
+        return None;
+    }
+    // NOTE    An image with tags is rendered as such, even when it is a badge
+    let (url, alt) = image.children().split_last()?;
+    if url.rule() != Rule::url || !is_badge(url.as_str(), options) {
+        return None;
+    }
+    let alt = alt.iter().map(|el| el.as_str()).collect::<String>();
+    Some((alt, url.as_str().to_string()))
+}
+
+// Kind of the element if it is a header, a list element or a link without any content
+fn empty_element(node: &Node) -> Option<ElementKind> {
+    let content = match node.rule() {
+        Rule::h1 | Rule::h2 | Rule::h3 | Rule::h4 | Rule::h5 | Rule::h6 => node.children(),
+        Rule::list_element | Rule::ordered_list_element => node.children(),
+        // NOTE    The URL is always the last element of a link
+        Rule::link => node.children().split_last().map(|(_, text)| text)?,
+        _ => return None,
+    };
+    content
+        .iter()
+        .filter(|child| child.rule() != Rule::list_number)
+        .all(|child| child.as_str().trim().is_empty())
+        .then(|| node.kind())
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct ParseState {
     peek: bool,
     add_space: bool,
+    list_depth: usize,
+    list_index: usize,
+    // Directive applying to the next blocks, `SkipStart` or `Raw`
+    directive: Option<Directive>,
 }
 
 impl ParseState {
@@ -171,21 +687,155 @@ impl ParseState {
             ..default
         }
     }
+
+    // State of the transform pass starting where the peek pass is, for the directives
+    // applying across blocks
+    #[cfg(feature = "parallel")]
+    pub(crate) fn transform_from(peek: &ParseState) -> ParseState {
+        ParseState {
+            directive: peek.directive,
+            ..Self::default()
+        }
+    }
 }
 
-struct TransformFramework<'a, T> {
-    transformer: &'a mut T,
+pub(crate) struct TransformFramework<'a, 'i, T> {
+    pub(crate) transformer: &'a mut T,
+    options: &'a TransformOptions,
+    pub(crate) footnotes: FootnoteTable<'i>,
+    pub(crate) bibliography: Bibliography,
+    pub(crate) ids: IdGenerator,
+    // NOTE    Shared with the workers of `transform_markdown_string_parallel`
+    pub(crate) numbering: Arc<Numbering>,
+    pub(crate) crossrefs: Arc<Crossrefs>,
+    pub(crate) warnings: Vec<Warning>,
+    includes: HashMap<String, Result<String, String>>,
+    image_candidates: HashMap<String, Vec<ImageCandidate>>,
+    #[cfg(feature = "reflinks")]
+    pub(crate) references: HashMap<String, Option<String>>,
 }
 
-impl<'a, T> TransformFramework<'a, T>
+impl<'a, 'i, T> TransformFramework<'a, 'i, T>
 where
     T: MarkdownTransformer,
 {
-    fn new(transformer: &mut T) -> TransformFramework<T> {
-        TransformFramework { transformer }
+    pub(crate) fn new(
+        transformer: &'a mut T,
+        options: &'a TransformOptions,
+    ) -> TransformFramework<'a, 'i, T> {
+        TransformFramework {
+            transformer,
+            options,
+            footnotes: FootnoteTable::default(),
+            bibliography: Bibliography::default(),
+            ids: IdGenerator::default(),
+            numbering: Arc::default(),
+            crossrefs: Arc::default(),
+            warnings: vec![],
+            includes: HashMap::new(),
+            image_candidates: HashMap::new(),
+            #[cfg(feature = "reflinks")]
+            references: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn run_pass(&mut self, mut state: ParseState, pair: &'i Node<'i>) -> String {
+        self.ids.reset();
+        self.index_document(pair);
+        self.act_on_pair(&mut state, pair)
+    }
+
+    // Numbers, anchors and cross-reference targets only depend on the document, not on the pass
+    pub(crate) fn index_document(&mut self, root: &'i Node<'i>) {
+        let numbering = Numbering::new(root, self.options);
+        self.crossrefs = Arc::new(Crossrefs::new(root, &numbering, self.options));
+        self.numbering = Arc::new(numbering);
+    }
+
+    pub(crate) fn peek_document(&mut self, root: &'i Node<'i>) {
+        self.run_pass(ParseState::peek(), root);
+        self.transformer.finished(true);
+    }
+
+    pub(crate) fn transform_document(&mut self, root: &'i Node<'i>) -> String {
+        let mut res = self.run_pass(ParseState::default(), root);
+        res += self.finish_document().as_str();
+        res += self.transformer.finished(false).as_str();
+        self.options.finish_output(res)
+    }
+
+    // Output of the outermost elements of the kinds with their spans, the elements around
+    // them are only walked through
+    pub(crate) fn act_on_kinds(
+        &mut self,
+        state: &mut ParseState,
+        pair: &'i Node<'i>,
+        kinds: &[ElementKind],
+        outputs: &mut Vec<(Span, String)>,
+    ) {
+        if kinds.contains(&pair.kind()) {
+            let output = self.act_on_pair(state, pair);
+            outputs.push((pair.span(), output));
+            return;
+        }
+        for child in pair.children() {
+            self.act_on_kinds(state, child, kinds, outputs);
+        }
     }
 
-    fn get_rich_text(&mut self, state: &ParseState, pair: Pair<Rule>) -> String {
+    pub(crate) fn footnotes_section(&mut self, state: &ParseState) -> String {
+        self.footnotes.section_emitted = true;
+        let entries = self
+            .footnotes
+            .sorted_definitions()
+            .into_iter()
+            .map(|(number, label, pair)| Footnote {
+                number,
+                label,
+                text: self.get_rich_text(state, pair),
+            })
+            .collect();
+        self.transformer.transform_footnotes_section(entries)
+    }
+
+    pub(crate) fn bibliography_section(&mut self) -> String {
+        self.bibliography.section_emitted = true;
+        let keys = self.bibliography.keys();
+        self.transformer.transform_bibliography(keys)
+    }
+
+    // Section of the `[FOOTNOTES]` marker, a marker written after the footnotes were already
+    // emitted being left out
+    pub(crate) fn footnotes_marker(&mut self, state: &ParseState, span: Span) -> String {
+        if self.footnotes.section_emitted {
+            let kind = ElementKind::FootnotesMarker;
+            self.warn(state, Warning::DuplicateMarker { kind, span });
+            return String::new();
+        }
+        self.footnotes_section(state)
+    }
+
+    pub(crate) fn bibliography_marker(&mut self, state: &ParseState, span: Span) -> String {
+        if self.bibliography.section_emitted {
+            let kind = ElementKind::BibliographyMarker;
+            self.warn(state, Warning::DuplicateMarker { kind, span });
+            return String::new();
+        }
+        self.bibliography_section()
+    }
+
+    pub(crate) fn finish_document(&mut self) -> String {
+        let mut res = String::new();
+        if !self.bibliography.section_emitted && !self.bibliography.is_empty() {
+            res += self.bibliography_section().as_str();
+        }
+        if !self.footnotes.section_emitted && self.footnotes.has_definitions() {
+            res += self.footnotes_section(&ParseState::default()).as_str();
+        }
+        res
+    }
+
+    fn get_rich_text(&mut self, state: &ParseState, pair: &'i Node<'i>) -> String {
         let mut child_state = state.clone();
         child_state.peek = false;
         self.act_on_pair(&mut child_state, pair)
@@ -195,7 +845,7 @@ where
         &mut self,
         state: &ParseState,
         nb: usize,
-        inner: &mut Pairs<Rule>,
+        inner: &mut Iter<'i, Node<'i>>,
     ) -> String {
         // NOTE     Fixed in the code, should never happen in real case scenario
         assert!(
@@ -205,14 +855,167 @@ where
             inner.len()
         );
         let mut child_state = state.clone();
-        let inners = (0..nb)
-            .map(|_| {
-                // NOTE    Unwrap as we get an assert on the number of elements before
-                let pair = inner.next().unwrap();
-                self.act_on_pair(&mut child_state, pair)
-            })
-            .collect::<Vec<String>>();
-        inners.join("")
+        let mut buffer = String::new();
+        if nb > 1 && !state.peek {
+            buffer.reserve(inner.clone().take(nb).map(|p| p.as_str().len()).sum());
+        }
+        for _ in 0..nb {
+            // NOTE    Unwrap as we get an assert on the number of elements before
+            let pair = inner.next().unwrap();
+            buffer += self.act_on_pair(&mut child_state, pair).as_str();
+        }
+        buffer
+    }
+
+    // Wrappers of container elements are only emitted during the transform pass
+    fn wrap<F>(&mut self, state: &ParseState, text: &mut String, hook: F)
+    where
+        F: FnOnce(&mut T) -> String,
+    {
+        if !state.peek {
+            *text += hook(self.transformer).as_str();
+        }
+    }
+
+    // Warnings are only reported during the transform pass, once everything is peeked
+    fn warn(&mut self, state: &ParseState, warning: Warning) {
+        if !state.peek {
+            self.warnings.push(warning);
+        }
+    }
+
+    // Context given to the transformer before the hooks of the element
+    fn context<'n>(&mut self, pair: &'n Node) -> ElementContext<'n> {
+        let (rule, pair_text) = (pair.rule(), pair.as_str());
+        let id = self.ids.next(rule, pair_text);
+        ElementContext::new(rule, pair.span(), pair_text, id, pair.ordinal())
+            .with_number(self.numbering.get(pair))
+            .with_anchor(self.crossrefs.anchor(pair))
+    }
+
+    // Output of the directive comments and of the blocks they apply to, `None` for the
+    // elements transformed as usual
+    fn apply_directives(&mut self, state: &mut ParseState, pair: &'i Node<'i>) -> Option<String> {
+        let rule = pair.rule();
+        if rule == Rule::EOI {
+            return None;
+        }
+        if rule == Rule::comment {
+            let comment_text = pair.children().first()?.as_str();
+            if let Some(directive) = Directive::from_comment(comment_text) {
+                match (state.directive, directive) {
+                    (Some(Directive::SkipStart), Directive::SkipEnd) => state.directive = None,
+                    (Some(Directive::SkipStart), _) => return Some(String::new()),
+                    (_, Directive::SkipEnd) => {}
+                    (_, directive) => state.directive = Some(directive),
+                }
+                let ctx = self.context(pair);
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_directive(directive);
+                    return Some(String::new());
+                }
+                return Some(self.transformer.transform_directive(directive));
+            }
+        }
+        match state.directive? {
+            Directive::SkipStart => Some(String::new()),
+            Directive::Raw => {
+                state.directive = None;
+                let source = pair.as_str().to_string();
+                let ctx = self.context(pair);
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_raw_block(source);
+                    return Some(String::new());
+                }
+                Some(self.transformer.transform_raw_block(source))
+            }
+            Directive::SkipEnd => None,
+        }
+    }
+
+    // Whether the element is left out as it has no content, reporting it if asked to
+    fn skip_empty(&mut self, state: &ParseState, pair: &Node) -> bool {
+        let policy = self.options.empty_elements;
+        if policy == EmptyElements::Skip && matches!(pair.rule(), Rule::list | Rule::ordered_list) {
+            return pair.children().iter().all(|el| empty_element(el).is_some());
+        }
+        let Some(kind) = empty_element(pair) else {
+            return false;
+        };
+        match policy {
+            EmptyElements::Dispatch => false,
+            EmptyElements::Skip => true,
+            EmptyElements::Warn => {
+                // NOTE    Links without text are already reported as `Warning::EmptyLinkText`
+                if kind != ElementKind::Link {
+                    let span = pair.span();
+                    self.warn(state, Warning::EmptyElement { kind, span });
+                }
+                false
+            }
+        }
+    }
+
+    // Code of a block including a file (`file=path anchor=name lines=a-b`) when an include resolver is set,
+    // the flags left once the ones of the include are removed
+    fn include_code(
+        &mut self,
+        state: &ParseState,
+        ctx: &ElementContext,
+        code: String,
+        flags: Vec<String>,
+    ) -> (String, Vec<String>) {
+        let options = self.options;
+        let Some(ref resolver) = options.include_resolver else {
+            return (code, flags);
+        };
+        if !flags.iter().any(|flag| flag.starts_with("file=")) {
+            return (code, flags);
+        }
+        let (include, flags): (Vec<String>, Vec<String>) = flags.into_iter().partition(|flag| {
+            ["file=", "anchor=", "lines="]
+                .iter()
+                .any(|name| flag.starts_with(name))
+        });
+        let attr = |name: &str| include.iter().find_map(|flag| flag.strip_prefix(name));
+        // NOTE    Safe to unwrap, the presence of the flag was checked above
+        let path = attr("file=").unwrap();
+        let content = self
+            .includes
+            .entry(path.to_string())
+            .or_insert_with(|| resolver(path))
+            .clone();
+        let region = content.and_then(|content| match attr("anchor=") {
+            Some(anchor) => extract_anchor(&content, anchor)
+                .ok_or_else(|| format!("no anchor \"{anchor}\" in the file")),
+            None => Ok(content),
+        });
+        match region.and_then(|region| select_lines(&region, attr("lines="))) {
+            Ok(included) => (included, flags),
+            Err(error) => {
+                let warning = Warning::IncludeFailed {
+                    path: path.to_string(),
+                    error,
+                    span: ctx.span(),
+                };
+                self.warn(state, warning);
+                (code, flags)
+            }
+        }
+    }
+
+    // Variants of an image given by the image resolver, none without a resolver
+    fn image_candidates(&mut self, url: &str) -> Vec<ImageCandidate> {
+        let options = self.options;
+        let Some(ref resolver) = options.image_resolver else {
+            return vec![];
+        };
+        self.image_candidates
+            .entry(url.to_string())
+            .or_insert_with(|| resolver(url))
+            .clone()
     }
 
     fn act_on_raw_text(&mut self, state: &mut ParseState, text: String) -> String {
@@ -224,24 +1027,38 @@ where
         }
     }
 
-    fn get_whole_block(&self, inner: &mut Pairs<Rule>, join: &str) -> String {
+    fn get_whole_block(&self, inner: &mut Iter<'i, Node<'i>>, join: &str) -> String {
         let mut buffer = "".to_string();
         for text_line in inner {
             buffer += text_line.as_str();
             buffer += join;
         }
-        let end = buffer.len() - join.len();
+        // NOTE    Blocks can be empty, like the code blocks including a file
+        let end = buffer.len().saturating_sub(join.len());
         buffer[..end].to_string()
     }
 
-    fn get_metadata(
+    fn get_list_elements(
         &mut self,
         state: &ParseState,
-        all_data: &mut Pairs<Rule>,
-    ) -> HashMap<String, String> {
-        let mut md = HashMap::new();
+        elements: Iter<'i, Node<'i>>,
+    ) -> Vec<String> {
+        let skip = self.options.empty_elements == EmptyElements::Skip;
+        elements
+            .filter(|element| !skip || empty_element(element).is_none())
+            .enumerate()
+            .map(|(index, element)| {
+                let mut child_state = state.clone();
+                child_state.list_index = index;
+                self.act_on_pair(&mut child_state, element)
+            })
+            .collect()
+    }
+
+    fn get_metadata(&mut self, state: &ParseState, all_data: &mut Iter<'i, Node<'i>>) -> ImageTags {
+        let mut md = ImageTags::default();
         for kv in all_data.by_ref() {
-            let mut inner = kv.into_inner();
+            let mut inner = kv.children().iter();
             let mut state = state.clone();
             let Some(key) = inner.next() else {
                 break;
@@ -249,22 +1066,109 @@ where
             let Some(val) = inner.next() else {
                 break;
             };
+            // NOTE    The quotes of the values having a meaning of their own are removed before
+            //         the transformer sees them, the other ones keep typing the value as a string
+            let raw_val = match key.as_str() {
+                "width" | "height" | "caption" => val.as_str().trim_matches('"'),
+                _ => val.as_str(),
+            };
             let text_key = self.act_on_pair(&mut state, key);
-            let text_val = self.act_on_pair(&mut state, val);
-            md.insert(text_key, text_val);
+            let text_val = self.act_on_raw_text(&mut state, raw_val.to_string());
+            let options = self.options;
+            if let Some(ref schema) = options.image_tag_schema {
+                let value = val.as_str().trim_matches('"');
+                if let Err(reason) = schema.validate(key.as_str(), value) {
+                    let warning = Warning::InvalidImageTag {
+                        key: key.as_str().to_string(),
+                        reason,
+                        span: kv.span(),
+                    };
+                    self.warn(&state, warning);
+                }
+            }
+            // NOTE    The text hooks give nothing back in the peek pass, the tags are given as written
+            match state.peek {
+                true => md.insert(key.as_str().to_string(), raw_val.to_string()),
+                false => md.insert(text_key, text_val),
+            };
         }
         md
     }
 
+    // NOTE    Like the headers, the cells are given as rendered text in the peek pass too
+    fn get_table_row(&mut self, state: &ParseState, row: &'i Node<'i>) -> Vec<String> {
+        row.children()
+            .iter()
+            .filter(|cell| cell.rule() == Rule::table_cell)
+            .map(|cell| match cell.children().first() {
+                Some(content) => self.get_rich_text(state, content),
+                None => {
+                    let mut child_state = state.clone();
+                    child_state.peek = false;
+                    self.act_on_raw_text(&mut child_state, cell.as_str().trim().to_string())
+                }
+            })
+            .collect()
+    }
+
+    // NOTE    An empty cell `||` extends the one before it, the rows ending with a backslash
+    //         have the lines of their cells continued by the next row
+    #[cfg(feature = "table-spans")]
+    fn get_spanned_rows(
+        &mut self,
+        state: &ParseState,
+        rows: &'i [Node<'i>],
+    ) -> Vec<Vec<TableCell>> {
+        let mut spanned: Vec<Vec<TableCell>> = vec![];
+        let mut continued = false;
+        for row in rows {
+            let texts = self.get_table_row(state, row);
+            let sources = row
+                .children()
+                .iter()
+                .filter(|c| c.rule() == Rule::table_cell);
+            let mut cells: Vec<TableCell> = vec![];
+            for (source, text) in sources.zip(texts) {
+                match cells.last_mut() {
+                    Some(previous) if source.as_str().is_empty() => previous.colspan += 1,
+                    _ => cells.push(TableCell::new(text)),
+                }
+            }
+            match spanned.last_mut() {
+                Some(previous) if continued => {
+                    for (n, cell) in cells.into_iter().enumerate() {
+                        match previous.get_mut(n) {
+                            Some(above) if above.text.is_empty() => above.text = cell.text,
+                            Some(above) if !cell.text.is_empty() => {
+                                above.text.push('\n');
+                                above.text += &cell.text;
+                            }
+                            Some(_) => {}
+                            None => previous.push(cell),
+                        }
+                    }
+                }
+                _ => spanned.push(cells),
+            }
+            continued = row
+                .children()
+                .last()
+                .is_some_and(|child| child.rule() == Rule::table_row_continue);
+        }
+        spanned
+    }
+
     fn is_raw_text(&self, rule: &Rule) -> bool {
+        #[cfg(feature = "image-tags")]
+        if matches!(rule, Rule::img_tag_key | Rule::img_tag_val) {
+            return true;
+        }
         matches!(
             rule,
             Rule::text
                 | Rule::link_text
                 | Rule::inline_code_code
                 | Rule::codeblock_code
-                | Rule::img_tag_key
-                | Rule::img_tag_val
                 | Rule::comment_text
         )
     }
@@ -281,158 +1185,374 @@ where
                 | Rule::strike
                 | Rule::italic
                 | Rule::link
+                | Rule::autolink
+                | Rule::shortcode
+                | Rule::crossref
+                | Rule::citation
         )
     }
 
-    fn act_on_pair(&mut self, state: &mut ParseState, pair: Pair<Rule>) -> String {
+    pub(crate) fn act_on_pair(&mut self, state: &mut ParseState, pair: &'i Node<'i>) -> String {
         let mut text: String = "".to_string();
-        let rule = pair.as_rule();
+        if self.options.directives {
+            if let Some(text) = self.apply_directives(state, pair) {
+                return text;
+            }
+        }
+        if self.skip_empty(state, pair) {
+            return text;
+        }
+        let rule = pair.rule();
         if state.add_space && self.is_inline(&rule) {
             text += " ";
             state.add_space = false;
         }
+        if let Some(renderer) = self.options.renderer(rule) {
+            let pair_text = pair.as_str();
+            let inners = pair
+                .children()
+                .iter()
+                .map(|child| {
+                    // NOTE    Parts like URLs or list numbers have no hook, they are given as written
+                    let is_part = child.kind() == ElementKind::Part && child.children().is_empty();
+                    if is_part && !self.is_raw_text(&child.rule()) {
+                        child.as_str().to_string()
+                    } else {
+                        self.act_on_pair(state, child)
+                    }
+                })
+                .collect::<Vec<String>>();
+            if !state.peek {
+                text += renderer(pair_text, inners).as_str();
+            }
+            return text;
+        }
         if self.is_raw_text(&rule) {
             let raw_text = pair.as_str().to_string();
             text += self.act_on_raw_text(state, raw_text).as_str();
             return text;
         }
         let pair_text = pair.as_str();
-        let mut inner = pair.into_inner();
+        let ctx = self.context(pair);
+        let mut inner = pair.children().iter();
         match rule {
-            Rule::h1 => {
-                assert_eq!(inner.len(), 1, "Grammar error on h1, expected rich_txt");
+            Rule::h1 | Rule::h2 | Rule::h3 | Rule::h4 | Rule::h5 | Rule::h6 => {
+                assert_eq!(
+                    inner.len(),
+                    1,
+                    "Grammar error on {rule:?}, expected rich_txt"
+                );
+                let level = match rule {
+                    Rule::h1 => 1,
+                    Rule::h2 => 2,
+                    Rule::h3 => 3,
+                    Rule::h4 => 4,
+                    Rule::h5 => 5,
+                    _ => 6,
+                };
+                let level = self.options.remap_heading(level);
+                self.wrap(state, &mut text, |t| t.enter_header(level));
                 let header_text = self.get_rich_text(state, inner.next().unwrap());
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_header(1, header_text);
+                    self.transformer.peek_header(level, header_text);
                 } else {
-                    text += self.transformer.transform_header(1, header_text).as_str();
+                    text += self
+                        .transformer
+                        .transform_header(level, header_text)
+                        .as_str();
                 }
+                self.wrap(state, &mut text, |t| t.exit_header(level));
             }
 
-            Rule::h2 => {
-                assert_eq!(inner.len(), 1, "Grammar error on h2, expected rich_txt");
-                let header_text = self.get_rich_text(state, inner.next().unwrap());
+            Rule::italic => {
+                let italic_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_header(2, header_text);
+                    self.transformer.peek_italic(italic_text)
                 } else {
-                    text += self.transformer.transform_header(2, header_text).as_str();
+                    text += self.transformer.transform_italic(italic_text).as_str();
                 }
             }
 
-            Rule::h3 => {
-                assert_eq!(inner.len(), 1, "Grammar error on h3, expected rich_txt");
-                let header_text = self.get_rich_text(state, inner.next().unwrap());
+            Rule::bold => {
+                let bold_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_header(3, header_text);
+                    self.transformer.peek_bold(bold_text);
                 } else {
-                    text += self.transformer.transform_header(3, header_text).as_str();
+                    text += self.transformer.transform_bold(bold_text).as_str();
                 }
             }
 
-            Rule::h4 => {
-                assert_eq!(inner.len(), 1, "Grammar error on h4, expected rich_txt");
-                let header_text = self.get_rich_text(state, inner.next().unwrap());
+            Rule::strike => {
+                let strike_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_header(4, header_text);
+                    self.transformer.peek_strikethrough(strike_text)
                 } else {
-                    text += self.transformer.transform_header(4, header_text).as_str();
+                    text += self
+                        .transformer
+                        .transform_strikethrough(strike_text)
+                        .as_str();
                 }
             }
 
-            Rule::h5 => {
-                assert_eq!(inner.len(), 1, "Grammar error on h5, expected rich_txt");
-                let header_text = self.get_rich_text(state, inner.next().unwrap());
+            Rule::link if self.options.badges && badge_image(pair, self.options).is_some() => {
+                // NOTE    Safe to unwrap as checked by the guard
+                let (alt, img_url) = badge_image(pair, self.options).unwrap();
+                let target_url = pair.children().last().unwrap().as_str().to_string();
+                if alt.trim().is_empty() {
+                    let warning = Warning::MissingAltText {
+                        url: img_url.clone(),
+                        span: ctx.span(),
+                    };
+                    self.warn(state, warning);
+                }
+                let kind = LinkKind::classify(&target_url, self.options.site_host.as_deref());
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_header(5, header_text);
+                    self.transformer.peek_badge(alt, img_url, target_url, kind);
                 } else {
-                    text += self.transformer.transform_header(5, header_text).as_str();
+                    text += self
+                        .transformer
+                        .transform_badge(alt, img_url, target_url, kind)
+                        .as_str();
                 }
             }
 
-            Rule::h6 => {
-                assert_eq!(inner.len(), 1, "Grammar error on h6, expected rich_txt");
-                let header_text = self.get_rich_text(state, inner.next().unwrap());
+            Rule::link => {
+                let empty_text = inner.as_slice()[..inner.len() - 1]
+                    .iter()
+                    .all(|el| el.as_str().trim().is_empty());
+                let link_text = self.get_inner_elements(state, inner.len() - 1, &mut inner);
+                // NOTE    Safe to unwrap as we got all elements except one from iterator
+                let url = next_inner_string(&mut inner).unwrap();
+                if empty_text {
+                    let warning = Warning::EmptyLinkText {
+                        url: url.clone(),
+                        span: ctx.span(),
+                    };
+                    self.warn(state, warning);
+                }
+                let kind = LinkKind::classify(&url, self.options.site_host.as_deref());
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_header(6, header_text);
+                    self.transformer.peek_link(link_text, url, kind);
                 } else {
-                    text += self.transformer.transform_header(6, header_text).as_str();
+                    text += self
+                        .transformer
+                        .transform_link(link_text, url, kind)
+                        .as_str();
                 }
             }
 
-            Rule::italic => {
-                let italic_text = self.get_inner_elements(state, inner.len(), &mut inner);
+            Rule::autolink => {
+                let url = if pair_text.starts_with("www.") {
+                    format!("http://{pair_text}")
+                } else {
+                    pair_text.to_string()
+                };
+                let kind = LinkKind::classify(&url, self.options.site_host.as_deref());
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_italic(italic_text)
+                    self.transformer
+                        .peek_autolink(pair_text.to_string(), url, kind);
                 } else {
-                    text += self.transformer.transform_italic(italic_text).as_str();
+                    text += self
+                        .transformer
+                        .transform_autolink(pair_text.to_string(), url, kind)
+                        .as_str();
                 }
             }
 
-            Rule::bold => {
-                let bold_text = self.get_inner_elements(state, inner.len(), &mut inner);
+            #[cfg(feature = "reflinks")]
+            Rule::reflink => {
+                let link_text = self.get_inner_elements(state, inner.len() - 1, &mut inner);
+                // NOTE    Safe to unwrap as we got all elements except one from iterator
+                let slug = next_inner_string(&mut inner).unwrap();
+                // NOTE    Titles are only known once all the definitions are peeked
+                let title = self.references.get(&slug).cloned();
+                if title.is_none() {
+                    let warning = Warning::UnresolvedReference {
+                        label: slug.clone(),
+                        span: ctx.span(),
+                    };
+                    self.warn(state, warning);
+                }
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_bold(bold_text);
+                    self.transformer.peek_reflink(link_text, slug);
                 } else {
-                    text += self.transformer.transform_bold(bold_text).as_str();
+                    text += self
+                        .transformer
+                        .transform_reflink(link_text, slug, title.flatten())
+                        .as_str();
                 }
             }
 
-            Rule::strike => {
-                let strike_text = self.get_inner_elements(state, inner.len(), &mut inner);
+            #[cfg(feature = "reflinks")]
+            Rule::refurl => {
+                // NOTE the grammar should always match 2 elements, and an optional title
+                assert!(
+                    inner.len() >= 2,
+                    "Grammar error on refurl, expected at least 2 inners"
+                );
+                let slug = next_inner_string(&mut inner).unwrap();
+                let url = next_inner_string(&mut inner).unwrap();
+                let title = next_inner_string(&mut inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_strikethrough(strike_text)
+                    self.references.insert(slug.clone(), title.clone());
+                    self.transformer.peek_refurl(slug, url, title);
                 } else {
-                    text += self.transformer.transform_strikethrough(strike_text).as_str();
+                    text += self.transformer.transform_refurl(slug, url, title).as_str();
                 }
             }
 
-            Rule::link => {
-                let link_text = self.get_inner_elements(state, inner.len() - 1, &mut inner);
-                // NOTE    Safe to unwrap as we got all elements except one from iterator
-                let url = next_inner_string(&mut inner).unwrap();
+            Rule::footnote_ref => {
+                let label = next_inner_string(&mut inner).unwrap();
+                let number = self.footnotes.number(&label);
+                if !self.footnotes.is_defined(&label) {
+                    let warning = Warning::UnresolvedReference {
+                        label: label.clone(),
+                        span: ctx.span(),
+                    };
+                    self.warn(state, warning);
+                }
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_link(link_text, url);
+                    self.transformer.peek_footnote_ref(label, number);
                 } else {
-                    text += self.transformer.transform_link(link_text, url).as_str();
+                    text += self
+                        .transformer
+                        .transform_footnote_ref(label, number)
+                        .as_str();
                 }
             }
 
-            Rule::reflink => {
-                let link_text = self.get_inner_elements(state, inner.len() - 1, &mut inner);
-                // NOTE    Safe to unwrap as we got all elements except one from iterator
-                let slug = next_inner_string(&mut inner).unwrap();
+            Rule::footnote_def => {
+                assert_eq!(
+                    inner.len(),
+                    2,
+                    "Grammar error on footnote_def, expected 2 inners"
+                );
                 if state.peek {
-                    self.transformer.peek_reflink(link_text, slug);
+                    let label = next_inner_string(&mut inner).unwrap();
+                    self.footnotes.define(label, inner.next().unwrap());
+                }
+            }
+
+            Rule::footnotes_marker if state.peek => {}
+            Rule::footnotes_marker => text += self.footnotes_marker(state, ctx.span()).as_str(),
+
+            Rule::citation if !self.options.citations => {
+                text += self.act_on_raw_text(state, pair_text.to_string()).as_str();
+            }
+
+            Rule::citation => {
+                let keys = inner
+                    .map(|key| key.as_str().to_string())
+                    .collect::<Vec<String>>();
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    for key in keys.iter() {
+                        self.bibliography.cite(key);
+                    }
+                    self.transformer.peek_citation(keys);
                 } else {
-                    text += self.transformer.transform_reflink(link_text, slug).as_str();
+                    text += self.transformer.transform_citation(keys).as_str();
                 }
             }
 
-            Rule::refurl => {
-                // NOTE the grammar should always match 2 elements, and no more than that
-                assert_eq!(inner.len(), 2, "Grammar error on refurl, expected 2 inners");
-                let slug = next_inner_string(&mut inner).unwrap();
-                let url = next_inner_string(&mut inner).unwrap();
+            // NOTE    The marker is a paragraph of plain text when the citations are disabled
+            Rule::bibliography_marker if !self.options.citations => {
+                self.wrap(state, &mut text, |t| t.enter_paragraph());
+                let marker = self.act_on_raw_text(state, pair_text.to_string());
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_refurl(slug, url);
+                    self.transformer.peek_paragraph(marker);
                 } else {
-                    text += self.transformer.transform_refurl(slug, url).as_str();
+                    text += self.transformer.transform_paragraph(marker).as_str();
                 }
+                self.wrap(state, &mut text, |t| t.exit_paragraph());
+            }
+            Rule::bibliography_marker if state.peek => {}
+            Rule::bibliography_marker => {
+                text += self.bibliography_marker(state, ctx.span()).as_str()
             }
 
             Rule::quote => {
+                self.wrap(state, &mut text, |t| t.enter_quote());
                 let lines = inner
                     .map(|line| {
-                        assert_eq!(line.as_rule(), Rule::quote_line);
+                        assert_eq!(line.rule(), Rule::quote_line);
                         self.act_on_pair(state, line)
                     })
                     .collect::<Vec<String>>();
                 let quote_text = lines.join("\n");
+                self.transformer.element_context(&ctx);
                 if state.peek {
                     self.transformer.peek_quote(quote_text);
                 } else {
                     text += self.transformer.transform_quote(quote_text).as_str();
                 }
+                self.wrap(state, &mut text, |t| t.exit_quote());
+            }
+
+            Rule::alert => {
+                // NOTE    Safe to unwrap as the grammar always starts an alert with its kind
+                let kind_label = inner.next().unwrap().as_str();
+                let kind =
+                    AlertKind::from_label(kind_label).expect("Alert kind defined in grammar");
+                let body = inner
+                    .map(|line| self.act_on_pair(state, line))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_alert(kind, body);
+                } else {
+                    text += self.transformer.transform_alert(kind, body).as_str();
+                }
+            }
+
+            #[cfg(feature = "details")]
+            Rule::details => {
+                // NOTE    Safe to unwrap as the grammar always starts a section with its summary
+                let summary = self.act_on_pair(state, inner.next().unwrap());
+                let mut body = String::new();
+                for block in inner.flat_map(|body| body.children()) {
+                    body += self.act_on_pair(state, block).as_str();
+                }
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_details(summary, body);
+                } else {
+                    text += self.transformer.transform_details(summary, body).as_str();
+                }
+            }
+
+            #[cfg(feature = "tabs")]
+            Rule::tab_group => {
+                let mut tabs = vec![];
+                for tab in inner {
+                    let mut parts = tab.children().iter();
+                    // NOTE    Safe to unwrap as the grammar always starts a tab with its title
+                    let title = parts.next().unwrap().as_str().to_string();
+                    let title = self.act_on_raw_text(state, title);
+                    let mut body = String::new();
+                    for block in parts.flat_map(|body| body.children()) {
+                        body += self.act_on_pair(state, block).as_str();
+                    }
+                    tabs.push((title, body));
+                }
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_tab_group(tabs);
+                } else {
+                    text += self.transformer.transform_tab_group(tabs).as_str();
+                }
             }
 
             Rule::quote_line => {
@@ -443,30 +1563,107 @@ where
 
             Rule::codeblock => {
                 let mut got_lang = false;
-                if let Some(t) = inner.peek() {
-                    if t.as_rule() == Rule::slug {
+                if let Some(t) = inner.as_slice().first() {
+                    if t.rule() == Rule::slug {
                         got_lang = true;
                     }
                 }
                 let lang = if got_lang {
                     // NOTE Safe to unwrap as we just did a peek before
-                    Some(inner.next().unwrap().as_str().to_string())
+                    let language = inner.next().unwrap().as_str().to_string();
+                    Some(self.options.normalize_language(language))
                 } else {
                     None
                 };
-                if state.peek {
-                    self.transformer
-                        .peek_codeblock(lang, self.get_whole_block(&mut inner, "\n"));
+                let mut flags = vec![];
+                while let Some(flag) = inner
+                    .as_slice()
+                    .first()
+                    .filter(|el| el.rule() == Rule::codeblock_flag)
+                {
+                    flags.push(flag.as_str().to_string());
+                    inner.next();
+                }
+                let diagram = lang.as_deref().and_then(DiagramKind::from_language);
+                let separator = match lang.as_deref() {
+                    Some("csvtable") => Some(','),
+                    Some("tsvtable") => Some('\t'),
+                    _ => None,
+                };
+                let is_code = diagram.is_none() && separator.is_none();
+                if let Some(language) = lang.as_ref().filter(|_| is_code) {
+                    if self.options.is_unknown_language(language) {
+                        let warning = Warning::UnknownLanguage {
+                            language: language.clone(),
+                            span: ctx.span(),
+                        };
+                        self.warn(state, warning);
+                    }
+                }
+                let code = self.get_whole_block(&mut inner, "\n");
+                let (code, flags) = self.include_code(state, &ctx, code, flags);
+                if let Some(separator) = separator {
+                    let mut records = parse_delimited(&code, separator).into_iter();
+                    let header = records.next().unwrap_or_default();
+                    let alignments = vec![Alignment::None; header.len()];
+                    let rows = records
+                        .map(|mut row| {
+                            row.resize(header.len(), String::new());
+                            row
+                        })
+                        .collect();
+                    self.transformer.element_context(&ctx);
+                    if state.peek {
+                        self.transformer.peek_table(header, alignments, rows);
+                    } else {
+                        text += self
+                            .transformer
+                            .transform_table(header, alignments, rows)
+                            .as_str();
+                    }
+                    return text;
+                }
+                let lines = CodeblockLines::from_flags(&flags);
+                self.transformer.element_context(&ctx);
+                if let Some(kind) = diagram {
+                    if state.peek {
+                        self.transformer.peek_diagram(kind, code);
+                    } else {
+                        text += self.transformer.transform_diagram(kind, code).as_str();
+                    }
+                } else if self.options.code_themes || !lines.is_empty() {
+                    let themes = match self.options.code_themes {
+                        true => CodeTheme::of_block(&flags),
+                        false => vec![],
+                    };
+                    let info = CodeblockInfo {
+                        flags,
+                        lines,
+                        themes,
+                    };
+                    if state.peek {
+                        self.transformer.peek_codeblock_with_info(lang, code, info);
+                    } else {
+                        text += self
+                            .transformer
+                            .transform_codeblock_with_info(lang, code, info)
+                            .as_str();
+                    }
+                } else if state.peek {
+                    self.transformer.peek_codeblock(lang, flags, code);
                 } else {
                     text += self
                         .transformer
-                        .transform_codeblock(lang, self.get_whole_block(&mut inner, "\n"))
+                        .transform_codeblock(lang, flags, code)
                         .as_str();
                 }
             }
 
+            Rule::comment if self.options.strip_comments && !state.peek => {}
+
             Rule::comment => {
                 let t = self.get_rich_text(state, inner.next().unwrap());
+                self.transformer.element_context(&ctx);
                 if state.peek {
                     self.transformer.peek_comment(t);
                 } else {
@@ -474,6 +1671,80 @@ where
                 }
             }
 
+            Rule::crossref => {
+                let slug = next_inner_string(&mut inner).unwrap();
+                let target = match self.options.crossrefs {
+                    true => self.crossrefs.resolve(&slug),
+                    false => None,
+                };
+                let Some((kind, resolved)) = target else {
+                    if self.options.crossrefs {
+                        let warning = Warning::UnresolvedReference {
+                            label: slug,
+                            span: ctx.span(),
+                        };
+                        self.warn(state, warning);
+                    }
+                    text += self.act_on_raw_text(state, pair_text.to_string()).as_str();
+                    return text;
+                };
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_crossref(kind, slug, resolved);
+                } else {
+                    text += self
+                        .transformer
+                        .transform_crossref(kind, slug, resolved)
+                        .as_str();
+                }
+            }
+
+            Rule::shortcode => {
+                let name = next_inner_string(&mut inner).unwrap();
+                let options = self.options;
+                if let Some(ref variables) = options.variables {
+                    if inner.len() == 0 && !options.shortcodes.contains(&name) {
+                        let value = match variables.get(&name) {
+                            Some(value) => value.clone(),
+                            None => {
+                                let span = ctx.span();
+                                self.warn(state, Warning::UnknownVariable { name, span });
+                                match options.unknown_variables {
+                                    UnknownVariables::Keep => pair_text.to_string(),
+                                    UnknownVariables::Remove => return text,
+                                }
+                            }
+                        };
+                        text += self.act_on_raw_text(state, value).as_str();
+                        return text;
+                    }
+                }
+                if !self.options.shortcodes.contains(&name) {
+                    text += self.act_on_raw_text(state, pair_text.to_string()).as_str();
+                    return text;
+                }
+                let args = inner
+                    .map(|arg| arg.as_str().trim_matches('"').to_string())
+                    .collect();
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_shortcode(name, args);
+                } else {
+                    text += self.transformer.transform_shortcode(name, args).as_str();
+                }
+            }
+
+            Rule::html_block => {
+                let tag = next_inner_string(&mut inner).unwrap();
+                let html = pair_text.trim_end().to_string();
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_html_block(tag, html);
+                } else {
+                    text += self.transformer.transform_html_block(tag, html).as_str();
+                }
+            }
+
             Rule::inline_code => {
                 assert_eq!(
                     inner.len(),
@@ -481,6 +1752,7 @@ where
                     "Grammar error on inline_code, expected only 1 inner"
                 );
                 let code_text = next_inner_string(&mut inner).unwrap();
+                self.transformer.element_context(&ctx);
                 if state.peek {
                     self.transformer.peek_inline_code(code_text)
                 } else {
@@ -488,58 +1760,248 @@ where
                 }
             }
 
-            Rule::horiz_sep if state.peek => self.transformer.peek_horizontal_separator(),
             Rule::horiz_sep => {
-                text += self.transformer.transform_horizontal_separator().as_str();
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_horizontal_separator();
+                } else {
+                    text += self.transformer.transform_horizontal_separator().as_str();
+                }
             }
 
             Rule::image => {
-                assert!(
-                    inner.len() >= 2,
-                    "Grammar error on image, expected at least 2 inners"
-                );
-                let img_alt = next_inner_string(&mut inner).unwrap();
-                let url = next_inner_string(&mut inner).unwrap();
-                let mut added_tags = HashMap::new();
+                let mut img_alt = String::new();
+                let mut url = None;
+                for el in inner.by_ref() {
+                    if el.rule() == Rule::url {
+                        url = Some(el.as_str().to_string());
+                        break;
+                    }
+                    img_alt += el.as_str();
+                }
+                let url = url.expect("Grammar error on image, expected an url");
+                if img_alt.trim().is_empty() {
+                    let warning = Warning::MissingAltText {
+                        url: url.clone(),
+                        span: ctx.span(),
+                    };
+                    self.warn(state, warning);
+                }
+                let mut added_tags = ImageTags::default();
                 if let Some(img_tags) = inner.next() {
-                    let mut img_tags = img_tags.into_inner();
+                    let mut img_tags = img_tags.children().iter();
                     added_tags = self.get_metadata(state, &mut img_tags);
                 }
+                let caption = added_tags.remove("caption");
+                let candidates = self.image_candidates(&url);
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_image(img_alt, url, added_tags);
+                    if candidates.is_empty() {
+                        self.transformer.peek_image(img_alt, url, added_tags);
+                    } else {
+                        self.transformer
+                            .peek_responsive_image(img_alt, url, candidates, added_tags);
+                    }
+                    if let Some(caption) = caption {
+                        self.transformer.peek_figure(caption);
+                    }
                 } else {
-                    text += self
-                        .transformer
-                        .transform_image(img_alt, url, added_tags)
-                        .as_str();
+                    let image = if candidates.is_empty() {
+                        self.transformer.transform_image(img_alt, url, added_tags)
+                    } else {
+                        self.transformer
+                            .transform_responsive_image(img_alt, url, candidates, added_tags)
+                    };
+                    match caption {
+                        Some(caption) => {
+                            text += self.transformer.transform_figure(image, caption).as_str()
+                        }
+                        None => text += image.as_str(),
+                    }
                 }
             }
 
             Rule::list => {
-                let elements: Vec<String> = inner.map(|el| self.act_on_pair(state, el)).collect();
+                self.wrap(state, &mut text, |t| t.enter_list());
+                let elements = self.get_list_elements(state, inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
                     self.transformer.peek_list(elements);
                 } else {
                     text += self.transformer.transform_list(elements).as_str();
                 }
+                self.wrap(state, &mut text, |t| t.exit_list());
+            }
+
+            Rule::ordered_list => {
+                // NOTE    The grammar always starts an ordered list element with its number
+                let start = inner
+                    .as_slice()
+                    .first()
+                    .and_then(|el| el.children().first())
+                    .and_then(|nb| nb.as_str().parse().ok())
+                    .unwrap_or(1);
+                self.wrap(state, &mut text, |t| t.enter_ordered_list(start));
+                let elements = self.get_list_elements(state, inner);
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_ordered_list(start, elements);
+                } else {
+                    text += self
+                        .transformer
+                        .transform_ordered_list(start, elements)
+                        .as_str();
+                }
+                self.wrap(state, &mut text, |t| t.exit_ordered_list(start));
+            }
+
+            Rule::ordered_list_element => {
+                assert_eq!(
+                    inner.next().map(|nb| nb.rule()),
+                    Some(Rule::list_number),
+                    "Grammar error on ordered_list_element, expected list_number first"
+                );
+                let info = ListItemInfo::new(state.list_index, state.list_depth, pair_text, true)
+                    .with_checkbox(pair);
+                skip_checkbox(&mut inner);
+                // NOTE    Lists nested in the element are one level deeper than its own
+                state.list_depth += 1;
+                self.wrap(state, &mut text, |t| t.enter_list_element(info.clone()));
+                let element_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer
+                        .peek_list_element(element_text, info.clone());
+                } else {
+                    text += self
+                        .transformer
+                        .transform_list_element(element_text, info.clone())
+                        .as_str();
+                }
+                self.wrap(state, &mut text, |t| t.exit_list_element(info));
             }
 
             Rule::list_element => {
+                let info = ListItemInfo::new(state.list_index, state.list_depth, pair_text, false)
+                    .with_checkbox(pair);
+                skip_checkbox(&mut inner);
+                // NOTE    Lists nested in the element are one level deeper than its own
+                state.list_depth += 1;
+                self.wrap(state, &mut text, |t| t.enter_list_element(info.clone()));
                 let element_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
-                    self.transformer.peek_list_element(element_text);
+                    self.transformer
+                        .peek_list_element(element_text, info.clone());
                 } else {
                     text += self
                         .transformer
-                        .transform_list_element(element_text)
+                        .transform_list_element(element_text, info.clone())
                         .as_str();
                 }
+                self.wrap(state, &mut text, |t| t.exit_list_element(info));
+            }
+
+            Rule::table => {
+                self.wrap(state, &mut text, |t| t.enter_table());
+                let header_row = inner.next().unwrap();
+                // NOTE    Safe to unwrap as the grammar always has a delimiter row after the header
+                let alignments = inner
+                    .next()
+                    .unwrap()
+                    .children()
+                    .iter()
+                    .map(|delim| Alignment::from_delimiter(delim.as_str()))
+                    .collect::<Vec<Alignment>>();
+                #[cfg(feature = "table-spans")]
+                {
+                    let header = self.get_spanned_rows(state, core::slice::from_ref(header_row));
+                    let header = header.into_iter().next().unwrap_or_default();
+                    let width = header.iter().map(|cell| cell.colspan).sum();
+                    let rows = self
+                        .get_spanned_rows(state, inner.as_slice())
+                        .into_iter()
+                        .map(|row| fit_cells(row, width))
+                        .collect::<Vec<Vec<TableCell>>>();
+                    self.transformer.element_context(&ctx);
+                    if is_simple_grid(&header, &rows) {
+                        let header = expand_cells(header);
+                        let rows = rows.into_iter().map(expand_cells).collect();
+                        if state.peek {
+                            self.transformer.peek_table(header, alignments, rows);
+                        } else {
+                            text += self
+                                .transformer
+                                .transform_table(header, alignments, rows)
+                                .as_str();
+                        }
+                    } else if state.peek {
+                        self.transformer
+                            .peek_table_with_spans(header, alignments, rows);
+                    } else {
+                        text += self
+                            .transformer
+                            .transform_table_with_spans(header, alignments, rows)
+                            .as_str();
+                    }
+                }
+                #[cfg(not(feature = "table-spans"))]
+                {
+                    let header = self.get_table_row(state, header_row);
+                    let rows = inner
+                        .map(|row| {
+                            let mut row = self.get_table_row(state, row);
+                            row.resize(header.len(), String::new());
+                            row
+                        })
+                        .collect();
+                    self.transformer.element_context(&ctx);
+                    if state.peek {
+                        self.transformer.peek_table(header, alignments, rows);
+                    } else {
+                        text += self
+                            .transformer
+                            .transform_table(header, alignments, rows)
+                            .as_str();
+                    }
+                }
+                self.wrap(state, &mut text, |t| t.exit_table());
             }
 
             Rule::paragraph_newline => state.add_space = true,
 
+            Rule::paragraph if self.options.text_direction || has_lang(pair) => {
+                let mut info = ParagraphInfo::new(None);
+                let mut source = pair_text;
+                if has_lang(pair) {
+                    info.lang = next_inner_string(&mut inner);
+                    // NOTE    The attribute is the first thing of the paragraph
+                    source = source.split_once('}').map_or(source, |(_, text)| text);
+                }
+                if self.options.text_direction {
+                    info = info.detect_direction(source);
+                }
+                self.wrap(state, &mut text, |t| {
+                    t.enter_paragraph_with_info(info.clone())
+                });
+                let paragraph_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer
+                        .peek_paragraph_with_info(paragraph_text, info.clone());
+                } else {
+                    text += self
+                        .transformer
+                        .transform_paragraph_with_info(paragraph_text, info.clone())
+                        .as_str();
+                }
+                self.wrap(state, &mut text, |t| t.exit_paragraph_with_info(info));
+            }
+
             Rule::paragraph => {
+                self.wrap(state, &mut text, |t| t.enter_paragraph());
                 let paragraph_text = self.get_inner_elements(state, inner.len(), &mut inner);
+                self.transformer.element_context(&ctx);
                 if state.peek {
                     self.transformer.peek_paragraph(paragraph_text);
                 } else {
@@ -548,9 +2010,23 @@ where
                         .transform_paragraph(paragraph_text)
                         .as_str();
                 }
+                self.wrap(state, &mut text, |t| t.exit_paragraph());
+            }
+
+            Rule::invalid => {
+                self.transformer.element_context(&ctx);
+                if state.peek {
+                    self.transformer.peek_invalid(pair_text.to_string());
+                } else {
+                    text += self
+                        .transformer
+                        .transform_invalid(pair_text.to_string())
+                        .as_str();
+                }
             }
 
             Rule::vertical_space => {
+                self.transformer.element_context(&ctx);
                 if state.peek {
                     self.transformer.peek_vertical_space()
                 } else {
@@ -558,10 +2034,18 @@ where
                 }
             }
 
-            Rule::file | Rule::rich_txt | Rule::quote_txt | Rule::NO_INLINE_TEXT => {
-                if inner.len() == 0 {
+            Rule::file
+            | Rule::rich_txt
+            | Rule::quote_txt
+            | Rule::NO_INLINE_TEXT
+            | Rule::list_element_blocks => {
+                if inner.as_slice().is_empty() {
                     return self.act_on_raw_text(state, pair_text.to_string());
                 }
+                // NOTE    Transformed output is usually about the size of the markdown source
+                if rule == Rule::file && !state.peek {
+                    text.reserve(pair_text.len());
+                }
                 for child in inner {
                     text += self.act_on_pair(state, child).as_str();
                 }
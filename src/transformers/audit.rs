@@ -0,0 +1,125 @@
+use crate::prelude::*;
+use crate::{
+    context::CurrentSpan, ElementContext, ImageTags, LinkKind, MarkdownTransformer, Span,
+    StatefulTransformer,
+};
+
+// Link texts that don't tell where the link leads, compared in lowercase
+const UNINFORMATIVE_LINK_TEXTS: [&str; 8] = [
+    "here",
+    "click here",
+    "this",
+    "link",
+    "this link",
+    "more",
+    "read more",
+    "learn more",
+];
+
+/// Accessibility issue found in a document by the `AccessibilityAudit`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditIssue {
+    /// Image without alternative text
+    MissingAltText { url: String, span: Span },
+    /// Link whose text doesn't describe its target, like "here" or the URL itself
+    UninformativeLinkText {
+        text: String,
+        url: String,
+        span: Span,
+    },
+    /// Heading more than one level deeper than the previous one
+    HeadingLevelJump { from: usize, to: usize, span: Span },
+}
+
+/// Transformer auditing the accessibility of a document while it is transformed,
+/// the issues found are available with `issues` and its output can be ignored
+#[derive(Clone, Default)]
+pub struct AccessibilityAudit {
+    span: CurrentSpan,
+    transforming: bool,
+    last_heading: Option<usize>,
+    issues: Vec<AuditIssue>,
+}
+
+impl AccessibilityAudit {
+    pub fn issues(&self) -> &[AuditIssue] {
+        &self.issues
+    }
+
+    fn is_uninformative(text: &str, url: &str) -> bool {
+        let text = text
+            .trim()
+            .trim_matches(|c: char| c.is_ascii_punctuation())
+            .to_lowercase();
+        text.is_empty()
+            || text == url.to_lowercase()
+            || UNINFORMATIVE_LINK_TEXTS.contains(&text.as_str())
+    }
+}
+
+impl MarkdownTransformer for AccessibilityAudit {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.span.set(ctx);
+    }
+
+    fn peek_header(&mut self, level: usize, _text: String) {
+        if let Some(from) = self.last_heading {
+            if level > from + 1 {
+                let span = self.span.get();
+                self.issues.push(AuditIssue::HeadingLevelJump {
+                    from,
+                    to: level,
+                    span,
+                });
+            }
+        }
+        self.last_heading = Some(level);
+    }
+
+    // NOTE    Link texts are only rendered during the transform pass, the texts of some
+    //         elements, like headings, being transformed during the peek pass too. Images
+    //         are checked in the same pass to report the issues in order.
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        if self.transforming && Self::is_uninformative(&text, &url) {
+            let span = self.span.get();
+            self.issues.push(AuditIssue::UninformativeLinkText {
+                text: text.trim().to_string(),
+                url,
+                span,
+            });
+        }
+        text
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, _add_tags: ImageTags) -> String {
+        if self.transforming && alt.trim().is_empty() {
+            let span = self.span.get();
+            self.issues.push(AuditIssue::MissingAltText { url, span });
+        }
+        alt
+    }
+
+    fn finished(&mut self, peek: bool) -> String {
+        if peek {
+            self.last_heading = None;
+        }
+        self.transforming = peek;
+        String::new()
+    }
+}
+
+impl StatefulTransformer for AccessibilityAudit {
+    type Snapshot = AccessibilityAudit;
+
+    fn reset(&mut self) {
+        *self = AccessibilityAudit::default();
+    }
+
+    fn snapshot(&self) -> AccessibilityAudit {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: AccessibilityAudit) {
+        *self = snapshot;
+    }
+}
@@ -0,0 +1,499 @@
+use super::Blocks;
+use crate::prelude::*;
+use crate::{
+    format_table, AlertKind, Alignment, ElementContext, Footnote, ImageTags, LinkKind, Localizer,
+    MarkdownTransformer, Phrase, StatefulTransformer,
+};
+
+// Characters that have to be escaped anywhere in the text of a Telegram message
+const TELEGRAM_SPECIAL: &str = "_*[]()~`>#+-=|{}.!\\";
+// Characters that have to be escaped in the text of a Discord message
+const DISCORD_SPECIAL: &str = "\\*_~`|[]";
+
+fn escape_chars(text: &str, special: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Elements are indented under their marker, so nested blocks stay in the element
+fn render_list<I: Iterator<Item = String>>(markers: I, elements: Vec<String>) -> String {
+    markers
+        .zip(elements)
+        .map(|(marker, element)| {
+            let indent = " ".repeat(marker.chars().count());
+            let lines = element
+                .lines()
+                .map(|line| match line {
+                    "" => String::new(),
+                    line => format!("{indent}{line}"),
+                })
+                .collect::<Vec<String>>();
+            format!("{marker}{}", lines.join("\n").trim_start())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Transformer rendering messages in the MarkdownV2 dialect of the Telegram bot API
+///
+/// Every special character of the text is escaped, headings are rendered in bold and
+/// tables as preformatted blocks, as Telegram supports neither.
+#[derive(Clone, Default)]
+pub struct TelegramRenderer {
+    blocks: Blocks,
+    localizer: Localizer,
+    refs: HashMap<String, String>,
+    // Text of the cells, rendered as a preformatted block and not escaped
+    in_table: bool,
+}
+
+impl TelegramRenderer {
+    /// Translation of the titles of the alerts
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = localizer;
+        self
+    }
+
+    fn escape(text: &str) -> String {
+        escape_chars(text, TELEGRAM_SPECIAL)
+    }
+
+    // NOTE    Only backquotes and backslashes are escaped in code entities
+    fn escape_code(text: &str) -> String {
+        escape_chars(text, "`\\")
+    }
+
+    fn escape_url(url: &str) -> String {
+        escape_chars(url, ")\\")
+    }
+}
+
+impl MarkdownTransformer for TelegramRenderer {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.blocks.context(ctx);
+    }
+
+    fn localize(&mut self, phrase: Phrase) -> String {
+        Self::escape(&self.localizer.text(phrase))
+    }
+
+    fn transform_text(&mut self, text: String) -> String {
+        if self.in_table {
+            return text;
+        }
+        TelegramRenderer::escape(&text)
+    }
+
+    fn enter_table(&mut self) -> String {
+        self.in_table = true;
+        String::new()
+    }
+
+    fn exit_table(&mut self) -> String {
+        self.in_table = false;
+        String::new()
+    }
+
+    fn transform_header(&mut self, _level: usize, text: String) -> String {
+        self.blocks.push(format!("*{text}*"))
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("*{text}*")
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        format!("_{text}_")
+    }
+
+    fn transform_strikethrough(&mut self, text: String) -> String {
+        format!("~{text}~")
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.refs.insert(slug, url);
+    }
+
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        match self.refs.get(&slug).cloned() {
+            Some(url) => self.transform_link(text, url, LinkKind::External),
+            None => text,
+        }
+    }
+
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        format!("[{text}]({})", TelegramRenderer::escape_url(&url))
+    }
+
+    fn transform_autolink(&mut self, text: String, _url: String, _kind: LinkKind) -> String {
+        TelegramRenderer::escape(&text)
+    }
+
+    fn transform_footnote_ref(&mut self, _label: String, number: usize) -> String {
+        format!("\\[{number}\\]")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let entries = entries
+            .into_iter()
+            .map(|f| format!("\\[{}\\] {}", f.number, f.text))
+            .collect::<Vec<String>>();
+        self.blocks.push(entries.join("\n"))
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, _add_tags: ImageTags) -> String {
+        let alt = if alt.is_empty() { url.clone() } else { alt };
+        format!(
+            "[{}]({})",
+            TelegramRenderer::escape(&alt),
+            TelegramRenderer::escape_url(&url)
+        )
+    }
+
+    fn transform_comment(&mut self, _text: String) -> String {
+        String::new()
+    }
+
+    fn transform_html_block(&mut self, _tag: String, _html: String) -> String {
+        String::new()
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        let lines = text
+            .lines()
+            .map(|line| format!(">{line}"))
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let label = self.localize(Phrase::Alert(kind));
+        let lines = core::iter::once(format!(">*{label}*"))
+            .chain(body.lines().map(|line| format!(">{line}")))
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_invalid(&mut self, raw: String) -> String {
+        self.blocks.push(TelegramRenderer::escape(&raw))
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        _flags: Vec<String>,
+        text: String,
+    ) -> String {
+        let language = language.unwrap_or_default();
+        let code = TelegramRenderer::escape_code(&text);
+        self.blocks.push(format!("```{language}\n{code}\n```"))
+    }
+
+    fn transform_inline_code(&mut self, text: String) -> String {
+        format!("`{}`", TelegramRenderer::escape_code(&text))
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        self.blocks.push("\u{2014}\u{2014}\u{2014}".to_string())
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        let markers = core::iter::repeat("\u{2022} ".to_string());
+        self.blocks.push(render_list(markers, elements))
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        let markers = (start..).map(|n| format!("{n}\\. "));
+        self.blocks.push(render_list(markers, elements))
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let mut all_rows = vec![header];
+        all_rows.extend(rows);
+        let table = TelegramRenderer::escape_code(&format_table(&all_rows, &alignments));
+        self.blocks.push(format!("```\n{table}\n```"))
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        "\n".to_string()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        self.blocks.push(text)
+    }
+
+    fn finished(&mut self, _peek: bool) -> String {
+        self.blocks.reset();
+        String::new()
+    }
+}
+
+impl StatefulTransformer for TelegramRenderer {
+    type Snapshot = TelegramRenderer;
+
+    fn reset(&mut self) {
+        self.blocks.reset();
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> TelegramRenderer {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: TelegramRenderer) {
+        *self = snapshot;
+    }
+}
+
+/// Transformer rendering messages in the markdown dialect of Discord
+///
+/// Headings deeper than 3 levels are rendered in bold and tables as code blocks,
+/// as Discord doesn't support them.
+#[derive(Clone, Default)]
+pub struct DiscordRenderer {
+    blocks: Blocks,
+    localizer: Localizer,
+    refs: HashMap<String, String>,
+    // Text of the cells, rendered as a preformatted block and not escaped
+    in_table: bool,
+}
+
+impl DiscordRenderer {
+    /// Translation of the titles of the alerts
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = localizer;
+        self
+    }
+
+    fn escape(text: &str) -> String {
+        escape_chars(text, DISCORD_SPECIAL)
+    }
+
+    // Text starting a line must not be taken for a heading, a subtext (`-# `),
+    // a quote or a list
+    fn escape_line_start(text: String) -> String {
+        let rest = if text.starts_with('#') {
+            text.trim_start_matches('#')
+        } else if let Some(subtext) = text.strip_prefix("-#") {
+            subtext.trim_start_matches('#')
+        } else if text.starts_with(|c: char| c.is_ascii_digit()) {
+            let rest = text.trim_start_matches(|c: char| c.is_ascii_digit());
+            rest.strip_prefix(['.', ')']).unwrap_or(&text)
+        } else {
+            text.strip_prefix(['>', '-', '+']).unwrap_or(&text)
+        };
+        let is_marker = rest.len() < text.len() && rest.starts_with(' ');
+        if is_marker {
+            format!("\\{text}")
+        } else {
+            text
+        }
+    }
+}
+
+impl MarkdownTransformer for DiscordRenderer {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.blocks.context(ctx);
+    }
+
+    fn localize(&mut self, phrase: Phrase) -> String {
+        Self::escape(&self.localizer.text(phrase))
+    }
+
+    fn transform_text(&mut self, text: String) -> String {
+        if self.in_table {
+            return text;
+        }
+        DiscordRenderer::escape(&text)
+    }
+
+    fn enter_table(&mut self) -> String {
+        self.in_table = true;
+        String::new()
+    }
+
+    fn exit_table(&mut self) -> String {
+        self.in_table = false;
+        String::new()
+    }
+
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        if level <= 3 {
+            self.blocks.push(format!("{} {text}", "#".repeat(level)))
+        } else {
+            self.blocks.push(format!("**{text}**"))
+        }
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("**{text}**")
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        format!("*{text}*")
+    }
+
+    fn transform_strikethrough(&mut self, text: String) -> String {
+        format!("~~{text}~~")
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.refs.insert(slug, url);
+    }
+
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        match self.refs.get(&slug).cloned() {
+            Some(url) => self.transform_link(text, url, LinkKind::External),
+            None => text,
+        }
+    }
+
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        format!("[{text}]({url})")
+    }
+
+    // NOTE    Discord turns bare URLs into links itself, escaping them would break them
+    fn transform_autolink(&mut self, text: String, _url: String, _kind: LinkKind) -> String {
+        text
+    }
+
+    fn transform_footnote_ref(&mut self, _label: String, number: usize) -> String {
+        format!("\\[{number}\\]")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let entries = entries
+            .into_iter()
+            .map(|f| format!("\\[{}\\] {}", f.number, f.text))
+            .collect::<Vec<String>>();
+        self.blocks.push(entries.join("\n"))
+    }
+
+    // NOTE    Discord shows a preview of image URLs
+    fn transform_image(&mut self, _alt: String, url: String, _add_tags: ImageTags) -> String {
+        url
+    }
+
+    fn transform_comment(&mut self, _text: String) -> String {
+        String::new()
+    }
+
+    fn transform_html_block(&mut self, _tag: String, _html: String) -> String {
+        String::new()
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        let lines = text
+            .lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let label = self.localize(Phrase::Alert(kind));
+        let lines = core::iter::once(format!("> **{label}**"))
+            .chain(body.lines().map(|line| format!("> {line}")))
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_invalid(&mut self, raw: String) -> String {
+        self.blocks.push(DiscordRenderer::escape(&raw))
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        _flags: Vec<String>,
+        text: String,
+    ) -> String {
+        let language = language.unwrap_or_default();
+        self.blocks.push(format!("```{language}\n{text}\n```"))
+    }
+
+    fn transform_inline_code(&mut self, text: String) -> String {
+        if text.contains('`') {
+            format!("`` {text} ``")
+        } else {
+            format!("`{text}`")
+        }
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        self.blocks.push("\u{2500}".repeat(20))
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        let markers = core::iter::repeat("- ".to_string());
+        self.blocks.push(render_list(markers, elements))
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        let markers = (start..).map(|n| format!("{n}. "));
+        self.blocks.push(render_list(markers, elements))
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let mut all_rows = vec![header];
+        all_rows.extend(rows);
+        let table = format_table(&all_rows, &alignments);
+        self.blocks.push(format!("```\n{table}\n```"))
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        "\n".to_string()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        let lines = text
+            .split('\n')
+            .map(|line| DiscordRenderer::escape_line_start(line.to_string()))
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn finished(&mut self, _peek: bool) -> String {
+        self.blocks.reset();
+        String::new()
+    }
+}
+
+impl StatefulTransformer for DiscordRenderer {
+    type Snapshot = DiscordRenderer;
+
+    fn reset(&mut self) {
+        self.blocks.reset();
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> DiscordRenderer {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: DiscordRenderer) {
+        *self = snapshot;
+    }
+}
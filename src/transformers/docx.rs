@@ -0,0 +1,432 @@
+use std::io::{Cursor, Seek, Write};
+
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    Alignment, Document, Errcode, ImageTags, Inline, InlineTransformer, ListItemInfo,
+    TransformOptions,
+};
+
+const NS_MAIN: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const NS_RELS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const NS_DRAWING: &str = "http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing";
+const NS_PICTURE: &str = "http://schemas.openxmlformats.org/drawingml/2006/picture";
+const NS_GRAPHIC: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+// Images are linked, not embedded, so their size is unknown: 4 x 3 inches, in EMU, unless
+// given by their `width` and `height` tags in pixels
+const IMAGE_WIDTH: usize = 3657600;
+const IMAGE_HEIGHT: usize = 2743200;
+const EMU_PER_PIXEL: usize = 9525;
+
+// Relationships 1 and 2 are the styles and the numbering definitions
+const FIRST_RELATIONSHIP: usize = 3;
+const BULLET_NUM_ID: usize = 1;
+
+const CONTENT_TYPES: &str = concat!(
+    "<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">",
+    "<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>",
+    "<Default Extension=\"xml\" ContentType=\"application/xml\"/>",
+    "<Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>",
+    "<Override PartName=\"/word/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml\"/>",
+    "<Override PartName=\"/word/numbering.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml\"/>",
+    "</Types>",
+);
+
+const PACKAGE_RELS: &str = concat!(
+    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    "<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/>",
+    "</Relationships>",
+);
+
+#[derive(Clone, Copy, Default)]
+struct RunFormat {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    link: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RelationKind {
+    Hyperlink,
+    Image,
+}
+
+/// Transformer writing Word documents (.docx), with headings, lists, tables, code blocks,
+/// formatted text, links and images
+///
+/// Images are linked to their URL rather than embedded, Word loads them when opening
+/// the document.
+#[derive(Clone, Default)]
+pub struct DocxTransformer {
+    relationships: Vec<(RelationKind, String)>,
+    // Start and depth of each ordered list, their numbering being restarted
+    ordered_lists: Vec<(usize, usize)>,
+    // Numbering of the list being transformed at each depth
+    current_lists: Vec<usize>,
+    pictures: usize,
+}
+
+impl DocxTransformer {
+    /// Write the document as a .docx archive
+    pub fn write<W: Write + Seek>(
+        &mut self,
+        document: &Document,
+        writer: W,
+    ) -> Result<(), Errcode> {
+        self.write_with_options(document, &TransformOptions::default(), writer)
+    }
+
+    /// Same as `write`, with the options of the transformation
+    pub fn write_with_options<W: Write + Seek>(
+        &mut self,
+        document: &Document,
+        options: &TransformOptions,
+        writer: W,
+    ) -> Result<(), Errcode> {
+        *self = DocxTransformer::default();
+        let body = document.transform_inline_with_options(self, options);
+        let files = [
+            ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+            ("_rels/.rels", PACKAGE_RELS.to_string()),
+            ("word/document.xml", body),
+            ("word/_rels/document.xml.rels", self.document_rels()),
+            ("word/styles.xml", styles()),
+            ("word/numbering.xml", self.numbering()),
+        ];
+        let mut zip = ZipWriter::new(writer);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, content) in files {
+            zip.start_file(name, options).map_err(zip_error)?;
+            zip.write_all(XML_HEADER.as_bytes())?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.finish().map_err(zip_error)?;
+        Ok(())
+    }
+
+    fn relationship(&mut self, kind: RelationKind, target: &str) -> String {
+        self.relationships.push((kind, target.to_string()));
+        format!("rId{}", self.relationships.len() - 1 + FIRST_RELATIONSHIP)
+    }
+
+    fn document_rels(&self) -> String {
+        let mut rels = format!("<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+            <Relationship Id=\"rId1\" Type=\"{NS_RELS}/styles\" Target=\"styles.xml\"/>\
+            <Relationship Id=\"rId2\" Type=\"{NS_RELS}/numbering\" Target=\"numbering.xml\"/>");
+        for (n, (kind, target)) in self.relationships.iter().enumerate() {
+            let kind = match kind {
+                RelationKind::Hyperlink => "hyperlink",
+                RelationKind::Image => "image",
+            };
+            rels += &format!(
+                "<Relationship Id=\"rId{}\" Type=\"{NS_RELS}/{kind}\" Target=\"{}\" TargetMode=\"External\"/>",
+                n + FIRST_RELATIONSHIP,
+                escape(target)
+            );
+        }
+        rels + "</Relationships>"
+    }
+
+    fn numbering(&self) -> String {
+        let mut xml = format!("<w:numbering xmlns:w=\"{NS_MAIN}\">");
+        for (id, bullets) in [(0, true), (1, false)] {
+            xml += &format!("<w:abstractNum w:abstractNumId=\"{id}\">");
+            for level in 0..9 {
+                let (format, text) = if bullets {
+                    (
+                        "bullet",
+                        ["\u{2022}", "\u{25E6}", "\u{25AA}"][level % 3].to_string(),
+                    )
+                } else {
+                    ("decimal", format!("%{}.", level + 1))
+                };
+                xml += &format!(
+                    "<w:lvl w:ilvl=\"{level}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"{format}\"/>\
+                    <w:lvlText w:val=\"{text}\"/><w:lvlJc w:val=\"left\"/>\
+                    <w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+                    720 * (level + 1)
+                );
+            }
+            xml += "</w:abstractNum>";
+        }
+        xml +=
+            &format!("<w:num w:numId=\"{BULLET_NUM_ID}\"><w:abstractNumId w:val=\"0\"/></w:num>");
+        for (n, (start, depth)) in self.ordered_lists.iter().enumerate() {
+            xml += &format!(
+                "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"1\"/>\
+                <w:lvlOverride w:ilvl=\"{depth}\"><w:startOverride w:val=\"{start}\"/></w:lvlOverride></w:num>",
+                n + BULLET_NUM_ID + 1
+            );
+        }
+        xml + "</w:numbering>"
+    }
+
+    fn runs(&mut self, content: &[Inline], format: RunFormat) -> String {
+        let mut xml = String::new();
+        for run in content {
+            match run {
+                Inline::Text(text) => xml += &text_run(text, format, None),
+                Inline::Code(code) => xml += &text_run(code, format, Some("CodeChar")),
+                Inline::Bold(inner) | Inline::Italic(inner) | Inline::Strike(inner) => {
+                    let mut nested = format;
+                    match run {
+                        Inline::Bold(_) => nested.bold = true,
+                        Inline::Italic(_) => nested.italic = true,
+                        _ => nested.strike = true,
+                    }
+                    xml += &self.runs(inner, nested);
+                }
+                Inline::Link { text, url } => {
+                    let id = self.relationship(RelationKind::Hyperlink, url);
+                    let mut nested = format;
+                    nested.link = true;
+                    let runs = self.runs(text, nested);
+                    xml += &format!("<w:hyperlink r:id=\"{id}\">{runs}</w:hyperlink>");
+                }
+                Inline::Image { alt, url, tags } => xml += &self.picture(alt, url, tags),
+                Inline::FootnoteRef(label) => xml += &text_run(&format!("[{label}]"), format, None),
+                Inline::LineBreak => xml += "<w:r><w:br/></w:r>",
+            }
+        }
+        xml
+    }
+
+    fn picture(&mut self, alt: &str, url: &str, tags: &ImageTags) -> String {
+        let size = |key: &str, default: usize| {
+            tags.get(key)
+                .and_then(|value| value.trim_end_matches("px").parse::<usize>().ok())
+                .map_or(default, |pixels| pixels * EMU_PER_PIXEL)
+        };
+        let (width, height) = (size("width", IMAGE_WIDTH), size("height", IMAGE_HEIGHT));
+        let id = self.relationship(RelationKind::Image, url);
+        self.pictures += 1;
+        let n = self.pictures;
+        let alt = escape(alt);
+        format!(
+            "<w:r><w:drawing><wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">\
+            <wp:extent cx=\"{width}\" cy=\"{height}\"/>\
+            <wp:docPr id=\"{n}\" name=\"Picture {n}\" descr=\"{alt}\"/>\
+            <a:graphic xmlns:a=\"{NS_GRAPHIC}\"><a:graphicData uri=\"{NS_PICTURE}\">\
+            <pic:pic xmlns:pic=\"{NS_PICTURE}\">\
+            <pic:nvPicPr><pic:cNvPr id=\"{n}\" name=\"Picture {n}\" descr=\"{alt}\"/><pic:cNvPicPr/></pic:nvPicPr>\
+            <pic:blipFill><a:blip r:link=\"{id}\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>\
+            <pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{width}\" cy=\"{height}\"/></a:xfrm>\
+            <a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr>\
+            </pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"
+        )
+    }
+
+    fn paragraph(&mut self, style: Option<&str>, content: &[Inline]) -> String {
+        let runs = self.runs(content, RunFormat::default());
+        paragraph(style.map(paragraph_style).unwrap_or_default(), runs)
+    }
+}
+
+impl InlineTransformer for DocxTransformer {
+    fn transform_header(&mut self, level: usize, content: Vec<Inline>) -> String {
+        self.paragraph(Some(&format!("Heading{level}")), &content)
+    }
+
+    fn transform_paragraph(&mut self, content: Vec<Inline>) -> String {
+        self.paragraph(None, &content)
+    }
+
+    fn transform_quote(&mut self, content: Vec<Inline>) -> String {
+        self.paragraph(Some("Quote"), &content)
+    }
+
+    fn transform_list_element(
+        &mut self,
+        content: Vec<Inline>,
+        blocks: Vec<String>,
+        info: ListItemInfo,
+    ) -> String {
+        if self.current_lists.len() <= info.depth {
+            self.current_lists.resize(info.depth + 1, BULLET_NUM_ID);
+        }
+        // NOTE    Elements of nested lists are transformed before the element containing them,
+        //         so each depth keeps track of its own list
+        if !info.ordered {
+            self.current_lists[info.depth] = BULLET_NUM_ID;
+        } else if info.index == 0 {
+            let start = info
+                .marker
+                .trim_end_matches(['.', ')'])
+                .parse()
+                .unwrap_or(1);
+            self.ordered_lists.push((start, info.depth));
+            self.current_lists[info.depth] = self.ordered_lists.len() + BULLET_NUM_ID;
+        }
+        let properties = format!(
+            "{}<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>",
+            paragraph_style("ListParagraph"),
+            info.depth,
+            self.current_lists[info.depth]
+        );
+        let runs = self.runs(&content, RunFormat::default());
+        paragraph(properties, runs) + &blocks.concat()
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        elements.concat()
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        _lang: Option<String>,
+        _flags: Vec<String>,
+        code: String,
+    ) -> String {
+        let runs = code
+            .split('\n')
+            .map(|line| text_run(line, RunFormat::default(), None))
+            .collect::<Vec<String>>()
+            .join("<w:r><w:br/></w:r>");
+        paragraph(paragraph_style("CodeBlock"), runs)
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<Vec<Inline>>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    ) -> String {
+        let mut xml = "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr><w:tblGrid>".to_string();
+        xml += &"<w:gridCol/>".repeat(header.len());
+        xml += "</w:tblGrid>";
+        let header = (header, true);
+        for (row, bold) in core::iter::once(header).chain(rows.into_iter().map(|r| (r, false))) {
+            xml += "<w:tr>";
+            for (n, cell) in row.iter().enumerate() {
+                let align = match alignments.get(n).unwrap_or(&Alignment::None) {
+                    Alignment::Center => "<w:jc w:val=\"center\"/>",
+                    Alignment::Right => "<w:jc w:val=\"right\"/>",
+                    Alignment::None | Alignment::Left => "",
+                };
+                let format = RunFormat {
+                    bold,
+                    ..RunFormat::default()
+                };
+                xml += &format!(
+                    "<w:tc>{}</w:tc>",
+                    paragraph(align.to_string(), self.runs(cell, format))
+                );
+            }
+            xml += "</w:tr>";
+        }
+        xml + "</w:tbl>"
+    }
+
+    fn transform_horiz_sep(&mut self) -> String {
+        let border = "<w:pBdr><w:bottom w:val=\"single\" w:sz=\"6\" w:space=\"1\" w:color=\"auto\"/></w:pBdr>";
+        paragraph(border.to_string(), String::new())
+    }
+
+    fn transform_document(&mut self, blocks: Vec<String>) -> String {
+        format!(
+            "<w:document xmlns:w=\"{NS_MAIN}\" xmlns:r=\"{NS_RELS}\" xmlns:wp=\"{NS_DRAWING}\"><w:body>{}</w:body></w:document>",
+            blocks.concat()
+        )
+    }
+}
+
+fn zip_error(err: zip::result::ZipError) -> Errcode {
+    Errcode::IoError(err.into())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn paragraph_style(style: &str) -> String {
+    format!("<w:pStyle w:val=\"{style}\"/>")
+}
+
+fn paragraph(properties: String, runs: String) -> String {
+    if properties.is_empty() {
+        format!("<w:p>{runs}</w:p>")
+    } else {
+        format!("<w:p><w:pPr>{properties}</w:pPr>{runs}</w:p>")
+    }
+}
+
+fn text_run(text: &str, format: RunFormat, style: Option<&str>) -> String {
+    let style = style.or(format.link.then_some("Hyperlink"));
+    let mut properties = style
+        .map(|s| format!("<w:rStyle w:val=\"{s}\"/>"))
+        .unwrap_or_default();
+    if format.bold {
+        properties += "<w:b/>";
+    }
+    if format.italic {
+        properties += "<w:i/>";
+    }
+    if format.strike {
+        properties += "<w:strike/>";
+    }
+    let text = format!("<w:t xml:space=\"preserve\">{}</w:t>", escape(text));
+    if properties.is_empty() {
+        format!("<w:r>{text}</w:r>")
+    } else {
+        format!("<w:r><w:rPr>{properties}</w:rPr>{text}</w:r>")
+    }
+}
+
+fn styles() -> String {
+    let mut xml = format!(
+        "<w:styles xmlns:w=\"{NS_MAIN}\">\
+        <w:style w:type=\"paragraph\" w:default=\"1\" w:styleId=\"Normal\"><w:name w:val=\"Normal\"/>\
+        <w:pPr><w:spacing w:after=\"120\"/></w:pPr></w:style>"
+    );
+    for (level, size) in [32, 28, 26, 24, 22, 22].into_iter().enumerate() {
+        xml += &format!(
+            "<w:style w:type=\"paragraph\" w:styleId=\"Heading{n}\"><w:name w:val=\"heading {n}\"/>\
+            <w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/>\
+            <w:pPr><w:keepNext/><w:spacing w:before=\"240\" w:after=\"120\"/><w:outlineLvl w:val=\"{level}\"/></w:pPr>\
+            <w:rPr><w:b/><w:sz w:val=\"{size}\"/></w:rPr></w:style>",
+            n = level + 1
+        );
+    }
+    xml += "<w:style w:type=\"paragraph\" w:styleId=\"Quote\"><w:name w:val=\"Quote\"/>\
+        <w:basedOn w:val=\"Normal\"/><w:pPr><w:ind w:left=\"720\"/></w:pPr><w:rPr><w:i/></w:rPr></w:style>\
+        <w:style w:type=\"paragraph\" w:styleId=\"ListParagraph\"><w:name w:val=\"List Paragraph\"/>\
+        <w:basedOn w:val=\"Normal\"/><w:pPr><w:spacing w:after=\"0\"/></w:pPr></w:style>\
+        <w:style w:type=\"paragraph\" w:styleId=\"CodeBlock\"><w:name w:val=\"Code Block\"/>\
+        <w:basedOn w:val=\"Normal\"/><w:pPr><w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"F2F2F2\"/></w:pPr>\
+        <w:rPr><w:rFonts w:ascii=\"Courier New\" w:hAnsi=\"Courier New\" w:cs=\"Courier New\"/></w:rPr></w:style>\
+        <w:style w:type=\"character\" w:styleId=\"CodeChar\"><w:name w:val=\"Code\"/>\
+        <w:rPr><w:rFonts w:ascii=\"Courier New\" w:hAnsi=\"Courier New\" w:cs=\"Courier New\"/></w:rPr></w:style>\
+        <w:style w:type=\"character\" w:styleId=\"Hyperlink\"><w:name w:val=\"Hyperlink\"/>\
+        <w:rPr><w:color w:val=\"0563C1\"/><w:u w:val=\"single\"/></w:rPr></w:style>\
+        <w:style w:type=\"table\" w:styleId=\"TableGrid\"><w:name w:val=\"Table Grid\"/><w:tblPr><w:tblBorders>\
+        <w:top w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>\
+        <w:left w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>\
+        <w:bottom w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>\
+        <w:right w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>\
+        <w:insideH w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>\
+        <w:insideV w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>\
+        </w:tblBorders></w:tblPr></w:style>";
+    xml + "</w:styles>"
+}
+
+/// Parse the markdown input and write it as a .docx archive
+pub fn transform_markdown_docx(input: &str) -> Result<Vec<u8>, Errcode> {
+    transform_markdown_docx_with_options(input, &TransformOptions::default())
+}
+
+/// Same as `transform_markdown_docx`, with the options of the transformation
+pub fn transform_markdown_docx_with_options(
+    input: &str,
+    options: &TransformOptions,
+) -> Result<Vec<u8>, Errcode> {
+    let document = Document::parse_with_mode(input, options.parse_mode)?;
+    let mut output = Cursor::new(vec![]);
+    DocxTransformer::default().write_with_options(&document, options, &mut output)?;
+    Ok(output.into_inner())
+}
@@ -0,0 +1,436 @@
+use super::html::{alert_color, escape, HtmlEscaping};
+use crate::prelude::*;
+use crate::{
+    AlertKind, Alignment, Footnote, ImageTags, LinkKind, ListItemInfo, Localizer,
+    MarkdownTransformer, ParagraphInfo, Phrase, StatefulTransformer, TableCell, TextDirection,
+};
+
+const MONOSPACE: &str = "Menlo,Consolas,'Courier New',monospace";
+const CODE_BACKGROUND: &str = "#f4f4f4";
+const BORDER_COLOR: &str = "#dddddd";
+
+// Styled elements, each written in a `style` attribute
+// Styled elements, written in a `style` attribute or as a class of the stylesheet
+#[derive(Clone, Copy)]
+enum Style {
+    Header(usize),
+    Link,
+    Footnote,
+    Separator,
+    Image,
+    Caption,
+    Quote,
+    Alert(AlertKind),
+    AlertLabel(AlertKind),
+    Details,
+    Summary,
+    TabTitle,
+    Codeblock,
+    InlineCode,
+    ListElement,
+    List,
+    Table,
+    Cell(&'static str),
+    Paragraph,
+    RtlParagraph,
+}
+
+/// Transformer rendering HTML for email bodies, which clients like Outlook or Gmail
+/// display correctly
+///
+/// Every element carries its own `style` attribute, as email clients drop stylesheets
+/// and classes, and no layout element (`<section>`, `<div>`) is emitted. Tables are only
+/// used for the tables of the document.
+#[derive(Clone)]
+pub struct EmailHtmlRenderer {
+    font_family: String,
+    text_color: String,
+    link_color: String,
+    localizer: Localizer,
+    refs: HashMap<String, String>,
+}
+
+impl Default for EmailHtmlRenderer {
+    fn default() -> Self {
+        EmailHtmlRenderer {
+            font_family: "Helvetica,Arial,sans-serif".to_string(),
+            text_color: "#222222".to_string(),
+            link_color: "#1a73e8".to_string(),
+            localizer: Localizer::default(),
+            refs: HashMap::new(),
+        }
+    }
+}
+
+impl EmailHtmlRenderer {
+    /// CSS `font-family` of the text, a list of fonts commonly available is safer
+    pub fn font_family(mut self, font_family: &str) -> Self {
+        self.font_family = font_family.to_string();
+        self
+    }
+
+    /// CSS color of the text
+    pub fn text_color(mut self, color: &str) -> Self {
+        self.text_color = color.to_string();
+        self
+    }
+
+    /// CSS color of the links
+    pub fn link_color(mut self, color: &str) -> Self {
+        self.link_color = color.to_string();
+        self
+    }
+
+    /// Translation of the titles of the alerts
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = localizer;
+        self
+    }
+
+    fn escape(&self, text: &str) -> String {
+        escape(text, HtmlEscaping::Minimal)
+    }
+
+    fn image(&self, alt: &str, url: &str, add_tags: &ImageTags) -> String {
+        // NOTE    Outlook ignores the CSS width of images, only the attribute is used
+        let width = add_tags
+            .get("width")
+            .map(|w| format!(" width=\"{}\"", self.escape(w.trim_matches('"'))))
+            .unwrap_or_default();
+        format!(
+            "<img src=\"{}\" alt=\"{}\"{width}{}>",
+            self.escape(url),
+            self.escape(alt),
+            self.style(Style::Image)
+        )
+    }
+
+    // Attribute styling the element, with a leading space
+    fn style(&self, style: Style) -> String {
+        format!(" style=\"{}\"", self.css(style))
+    }
+
+    fn css(&self, style: Style) -> String {
+        match style {
+            Style::Header(level) => {
+                let size = [28, 24, 20, 18, 16, 14][level.clamp(1, 6) - 1];
+                format!("margin:24px 0 12px;{}line-height:1.25;", self.text_style(size))
+            }
+            Style::Link => format!("color:{};text-decoration:underline;", self.link_color),
+            Style::Footnote => format!("margin:0 0 8px;{}line-height:1.4;", self.text_style(13)),
+            Style::Separator => format!("border:0;border-top:1px solid {BORDER_COLOR};margin:24px 0;"),
+            Style::Image => "display:block;max-width:100%;height:auto;border:0;".to_string(),
+            Style::Caption => format!("margin:4px 0 16px;{}font-style:italic;", self.text_style(13)),
+            Style::Quote => format!(
+                "margin:0 0 16px;padding:0 0 0 12px;border-left:4px solid {BORDER_COLOR};{}line-height:1.5;",
+                self.text_style(16)
+            ),
+            Style::Alert(kind) => format!(
+                "margin:0 0 16px;padding:0 0 0 12px;border-left:4px solid {};{}line-height:1.5;",
+                alert_color(kind),
+                self.text_style(16)
+            ),
+            Style::AlertLabel(kind) => format!("color:{};", alert_color(kind)),
+            Style::Details => "margin:0 0 16px;".to_string(),
+            Style::Summary => format!(
+                "margin:0 0 8px;{}font-weight:bold;cursor:pointer;",
+                self.text_style(16)
+            ),
+            Style::TabTitle => format!(
+                "margin:0 0 8px;padding:0 0 4px;border-bottom:2px solid {};{}font-weight:bold;",
+                self.link_color,
+                self.text_style(14)
+            ),
+            Style::Codeblock => format!(
+                "margin:0 0 16px;padding:12px;background-color:{CODE_BACKGROUND};font-family:{MONOSPACE};font-size:14px;line-height:1.4;white-space:pre-wrap;word-wrap:break-word;"
+            ),
+            Style::InlineCode => format!(
+                "padding:2px 4px;background-color:{CODE_BACKGROUND};font-family:{MONOSPACE};font-size:14px;"
+            ),
+            Style::ListElement => "margin:0 0 4px;".to_string(),
+            Style::List => format!(
+                "margin:0 0 16px;padding:0 0 0 24px;{}line-height:1.5;",
+                self.text_style(16)
+            ),
+            Style::Table => format!(
+                "margin:0 0 16px;border-collapse:collapse;{}",
+                self.text_style(15)
+            ),
+            Style::Cell(align) => {
+                format!("padding:6px 12px;border:1px solid {BORDER_COLOR};text-align:{align};")
+            }
+            Style::Paragraph => format!("margin:0 0 16px;{}line-height:1.5;", self.text_style(16)),
+            Style::RtlParagraph => format!(
+                "margin:0 0 16px;{}line-height:1.5;text-align:right;",
+                self.text_style(16)
+            ),
+        }
+    }
+
+    fn text_style(&self, size: usize) -> String {
+        format!(
+            "font-family:{};font-size:{size}px;color:{};",
+            self.escape(&self.font_family),
+            self.text_color
+        )
+    }
+}
+
+impl MarkdownTransformer for EmailHtmlRenderer {
+    fn localize(&mut self, phrase: Phrase) -> String {
+        self.escape(&self.localizer.text(phrase))
+    }
+
+    fn transform_text(&mut self, text: String) -> String {
+        self.escape(&text)
+    }
+
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        let style = self.style(Style::Header(level.clamp(1, 6)));
+        format!("<h{level}{style}>{text}</h{level}>")
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<strong>{text}</strong>")
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        format!("<em>{text}</em>")
+    }
+
+    fn transform_strikethrough(&mut self, text: String) -> String {
+        format!("<del>{text}</del>")
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.refs.insert(slug, url);
+    }
+
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        match self.refs.get(&slug).cloned() {
+            Some(url) => self.transform_link(text, url, LinkKind::External),
+            None => text,
+        }
+    }
+
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        format!(
+            "<a href=\"{}\"{}>{text}</a>",
+            self.escape(&url),
+            self.style(Style::Link)
+        )
+    }
+
+    fn transform_footnote_ref(&mut self, _label: String, number: usize) -> String {
+        format!("<sup>[{number}]</sup>")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let entries = entries
+            .into_iter()
+            .map(|f| {
+                format!(
+                    "<p{}>[{}] {}</p>",
+                    self.style(Style::Footnote),
+                    f.number,
+                    f.text
+                )
+            })
+            .collect::<Vec<String>>();
+        format!("<hr{}>{}", self.style(Style::Separator), entries.concat())
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+        self.image(&alt, &url, &add_tags)
+    }
+
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        format!(
+            "{image}<p{}>{}</p>",
+            self.style(Style::Caption),
+            self.escape(&caption)
+        )
+    }
+
+    fn transform_comment(&mut self, _text: String) -> String {
+        String::new()
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        format!(
+            "<blockquote{}>{}</blockquote>",
+            self.style(Style::Quote),
+            text.lines().collect::<Vec<&str>>().join("<br>")
+        )
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let label = self.localize(Phrase::Alert(kind));
+        format!(
+            "<blockquote{}><strong{}>{label}</strong><br>{}</blockquote>",
+            self.style(Style::Alert(kind)),
+            self.style(Style::AlertLabel(kind)),
+            body.lines().collect::<Vec<&str>>().join("<br>")
+        )
+    }
+
+    fn transform_details(&mut self, summary: String, body: String) -> String {
+        format!(
+            "<details{}><summary{}>{summary}</summary>{body}</details>",
+            self.style(Style::Details),
+            self.style(Style::Summary)
+        )
+    }
+
+    // NOTE    Email clients can't switch between tabs, all of them are shown one after the other
+    fn transform_tab_group(&mut self, tabs: Vec<(String, String)>) -> String {
+        tabs.into_iter()
+            .map(|(title, body)| format!("<p{}>{title}</p>{body}", self.style(Style::TabTitle)))
+            .collect()
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        _language: Option<String>,
+        _flags: Vec<String>,
+        text: String,
+    ) -> String {
+        format!(
+            "<pre{}>{}</pre>",
+            self.style(Style::Codeblock),
+            self.escape(&text)
+        )
+    }
+
+    fn transform_inline_code(&mut self, text: String) -> String {
+        format!(
+            "<code{}>{}</code>",
+            self.style(Style::InlineCode),
+            self.escape(&text)
+        )
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        format!("<hr{}>", self.style(Style::Separator))
+    }
+
+    fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+        let checkbox = match info.checked {
+            Some(true) => "<input type=\"checkbox\" disabled checked> ",
+            Some(false) => "<input type=\"checkbox\" disabled> ",
+            None => "",
+        };
+        format!(
+            "<li{}>{checkbox}{element}</li>",
+            self.style(Style::ListElement)
+        )
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        format!("<ul{}>{}</ul>", self.style(Style::List), elements.concat())
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        format!(
+            "<ol start=\"{start}\"{}>{}</ol>",
+            self.style(Style::List),
+            elements.concat()
+        )
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let cells = |row: Vec<String>| row.into_iter().map(TableCell::new).collect();
+        let rows = rows.into_iter().map(cells).collect();
+        self.transform_table_with_spans(cells(header), alignments, rows)
+    }
+
+    fn transform_table_with_spans(
+        &mut self,
+        header: Vec<TableCell>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<TableCell>>,
+    ) -> String {
+        let row = |tag: &str, cells: Vec<TableCell>| {
+            let mut buffer = "<tr>".to_string();
+            let mut column = 0;
+            for cell in cells {
+                let align = match alignments.get(column) {
+                    Some(Alignment::Center) => "center",
+                    Some(Alignment::Right) => "right",
+                    _ => "left",
+                };
+                let colspan = match cell.colspan {
+                    1 => String::new(),
+                    n => format!(" colspan=\"{n}\""),
+                };
+                buffer += &format!(
+                    "<{tag}{colspan}{}>{}</{tag}>",
+                    self.style(Style::Cell(align)),
+                    cell.text.replace('\n', "<br>")
+                );
+                column += cell.colspan;
+            }
+            buffer + "</tr>"
+        };
+        let mut buffer = format!(
+            "<table cellpadding=\"0\" cellspacing=\"0\"{}>",
+            self.style(Style::Table)
+        );
+        buffer += &row("th", header);
+        for cells in rows {
+            buffer += &row("td", cells);
+        }
+        buffer + "</table>"
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        "<br>".to_string()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        format!("<p{}>{text}</p>", self.style(Style::Paragraph))
+    }
+
+    fn transform_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        let lang = info
+            .lang
+            .map(|lang| format!(" lang=\"{}\"", self.escape(&lang)))
+            .unwrap_or_default();
+        // NOTE    Mail clients ignoring the attribute still get the alignment from the style
+        match info.direction {
+            TextDirection::Ltr => format!("<p{lang}{}>{text}</p>", self.style(Style::Paragraph)),
+            TextDirection::Rtl => format!(
+                "<p dir=\"rtl\"{lang}{}>{text}</p>",
+                self.style(Style::RtlParagraph)
+            ),
+        }
+    }
+}
+
+// Reference links are only valid in the document defining them
+impl StatefulTransformer for EmailHtmlRenderer {
+    type Snapshot = HashMap<String, String>;
+
+    fn reset(&mut self) {
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.refs.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.refs = snapshot;
+    }
+}
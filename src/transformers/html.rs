@@ -0,0 +1,618 @@
+use crate::prelude::*;
+use crate::{
+    srcset, AlertKind, Alignment, ElementContext, Footnote, ImageCandidate, ImageTags, LinkKind,
+    ListItemInfo, Localizer, MarkdownTransformer, ParagraphInfo, Phrase, StatefulTransformer,
+    TableCell, TextDirection,
+};
+
+/// Characters of the text and attribute values escaped by the `HtmlRenderer`, depending on
+/// how much the host displaying its output trusts the documents
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HtmlEscaping {
+    /// `&`, `<`, `>` and `"`, enough for the output to be valid HTML
+    #[default]
+    Minimal,
+    /// Also `'`, `` ` ``, `=` and `/`, for the output inserted in templates or pages
+    /// showing untrusted content, like comments
+    Strict,
+    /// Every ASCII character apart from letters, digits and whitespace, encoded as
+    /// `&#xHH;`, for the output inserted in an attribute value
+    Attribute,
+}
+
+const ALERT_KINDS: [AlertKind; 5] = [
+    AlertKind::Note,
+    AlertKind::Tip,
+    AlertKind::Important,
+    AlertKind::Warning,
+    AlertKind::Caution,
+];
+
+/// Transformer rendering HTML for web pages
+///
+/// No element carries a `style` attribute, so the output complies with the Content Security
+/// Policies forbidding inline styles: the few elements needing a style (aligned table cells,
+/// alerts, ...) get a class (`md-align-center`, `md-alert-note`, ...) styled by the
+/// `stylesheet`, served by the site or written in a `<style>` element bearing the nonce
+/// given to `style_nonce`.
+///
+/// The links and images to `javascript:`, `vbscript:` or `data:` URLs are rendered as their
+/// text, whatever the `HtmlEscaping`.
+#[derive(Clone, Default)]
+pub struct HtmlRenderer {
+    localizer: Localizer,
+    escaping: HtmlEscaping,
+    escape_raw_html: bool,
+    allowed_tags: HashSet<String>,
+    style_nonce: Option<String>,
+    permalink: Option<String>,
+    anchor: Option<String>,
+    refs: HashMap<String, (String, Option<String>)>,
+}
+
+impl HtmlRenderer {
+    /// Translation of the titles of the alerts
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = localizer;
+        self
+    }
+
+    /// Characters escaped in the text and the attribute values
+    pub fn escaping(mut self, escaping: HtmlEscaping) -> Self {
+        self.escaping = escaping;
+        self
+    }
+
+    /// Show the HTML blocks written in the document as text instead of keeping them as
+    /// they are, apart from the ones of the tags allowed with `allow_html_tag`
+    pub fn escape_raw_html(mut self, escape: bool) -> Self {
+        self.escape_raw_html = escape;
+        self
+    }
+
+    /// Tag allowed in the HTML blocks once `escape_raw_html` is enabled
+    ///
+    /// A block is only kept as written when all the tags it holds are allowed and none of
+    /// them has an event handler attribute (`onclick`, ...) or a `javascript:`, `vbscript:`
+    /// or `data:` URL, it is shown as text otherwise.
+    pub fn allow_html_tag<S: ToString>(mut self, tag: S) -> Self {
+        self.allowed_tags.insert(tag.to_string().to_lowercase());
+        self
+    }
+
+    /// Write the `stylesheet` at the end of the output, in a `<style>` element bearing this
+    /// nonce, allowed by the `style-src 'nonce-...'` directive of the Content Security Policy
+    pub fn style_nonce<S: ToString>(mut self, nonce: S) -> Self {
+        self.style_nonce = Some(nonce.to_string());
+        self
+    }
+
+    /// Give each heading the `id` of its anchor, followed by a permalink to it
+    /// (`<a class="anchor" href="#slug">`) whose content is the HTML `symbol`
+    pub fn permalinks<S: ToString>(mut self, symbol: S) -> Self {
+        self.permalink = Some(symbol.to_string());
+        self
+    }
+
+    /// Rules styling the classes of the elements, to be served along with the output
+    pub fn stylesheet(&self) -> String {
+        let mut rules = vec![];
+        for align in ["left", "center", "right"] {
+            rules.push(format!(".md-align-{align} {{ text-align:{align}; }}"));
+        }
+        rules.push(".md-alert { padding:0 0 0 12px; border-left:4px solid; }".to_string());
+        for kind in ALERT_KINDS {
+            let color = alert_color(kind);
+            let name = kind.label().to_lowercase();
+            rules.push(format!(".md-alert-{name} {{ border-color:{color}; }}"));
+            rules.push(format!(
+                ".md-alert-{name} .md-alert-title {{ color:{color}; }}"
+            ));
+        }
+        rules.push(".md-alert-title { font-weight:bold; }".to_string());
+        rules.push(".md-tab-title { font-weight:bold; }".to_string());
+        rules.push(".anchor { text-decoration:none; }".to_string());
+        rules.join("\n")
+    }
+
+    fn escape(&self, text: &str) -> String {
+        escape(text, self.escaping)
+    }
+
+    // Image element, `attributes` being added after its source and alternative text
+    fn image(&self, alt: &str, url: &str, attributes: &str, add_tags: &ImageTags) -> String {
+        if !is_safe_url(url) {
+            return self.escape(alt);
+        }
+        let mut size = String::new();
+        for key in ["width", "height"] {
+            if let Some(value) = add_tags.get(key) {
+                size += &format!(" {key}=\"{}\"", self.escape(value));
+            }
+        }
+        format!(
+            "<img src=\"{}\" alt=\"{}\"{attributes}{size}>",
+            self.escape(url),
+            self.escape(alt)
+        )
+    }
+
+    // Aligned cell of a table, the class giving its alignment
+    fn cell(&self, tag: &str, cell: TableCell, alignment: Option<&Alignment>) -> String {
+        let class = match alignment {
+            Some(Alignment::Left) => " class=\"md-align-left\"",
+            Some(Alignment::Center) => " class=\"md-align-center\"",
+            Some(Alignment::Right) => " class=\"md-align-right\"",
+            _ => "",
+        };
+        let colspan = match cell.colspan {
+            1 => String::new(),
+            n => format!(" colspan=\"{n}\""),
+        };
+        format!(
+            "<{tag}{colspan}{class}>{}</{tag}>",
+            cell.text.replace('\n', "<br>")
+        )
+    }
+}
+
+pub(super) fn escape(text: &str, escaping: HtmlEscaping) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match (c, escaping) {
+            ('&', _) => escaped += "&amp;",
+            ('<', _) => escaped += "&lt;",
+            ('>', _) => escaped += "&gt;",
+            ('"', _) => escaped += "&quot;",
+            ('\'' | '`' | '=' | '/', HtmlEscaping::Strict) => {
+                escaped += &format!("&#{};", c as u32)
+            }
+            (c, HtmlEscaping::Attribute)
+                if c.is_ascii() && !c.is_ascii_alphanumeric() && !c.is_ascii_whitespace() =>
+            {
+                escaped += &format!("&#x{:X};", c as u32)
+            }
+            (c, _) => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(super) fn alert_color(kind: AlertKind) -> &'static str {
+    match kind {
+        AlertKind::Note => "#0969da",
+        AlertKind::Tip => "#1a7f37",
+        AlertKind::Important => "#8250df",
+        AlertKind::Warning => "#9a6700",
+        AlertKind::Caution => "#cf222e",
+    }
+}
+
+// Paragraph with the attributes, its figures being written between its parts as they can't
+// be held by a `<p>`
+fn paragraph(attributes: &str, text: &str) -> String {
+    let mut buffer = String::new();
+    let mut rest = text;
+    loop {
+        let (part, figure) = match rest.find("<figure>") {
+            Some(start) => {
+                let end = rest[start..]
+                    .find("</figure>")
+                    .map_or(rest.len(), |end| start + end + "</figure>".len());
+                (&rest[..start], &rest[start..end])
+            }
+            None => (rest, ""),
+        };
+        if !part.trim().is_empty() {
+            buffer += &format!("<p{attributes}>{part}</p>");
+        }
+        buffer += figure;
+        rest = &rest[part.len() + figure.len()..];
+        if rest.is_empty() {
+            return buffer;
+        }
+    }
+}
+
+// Whether all the tags of the HTML are allowed, without event handlers or unsafe URLs
+fn is_allowed_html(html: &str, allowed: &HashSet<String>) -> bool {
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if let Some(comment) = rest.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let tag = rest.strip_prefix('/').unwrap_or(rest);
+        let name_len = tag
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(tag.len());
+        // NOTE    A `<` not starting a tag, like in `a < b`, is text
+        if name_len == 0 && !tag.starts_with('!') {
+            continue;
+        }
+        if !allowed.contains(&tag[..name_len].to_lowercase()) {
+            return false;
+        }
+        let (attrs, after) = split_tag(&tag[name_len..]);
+        if !are_allowed_attrs(attrs) {
+            return false;
+        }
+        rest = after;
+    }
+    true
+}
+
+// Attributes of a tag up to its closing `>`, and the HTML following it
+fn split_tag(tag: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (n, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return (&tag[..n], &tag[n + 1..]),
+            _ => {}
+        }
+    }
+    (tag, "")
+}
+
+// Whether the URL doesn't run a script or embed content once clicked or loaded
+fn is_safe_url(url: &str) -> bool {
+    // NOTE    Browsers decode the entities and ignore the whitespaces and control
+    //         characters in URL schemes, `&#106;ava&Tab;script:` being `javascript:`
+    let url = decode_entities(url)
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_lowercase();
+    !["javascript:", "vbscript:", "data:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded += &rest[..start];
+        rest = &rest[start + 1..];
+        match decode_entity(rest) {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => decoded.push('&'),
+        }
+    }
+    decoded + rest
+}
+
+// Character of the entity starting the text (following its `&`), and the length of the entity
+fn decode_entity(text: &str) -> Option<(char, usize)> {
+    if let Some(number) = text.strip_prefix('#') {
+        let (radix, digits, prefix) = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => (16, hex, 2),
+            None => (10, number, 1),
+        };
+        let len = digits
+            .find(|c: char| !c.is_digit(radix))
+            .unwrap_or(digits.len());
+        let c = u32::from_str_radix(&digits[..len], radix)
+            .ok()
+            .and_then(char::from_u32)?;
+        // NOTE    The semicolon of a numeric entity is optional
+        let semicolon = usize::from(digits[len..].starts_with(';'));
+        return Some((c, prefix + len + semicolon));
+    }
+    let len = text.find(';')?;
+    let c = match text[..len].to_lowercase().as_str() {
+        "colon" => ':',
+        "tab" => '\t',
+        "newline" => '\n',
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        _ => return None,
+    };
+    Some((c, len + 1))
+}
+
+fn are_allowed_attrs(attrs: &str) -> bool {
+    let mut rest = attrs.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        if rest[..name_len].to_lowercase().starts_with("on") {
+            return false;
+        }
+        rest = rest[name_len..].trim_start();
+        if let Some(value) = rest.strip_prefix('=') {
+            let value = value.trim_start();
+            let (value, after) = match value.chars().next() {
+                Some(q @ ('"' | '\'')) => match value[1..].find(q) {
+                    Some(end) => (&value[1..end + 1], &value[end + 2..]),
+                    None => (&value[1..], ""),
+                },
+                _ => value.split_at(value.find(char::is_whitespace).unwrap_or(value.len())),
+            };
+            if !is_safe_url(value) {
+                return false;
+            }
+            rest = after;
+        }
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+    }
+    true
+}
+
+impl MarkdownTransformer for HtmlRenderer {
+    fn localize(&mut self, phrase: Phrase) -> String {
+        self.escape(&self.localizer.text(phrase))
+    }
+
+    fn transform_text(&mut self, text: String) -> String {
+        self.escape(&text)
+    }
+
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.anchor = ctx.anchor().map(str::to_string);
+    }
+
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        match (&self.permalink, &self.anchor) {
+            (Some(symbol), Some(anchor)) => {
+                let anchor = self.escape(anchor);
+                format!(
+                    "<h{level} id=\"{anchor}\">{text} <a class=\"anchor\" href=\"#{anchor}\">{symbol}</a></h{level}>"
+                )
+            }
+            _ => format!("<h{level}>{text}</h{level}>"),
+        }
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<strong>{text}</strong>")
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        format!("<em>{text}</em>")
+    }
+
+    fn transform_strikethrough(&mut self, text: String) -> String {
+        format!("<del>{text}</del>")
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, title: Option<String>) {
+        self.refs.insert(slug, (url, title));
+    }
+
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        let Some((url, title)) = self.refs.get(&slug).cloned() else {
+            return text;
+        };
+        if !is_safe_url(&url) {
+            return text;
+        }
+        let title = title
+            .map(|title| format!(" title=\"{}\"", self.escape(&title)))
+            .unwrap_or_default();
+        format!("<a href=\"{}\"{title}>{text}</a>", self.escape(&url))
+    }
+
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        if !is_safe_url(&url) {
+            return text;
+        }
+        format!("<a href=\"{}\">{text}</a>", self.escape(&url))
+    }
+
+    fn transform_footnote_ref(&mut self, _label: String, number: usize) -> String {
+        format!("<sup id=\"fnref-{number}\"><a href=\"#fn-{number}\">{number}</a></sup>")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let entries = entries
+            .into_iter()
+            .map(|f| {
+                format!(
+                    "<li id=\"fn-{n}\">{} <a href=\"#fnref-{n}\">\u{21A9}</a></li>",
+                    f.text,
+                    n = f.number
+                )
+            })
+            .collect::<Vec<String>>();
+        format!("<hr><ol class=\"md-footnotes\">{}</ol>", entries.concat())
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+        self.image(&alt, &url, "", &add_tags)
+    }
+
+    fn transform_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) -> String {
+        let responsive = format!(
+            " srcset=\"{}\" loading=\"lazy\"",
+            self.escape(&srcset(&candidates))
+        );
+        self.image(&alt, &url, &responsive, &add_tags)
+    }
+
+    // NOTE    The caption is already escaped by `transform_text`
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        format!("<figure>{image}<figcaption>{caption}</figcaption></figure>")
+    }
+
+    fn transform_comment(&mut self, _text: String) -> String {
+        String::new()
+    }
+
+    fn transform_html_block(&mut self, _tag: String, html: String) -> String {
+        if !self.escape_raw_html || is_allowed_html(&html, &self.allowed_tags) {
+            return html;
+        }
+        let text = self.escape(&html).replace('\n', "<br>");
+        self.transform_paragraph(text)
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        format!(
+            "<blockquote><p>{}</p></blockquote>",
+            text.lines().collect::<Vec<&str>>().join("<br>")
+        )
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let label = self.localize(Phrase::Alert(kind));
+        format!(
+            "<div class=\"md-alert md-alert-{}\"><p class=\"md-alert-title\">{label}</p><p>{}</p></div>",
+            kind.label().to_lowercase(),
+            body.lines().collect::<Vec<&str>>().join("<br>")
+        )
+    }
+
+    fn transform_details(&mut self, summary: String, body: String) -> String {
+        format!("<details><summary>{summary}</summary>{body}</details>")
+    }
+
+    // NOTE    Switching between the tabs needs a script, left to the site
+    fn transform_tab_group(&mut self, tabs: Vec<(String, String)>) -> String {
+        let tabs = tabs
+            .into_iter()
+            .map(|(title, body)| {
+                format!("<div class=\"md-tab\"><p class=\"md-tab-title\">{title}</p>{body}</div>")
+            })
+            .collect::<String>();
+        format!("<div class=\"md-tabs\">{tabs}</div>")
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        _flags: Vec<String>,
+        text: String,
+    ) -> String {
+        let class = language
+            .map(|language| format!(" class=\"language-{}\"", self.escape(&language)))
+            .unwrap_or_default();
+        format!("<pre><code{class}>{}</code></pre>", self.escape(&text))
+    }
+
+    fn transform_inline_code(&mut self, text: String) -> String {
+        format!("<code>{}</code>", self.escape(&text))
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        "<hr>".to_string()
+    }
+
+    fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+        let checkbox = match info.checked {
+            Some(true) => "<input type=\"checkbox\" disabled checked> ",
+            Some(false) => "<input type=\"checkbox\" disabled> ",
+            None => "",
+        };
+        format!("<li>{checkbox}{element}</li>")
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        format!("<ul>{}</ul>", elements.concat())
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        let start = match start {
+            1 => String::new(),
+            n => format!(" start=\"{n}\""),
+        };
+        format!("<ol{start}>{}</ol>", elements.concat())
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let cells = |row: Vec<String>| row.into_iter().map(TableCell::new).collect();
+        let rows = rows.into_iter().map(cells).collect();
+        self.transform_table_with_spans(cells(header), alignments, rows)
+    }
+
+    fn transform_table_with_spans(
+        &mut self,
+        header: Vec<TableCell>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<TableCell>>,
+    ) -> String {
+        let row = |tag: &str, cells: Vec<TableCell>| {
+            let mut buffer = "<tr>".to_string();
+            let mut column = 0;
+            for cell in cells {
+                let colspan = cell.colspan;
+                buffer += &self.cell(tag, cell, alignments.get(column));
+                column += colspan;
+            }
+            buffer + "</tr>"
+        };
+        let mut buffer = format!("<table><thead>{}</thead><tbody>", row("th", header));
+        for cells in rows {
+            buffer += &row("td", cells);
+        }
+        buffer + "</tbody></table>"
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        String::new()
+    }
+
+    fn finished(&mut self, peek: bool) -> String {
+        match self.style_nonce {
+            Some(ref nonce) if !peek => format!(
+                "<style nonce=\"{}\">{}</style>",
+                self.escape(nonce),
+                self.stylesheet()
+            ),
+            _ => String::new(),
+        }
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        paragraph("", &text)
+    }
+
+    fn transform_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) -> String {
+        let lang = info
+            .lang
+            .map(|lang| format!(" lang=\"{}\"", self.escape(&lang)))
+            .unwrap_or_default();
+        match info.direction {
+            TextDirection::Ltr => paragraph(&lang, &text),
+            TextDirection::Rtl => paragraph(&format!(" dir=\"rtl\"{lang}"), &text),
+        }
+    }
+}
+
+// Reference links are only valid in the document defining them
+impl StatefulTransformer for HtmlRenderer {
+    type Snapshot = HashMap<String, (String, Option<String>)>;
+
+    fn reset(&mut self) {
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.refs.clone()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.refs = snapshot;
+    }
+}
@@ -0,0 +1,441 @@
+use super::Blocks;
+use crate::prelude::*;
+use crate::table::format_spanned_table;
+use crate::{
+    format_table, reflow, AlertKind, Alignment, CrossrefKind, ElementContext, Footnote, ImageTags,
+    LinkKind, ListItemInfo, MarkdownTransformer, ParagraphInfo, StatefulTransformer, TableCell,
+};
+
+/// How links are written back in the rendered markdown
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LinkStyle {
+    /// Keep inline links inline and reference links as references
+    #[default]
+    Preserve,
+    /// Convert every inline link to a reference link, definitions collected at the bottom
+    Reference,
+    /// Resolve every reference link to an inline link, definitions are removed
+    Inline,
+}
+
+/// Transformer rendering the parsed document back to markdown, normalizing its syntax
+#[derive(Clone)]
+pub struct MarkdownRenderer {
+    link_style: LinkStyle,
+    bullet: char,
+    renumber_lists: bool,
+    wrap_width: Option<usize>,
+    blocks: Blocks,
+    refs: HashMap<String, String>,
+    definitions: Vec<(String, String)>,
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        MarkdownRenderer {
+            link_style: LinkStyle::default(),
+            bullet: '-',
+            renumber_lists: false,
+            wrap_width: None,
+            blocks: Blocks::default(),
+            refs: HashMap::new(),
+            definitions: vec![],
+        }
+    }
+}
+
+impl MarkdownRenderer {
+    pub fn link_style(mut self, style: LinkStyle) -> Self {
+        self.link_style = style;
+        self
+    }
+
+    /// Character used as marker for every unordered list element (`-`, `*` or `+`)
+    pub fn bullet(mut self, bullet: char) -> Self {
+        self.bullet = bullet;
+        self
+    }
+
+    /// Number every ordered list from 1, instead of the number of its first element
+    pub fn renumber_lists(mut self, renumber: bool) -> Self {
+        self.renumber_lists = renumber;
+        self
+    }
+
+    /// Hard-wrap paragraphs so their lines don't exceed `width` columns
+    pub fn wrap(mut self, width: usize) -> Self {
+        self.wrap_width = Some(width);
+        self
+    }
+
+    fn render_list<I: Iterator<Item = String>>(
+        &mut self,
+        markers: I,
+        elements: Vec<String>,
+    ) -> String {
+        let lines = markers
+            .zip(elements)
+            .map(|(marker, element)| {
+                let indent = " ".repeat(marker.len());
+                let lines = element
+                    .lines()
+                    .map(|line| match line {
+                        "" => String::new(),
+                        line => format!("{indent}{line}"),
+                    })
+                    .collect::<Vec<String>>();
+                format!("{marker}{}", lines.join("\n").trim_start())
+            })
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn definition_slug(&mut self, url: &str) -> String {
+        if let Some((slug, _)) = self.definitions.iter().find(|(_, u)| u == url) {
+            return slug.clone();
+        }
+        let mut n = self.definitions.len() + 1;
+        while self.refs.contains_key(&n.to_string())
+            || self.definitions.iter().any(|(s, _)| *s == n.to_string())
+        {
+            n += 1;
+        }
+        let slug = n.to_string();
+        self.definitions.push((slug.clone(), url.to_string()));
+        slug
+    }
+}
+
+// Values of image tags have to be quoted when they are not a single word
+fn quote_tag_value(value: String) -> String {
+    if value.contains(|c: char| c.is_whitespace() || c == ',' || c == ']') {
+        format!("\"{value}\"")
+    } else {
+        value
+    }
+}
+
+impl MarkdownTransformer for MarkdownRenderer {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.blocks.context(ctx);
+    }
+
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        self.blocks.push(format!("{} {text}", "#".repeat(level)))
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("**{text}**")
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        format!("*{text}*")
+    }
+
+    fn transform_strikethrough(&mut self, text: String) -> String {
+        format!("~~{text}~~")
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.refs.insert(slug, url);
+    }
+
+    // NOTE    Inline links have no title, it is lost when converting to inline style
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        match (self.link_style, self.refs.get(&slug)) {
+            (LinkStyle::Inline, Some(url)) => format!("[{text}]({url})"),
+            _ => format!("[{text}][{slug}]"),
+        }
+    }
+
+    fn transform_refurl(&mut self, slug: String, url: String, title: Option<String>) -> String {
+        let url = match title {
+            Some(title) => format!("{url} \"{title}\""),
+            None => url,
+        };
+        match self.link_style {
+            LinkStyle::Preserve => format!("[{slug}]: {url}"),
+            LinkStyle::Inline => String::new(),
+            LinkStyle::Reference => {
+                if !self.definitions.iter().any(|(s, _)| *s == slug) {
+                    self.definitions.push((slug, url));
+                }
+                String::new()
+            }
+        }
+    }
+
+    fn transform_autolink(&mut self, text: String, _url: String, _kind: LinkKind) -> String {
+        text
+    }
+
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        if self.link_style == LinkStyle::Reference {
+            let slug = self.definition_slug(&url);
+            format!("[{text}][{slug}]")
+        } else {
+            format!("[{text}]({url})")
+        }
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+        let mut buffer = format!("![{alt}]({url})");
+        if !add_tags.is_empty() {
+            let tags = add_tags
+                .into_iter()
+                .map(|(key, val)| format!("{key}: {}", quote_tag_value(val)))
+                .collect::<Vec<String>>();
+            buffer += format!("[{}]", tags.join(", ")).as_str();
+        }
+        buffer
+    }
+
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        let caption = format!("caption: {}", quote_tag_value(caption));
+        match image.strip_suffix(']') {
+            Some(image) => format!("{image}, {caption}]"),
+            None => format!("{image}[{caption}]"),
+        }
+    }
+
+    fn transform_crossref(
+        &mut self,
+        _kind: CrossrefKind,
+        slug: String,
+        _resolved: String,
+    ) -> String {
+        format!("[#{slug}]")
+    }
+
+    fn transform_citation(&mut self, keys: Vec<String>) -> String {
+        let keys = keys
+            .into_iter()
+            .map(|key| format!("@{key}"))
+            .collect::<Vec<String>>();
+        format!("[{}]", keys.join("; "))
+    }
+
+    fn transform_shortcode(&mut self, name: String, args: Vec<String>) -> String {
+        let mut buffer = format!("{{{{{name}");
+        for arg in args {
+            if arg.contains(char::is_whitespace) {
+                buffer += format!(" \"{arg}\"").as_str();
+            } else {
+                buffer += format!(" {arg}").as_str();
+            }
+        }
+        buffer + "}}"
+    }
+
+    fn transform_comment(&mut self, text: String) -> String {
+        self.blocks.push(format!("<!-- {text} -->"))
+    }
+
+    fn transform_raw_block(&mut self, source: String) -> String {
+        self.blocks.push(source)
+    }
+
+    fn transform_html_block(&mut self, _tag: String, html: String) -> String {
+        self.blocks.push(html)
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        // NOTE    A hard break already ends its line, the quote lines are joined after it
+        let lines = text
+            .replace("  \n\n", "  \n")
+            .lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let lines = core::iter::once(format!("> [!{}]", kind.label()))
+            .chain(
+                body.replace("  \n\n", "  \n")
+                    .lines()
+                    .map(|line| format!("> {line}")),
+            )
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_details(&mut self, summary: String, body: String) -> String {
+        // NOTE    The blocks start right under the summary line
+        let lines = core::iter::once(format!("??? {summary}"))
+            .chain(
+                body.trim_start_matches('\n')
+                    .lines()
+                    .map(|line| match line {
+                        "" => String::new(),
+                        line => format!("    {line}"),
+                    }),
+            )
+            .collect::<Vec<String>>();
+        self.blocks.push(lines.join("\n"))
+    }
+
+    fn transform_tab_group(&mut self, tabs: Vec<(String, String)>) -> String {
+        let tabs = tabs
+            .into_iter()
+            .map(|(title, body)| {
+                core::iter::once(format!("=== \"{title}\""))
+                    .chain(
+                        body.trim_start_matches('\n')
+                            .lines()
+                            .map(|line| match line {
+                                "" => String::new(),
+                                line => format!("    {line}"),
+                            }),
+                    )
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .collect::<Vec<String>>();
+        self.blocks.push(tabs.join("\n\n"))
+    }
+
+    fn transform_invalid(&mut self, raw: String) -> String {
+        self.blocks.push(raw)
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        flags: Vec<String>,
+        text: String,
+    ) -> String {
+        let info = language.into_iter().chain(flags).collect::<Vec<String>>();
+        // NOTE    An empty block has no line of code between its fences
+        let code = if text.is_empty() { text } else { text + "\n" };
+        self.blocks
+            .push(format!("```{}\n{code}```", info.join(",")))
+    }
+
+    fn transform_inline_code(&mut self, text: String) -> String {
+        if text.contains('`') {
+            format!("`` {text} ``")
+        } else {
+            format!("`{text}`")
+        }
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        self.blocks.push("---".to_string())
+    }
+
+    fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+        match info.checked {
+            Some(true) => format!("[x] {element}"),
+            Some(false) => format!("[ ] {element}"),
+            None => element,
+        }
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        let marker = format!("{} ", self.bullet);
+        self.render_list(core::iter::repeat(marker), elements)
+    }
+
+    fn transform_ordered_list(&mut self, start: usize, elements: Vec<String>) -> String {
+        let start = if self.renumber_lists { 1 } else { start };
+        self.render_list((start..).map(|n| format!("{n}. ")), elements)
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let mut all_rows = vec![header];
+        all_rows.extend(rows);
+        self.blocks.push(format_table(&all_rows, &alignments))
+    }
+
+    fn transform_table_with_spans(
+        &mut self,
+        header: Vec<TableCell>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<TableCell>>,
+    ) -> String {
+        self.blocks
+            .push(format_spanned_table(&header, &alignments, &rows))
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        "  \n".to_string()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        // NOTE    The line after a hard break is joined with a space, as the other lines are
+        let text = text.replace("  \n ", "  \n");
+        match self.wrap_width {
+            Some(width) => self.blocks.push(reflow(&text, width)),
+            None => self.blocks.push(text),
+        }
+    }
+
+    fn transform_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) -> String {
+        match info.lang {
+            Some(lang) if !text.trim().is_empty() => {
+                self.transform_paragraph(format!("{{lang={lang}}} {text}"))
+            }
+            _ => self.transform_paragraph(text),
+        }
+    }
+
+    fn transform_footnote_ref(&mut self, label: String, _number: usize) -> String {
+        format!("[^{label}]")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let entries = entries
+            .into_iter()
+            .map(|f| format!("[^{}]: {}", f.label, f.text))
+            .collect::<Vec<String>>();
+        self.blocks.push(entries.join("\n"))
+    }
+
+    fn finished(&mut self, peek: bool) -> String {
+        // NOTE    Inline hooks are also called during the peek pass, start over cleanly
+        if peek {
+            self.blocks.reset();
+            self.definitions.clear();
+            return String::new();
+        }
+        self.refs.clear();
+        if self.definitions.is_empty() {
+            self.blocks.reset();
+            return String::new();
+        }
+        let definitions = core::mem::take(&mut self.definitions)
+            .into_iter()
+            .map(|(slug, url)| format!("[{slug}]: {url}"))
+            .collect::<Vec<String>>();
+        let res = self.blocks.push(definitions.join("\n"));
+        self.blocks.reset();
+        res
+    }
+}
+
+impl StatefulTransformer for MarkdownRenderer {
+    type Snapshot = MarkdownRenderer;
+
+    fn reset(&mut self) {
+        self.blocks.reset();
+        self.refs.clear();
+        self.definitions.clear();
+    }
+
+    fn snapshot(&self) -> MarkdownRenderer {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: MarkdownRenderer) {
+        *self = snapshot;
+    }
+}
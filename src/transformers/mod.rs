@@ -0,0 +1,66 @@
+mod audit;
+mod chat;
+#[cfg(feature = "docx")]
+mod docx;
+mod email_html;
+mod html;
+mod markdown;
+mod passthrough;
+mod plain_text;
+mod ssml;
+mod trace;
+
+use crate::{prelude::*, ElementContext};
+
+pub use audit::{AccessibilityAudit, AuditIssue};
+pub use chat::{DiscordRenderer, TelegramRenderer};
+#[cfg(feature = "docx")]
+pub use docx::{transform_markdown_docx, transform_markdown_docx_with_options, DocxTransformer};
+pub use email_html::EmailHtmlRenderer;
+pub use html::{HtmlEscaping, HtmlRenderer};
+pub use markdown::{LinkStyle, MarkdownRenderer};
+pub use passthrough::PassthroughTransformer;
+pub use plain_text::PlainTextRenderer;
+pub use ssml::{CodeBlockSpeech, SsmlRenderer};
+pub use trace::{HookCall, TraceTransformer};
+
+// Top-level blocks of the documents rendered as text, each one separated from the previous one
+// with an empty line
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Blocks {
+    nb_blocks: usize,
+    nested: bool,
+}
+
+impl Blocks {
+    // Top-level blocks always start on the first column
+    pub(crate) fn context(&mut self, ctx: &ElementContext) {
+        self.nested = ctx.span().column > 1;
+    }
+
+    // Whether the block is the first top-level one of the document, `None` for the blocks
+    // nested in another element
+    pub(crate) fn count(&mut self) -> Option<bool> {
+        if self.nested {
+            return None;
+        }
+        self.nb_blocks += 1;
+        Some(self.nb_blocks == 1)
+    }
+
+    pub(crate) fn push(&mut self, text: String) -> String {
+        // NOTE    Blocks nested in a list element always follow the text of the element
+        match self.count() {
+            Some(true) => text,
+            _ => format!("\n\n{text}"),
+        }
+    }
+
+    pub(crate) fn is_started(&self) -> bool {
+        self.nb_blocks > 0
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.nb_blocks = 0;
+    }
+}
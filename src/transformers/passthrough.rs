@@ -0,0 +1,361 @@
+use crate::prelude::*;
+use crate::{
+    transformers::MarkdownRenderer, AlertKind, Alignment, CodeblockInfo, CrossrefKind, DiagramKind,
+    Directive, ElementContext, Footnote, ImageCandidate, ImageTags, LinkKind, ListItemInfo,
+    MarkdownTransformer, ParagraphInfo, Phrase, TableCell,
+};
+
+// Hooks whose default writes the markdown of the element with the `MarkdownRenderer`
+macro_rules! renderer_hooks {
+    ($($name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {$(
+        fn $name(&mut self, $($arg: $ty),*) $(-> $ret)? {
+            self.renderer().$name($($arg),*)
+        }
+    )*};
+}
+
+// Hooks of the `MarkdownTransformer` forwarded to the `PassthroughTransformer` ones
+macro_rules! forward_hooks {
+    ($($name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {$(
+        fn $name(&mut self, $($arg: $ty),*) $(-> $ret)? {
+            PassthroughTransformer::$name(self, $($arg),*)
+        }
+    )*};
+}
+
+/// Transformer keeping the markdown of the elements it doesn't override, to change some
+/// elements of a document and leave the rest as it was written
+///
+/// The hooks are the ones of `MarkdownTransformer`, but their defaults write the markdown
+/// syntax of the element around its transformed content (`**text**` for bold text), using the
+/// `MarkdownRenderer` returned by `renderer`. Every `PassthroughTransformer` is a
+/// `MarkdownTransformer`, the hooks falling back to other ones (ex: `transform_diagram` to
+/// `transform_codeblock`) still do.
+///
+/// The `peek_*` hooks overridden are not given to the renderer, call the ones of the
+/// renderer from them for it to gather the reference definitions.
+///
+/// ```
+/// # use mdtrans::{transformers::{MarkdownRenderer, PassthroughTransformer}, Pipeline};
+/// #[derive(Default)]
+/// struct Shout {
+///     renderer: MarkdownRenderer,
+/// }
+/// impl PassthroughTransformer for Shout {
+///     fn renderer(&mut self) -> &mut MarkdownRenderer {
+///         &mut self.renderer
+///     }
+///     fn transform_bold(&mut self, text: String) -> String {
+///         format!("**{}**", text.to_uppercase())
+///     }
+/// }
+///
+/// let output = Pipeline::new().transformer(Shout::default()).run("# Title\n\n*a* **b**");
+/// assert_eq!(output.unwrap(), "# Title\n\n*a* **B**");
+/// ```
+#[allow(unused_variables)]
+pub trait PassthroughTransformer {
+    /// Renderer writing the markdown of the elements whose hook is not overridden
+    fn renderer(&mut self) -> &mut MarkdownRenderer;
+
+    renderer_hooks! {
+        element_context(ctx: &ElementContext);
+        localize(phrase: Phrase) -> String;
+        peek_text(text: String);
+        transform_text(text: String) -> String;
+        peek_header(level: usize, text: String);
+        transform_header(level: usize, text: String) -> String;
+        peek_bold(text: String);
+        transform_bold(text: String) -> String;
+        peek_italic(text: String);
+        transform_italic(text: String) -> String;
+        peek_reflink(text: String, slug: String);
+        transform_reflink(text: String, slug: String, title: Option<String>) -> String;
+        peek_refurl(slug: String, url: String, title: Option<String>);
+        transform_refurl(slug: String, url: String, title: Option<String>) -> String;
+        peek_link(text: String, url: String, kind: LinkKind);
+        transform_link(text: String, url: String, kind: LinkKind) -> String;
+        transform_autolink(text: String, url: String, kind: LinkKind) -> String;
+        peek_footnote_ref(label: String, number: usize);
+        transform_footnote_ref(label: String, number: usize) -> String;
+        transform_footnotes_section(entries: Vec<Footnote>) -> String;
+        peek_crossref(kind: CrossrefKind, slug: String, resolved: String);
+        transform_crossref(kind: CrossrefKind, slug: String, resolved: String) -> String;
+        peek_citation(keys: Vec<String>);
+        transform_citation(keys: Vec<String>) -> String;
+        transform_bibliography(keys: Vec<String>) -> String;
+        peek_shortcode(name: String, args: Vec<String>);
+        transform_shortcode(name: String, args: Vec<String>) -> String;
+        peek_image(alt: String, url: String, add_tags: ImageTags);
+        transform_image(alt: String, url: String, add_tags: ImageTags) -> String;
+        peek_figure(caption: String);
+        transform_figure(image: String, caption: String) -> String;
+        peek_comment(text: String);
+        transform_comment(text: String) -> String;
+        peek_raw_block(source: String);
+        transform_raw_block(source: String) -> String;
+        peek_html_block(tag: String, html: String);
+        transform_html_block(tag: String, html: String) -> String;
+        peek_strikethrough(text: String);
+        transform_strikethrough(text: String) -> String;
+        peek_quote(text: String);
+        transform_quote(text: String) -> String;
+        transform_alert(kind: AlertKind, body: String) -> String;
+        peek_codeblock(language: Option<String>, flags: Vec<String>, text: String);
+        transform_codeblock(language: Option<String>, flags: Vec<String>, text: String) -> String;
+        peek_inline_code(text: String);
+        transform_inline_code(text: String) -> String;
+        peek_horizontal_separator();
+        transform_horizontal_separator() -> String;
+        peek_list(elements: Vec<String>);
+        transform_list(elements: Vec<String>) -> String;
+        transform_ordered_list(start: usize, elements: Vec<String>) -> String;
+        peek_table(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>);
+        transform_table(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>) -> String;
+        peek_table_with_spans(header: Vec<TableCell>, alignments: Vec<Alignment>, rows: Vec<Vec<TableCell>>);
+        transform_table_with_spans(header: Vec<TableCell>, alignments: Vec<Alignment>, rows: Vec<Vec<TableCell>>) -> String;
+        peek_list_element(element: String, info: ListItemInfo);
+        transform_list_element(element: String, info: ListItemInfo) -> String;
+        peek_invalid(raw: String);
+        transform_invalid(raw: String) -> String;
+        enter_header(level: usize) -> String;
+        exit_header(level: usize) -> String;
+        enter_paragraph() -> String;
+        exit_paragraph() -> String;
+        enter_quote() -> String;
+        exit_quote() -> String;
+        enter_list() -> String;
+        exit_list() -> String;
+        enter_ordered_list(start: usize) -> String;
+        exit_ordered_list(start: usize) -> String;
+        enter_list_element(info: ListItemInfo) -> String;
+        exit_list_element(info: ListItemInfo) -> String;
+        enter_table() -> String;
+        exit_table() -> String;
+        peek_vertical_space();
+        transform_vertical_space() -> String;
+        peek_paragraph(text: String);
+        transform_paragraph(text: String) -> String;
+        finished(peek: bool) -> String;
+    }
+
+    fn peek_autolink(&mut self, text: String, url: String, kind: LinkKind) {
+        PassthroughTransformer::peek_link(self, text, url, kind)
+    }
+
+    fn peek_badge(&mut self, alt: String, img_url: String, target_url: String, kind: LinkKind) {
+        PassthroughTransformer::peek_image(self, alt, img_url, ImageTags::default());
+        PassthroughTransformer::peek_link(self, String::new(), target_url, kind)
+    }
+    fn transform_badge(
+        &mut self,
+        alt: String,
+        img_url: String,
+        target_url: String,
+        kind: LinkKind,
+    ) -> String {
+        let image =
+            PassthroughTransformer::transform_image(self, alt, img_url, ImageTags::default());
+        PassthroughTransformer::transform_link(self, image, target_url, kind)
+    }
+
+    fn peek_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) {
+        PassthroughTransformer::peek_image(self, alt, url, add_tags)
+    }
+    fn transform_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) -> String {
+        PassthroughTransformer::transform_image(self, alt, url, add_tags)
+    }
+
+    fn peek_alert(&mut self, kind: AlertKind, body: String) {
+        PassthroughTransformer::peek_quote(self, body)
+    }
+
+    fn peek_directive(&mut self, directive: Directive) {
+        PassthroughTransformer::peek_comment(self, format!("mdtrans: {}", directive.name()))
+    }
+    fn transform_directive(&mut self, directive: Directive) -> String {
+        PassthroughTransformer::transform_comment(self, format!("mdtrans: {}", directive.name()))
+    }
+
+    fn peek_details(&mut self, summary: String, body: String) {
+        PassthroughTransformer::peek_paragraph(self, summary)
+    }
+    fn transform_details(&mut self, summary: String, body: String) -> String {
+        let mut text = PassthroughTransformer::transform_paragraph(self, summary);
+        text += &body;
+        text
+    }
+
+    fn peek_tab_group(&mut self, tabs: Vec<(String, String)>) {
+        for (title, _) in tabs {
+            PassthroughTransformer::peek_paragraph(self, title);
+        }
+    }
+    fn transform_tab_group(&mut self, tabs: Vec<(String, String)>) -> String {
+        let mut text = String::new();
+        for (title, body) in tabs {
+            text += &PassthroughTransformer::transform_paragraph(self, title);
+            text += &body;
+        }
+        text
+    }
+
+    fn peek_codeblock_with_info(
+        &mut self,
+        language: Option<String>,
+        text: String,
+        info: CodeblockInfo,
+    ) {
+        PassthroughTransformer::peek_codeblock(self, language, info.flags, text)
+    }
+    fn transform_codeblock_with_info(
+        &mut self,
+        language: Option<String>,
+        text: String,
+        info: CodeblockInfo,
+    ) -> String {
+        PassthroughTransformer::transform_codeblock(self, language, info.flags, text)
+    }
+
+    fn peek_diagram(&mut self, kind: DiagramKind, source: String) {
+        let language = Some(kind.language().to_string());
+        PassthroughTransformer::peek_codeblock(self, language, vec![], source)
+    }
+    fn transform_diagram(&mut self, kind: DiagramKind, source: String) -> String {
+        let language = Some(kind.language().to_string());
+        PassthroughTransformer::transform_codeblock(self, language, vec![], source)
+    }
+
+    fn peek_ordered_list(&mut self, start: usize, elements: Vec<String>) {
+        PassthroughTransformer::peek_list(self, elements)
+    }
+
+    fn enter_paragraph_with_info(&mut self, info: ParagraphInfo) -> String {
+        PassthroughTransformer::enter_paragraph(self)
+    }
+    fn exit_paragraph_with_info(&mut self, info: ParagraphInfo) -> String {
+        PassthroughTransformer::exit_paragraph(self)
+    }
+    fn peek_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) {
+        PassthroughTransformer::peek_paragraph(self, text)
+    }
+    fn transform_paragraph_with_info(&mut self, text: String, info: ParagraphInfo) -> String {
+        PassthroughTransformer::transform_paragraph(self, text)
+    }
+}
+
+impl<T: PassthroughTransformer> MarkdownTransformer for T {
+    forward_hooks! {
+        element_context(ctx: &ElementContext);
+        localize(phrase: Phrase) -> String;
+        peek_text(text: String);
+        transform_text(text: String) -> String;
+        peek_header(level: usize, text: String);
+        transform_header(level: usize, text: String) -> String;
+        peek_bold(text: String);
+        transform_bold(text: String) -> String;
+        peek_italic(text: String);
+        transform_italic(text: String) -> String;
+        peek_reflink(text: String, slug: String);
+        transform_reflink(text: String, slug: String, title: Option<String>) -> String;
+        peek_refurl(slug: String, url: String, title: Option<String>);
+        transform_refurl(slug: String, url: String, title: Option<String>) -> String;
+        peek_link(text: String, url: String, kind: LinkKind);
+        transform_link(text: String, url: String, kind: LinkKind) -> String;
+        peek_autolink(text: String, url: String, kind: LinkKind);
+        peek_badge(alt: String, img_url: String, target_url: String, kind: LinkKind);
+        transform_badge(alt: String, img_url: String, target_url: String, kind: LinkKind) -> String;
+        transform_autolink(text: String, url: String, kind: LinkKind) -> String;
+        peek_footnote_ref(label: String, number: usize);
+        transform_footnote_ref(label: String, number: usize) -> String;
+        transform_footnotes_section(entries: Vec<Footnote>) -> String;
+        peek_crossref(kind: CrossrefKind, slug: String, resolved: String);
+        transform_crossref(kind: CrossrefKind, slug: String, resolved: String) -> String;
+        peek_citation(keys: Vec<String>);
+        transform_citation(keys: Vec<String>) -> String;
+        transform_bibliography(keys: Vec<String>) -> String;
+        peek_shortcode(name: String, args: Vec<String>);
+        transform_shortcode(name: String, args: Vec<String>) -> String;
+        peek_image(alt: String, url: String, add_tags: ImageTags);
+        transform_image(alt: String, url: String, add_tags: ImageTags) -> String;
+        peek_responsive_image(alt: String, url: String, candidates: Vec<ImageCandidate>, add_tags: ImageTags);
+        transform_responsive_image(alt: String, url: String, candidates: Vec<ImageCandidate>, add_tags: ImageTags) -> String;
+        peek_figure(caption: String);
+        transform_figure(image: String, caption: String) -> String;
+        peek_comment(text: String);
+        transform_comment(text: String) -> String;
+        peek_directive(directive: Directive);
+        transform_directive(directive: Directive) -> String;
+        peek_raw_block(source: String);
+        transform_raw_block(source: String) -> String;
+        peek_html_block(tag: String, html: String);
+        transform_html_block(tag: String, html: String) -> String;
+        peek_strikethrough(text: String);
+        transform_strikethrough(text: String) -> String;
+        peek_quote(text: String);
+        transform_quote(text: String) -> String;
+        peek_alert(kind: AlertKind, body: String);
+        transform_alert(kind: AlertKind, body: String) -> String;
+        peek_details(summary: String, body: String);
+        transform_details(summary: String, body: String) -> String;
+        peek_tab_group(tabs: Vec<(String, String)>);
+        transform_tab_group(tabs: Vec<(String, String)>) -> String;
+        peek_codeblock(language: Option<String>, flags: Vec<String>, text: String);
+        transform_codeblock(language: Option<String>, flags: Vec<String>, text: String) -> String;
+        peek_codeblock_with_info(language: Option<String>, text: String, info: CodeblockInfo);
+        transform_codeblock_with_info(language: Option<String>, text: String, info: CodeblockInfo) -> String;
+        peek_diagram(kind: DiagramKind, source: String);
+        transform_diagram(kind: DiagramKind, source: String) -> String;
+        peek_inline_code(text: String);
+        transform_inline_code(text: String) -> String;
+        peek_horizontal_separator();
+        transform_horizontal_separator() -> String;
+        peek_list(elements: Vec<String>);
+        transform_list(elements: Vec<String>) -> String;
+        peek_ordered_list(start: usize, elements: Vec<String>);
+        transform_ordered_list(start: usize, elements: Vec<String>) -> String;
+        peek_table(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>);
+        transform_table(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>) -> String;
+        peek_table_with_spans(header: Vec<TableCell>, alignments: Vec<Alignment>, rows: Vec<Vec<TableCell>>);
+        transform_table_with_spans(header: Vec<TableCell>, alignments: Vec<Alignment>, rows: Vec<Vec<TableCell>>) -> String;
+        peek_list_element(element: String, info: ListItemInfo);
+        transform_list_element(element: String, info: ListItemInfo) -> String;
+        peek_invalid(raw: String);
+        transform_invalid(raw: String) -> String;
+        enter_header(level: usize) -> String;
+        exit_header(level: usize) -> String;
+        enter_paragraph() -> String;
+        exit_paragraph() -> String;
+        enter_quote() -> String;
+        exit_quote() -> String;
+        enter_list() -> String;
+        exit_list() -> String;
+        enter_ordered_list(start: usize) -> String;
+        exit_ordered_list(start: usize) -> String;
+        enter_list_element(info: ListItemInfo) -> String;
+        exit_list_element(info: ListItemInfo) -> String;
+        enter_table() -> String;
+        exit_table() -> String;
+        peek_vertical_space();
+        transform_vertical_space() -> String;
+        peek_paragraph(text: String);
+        transform_paragraph(text: String) -> String;
+        enter_paragraph_with_info(info: ParagraphInfo) -> String;
+        exit_paragraph_with_info(info: ParagraphInfo) -> String;
+        peek_paragraph_with_info(text: String, info: ParagraphInfo);
+        transform_paragraph_with_info(text: String, info: ParagraphInfo) -> String;
+        finished(peek: bool) -> String;
+    }
+}
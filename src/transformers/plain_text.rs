@@ -0,0 +1,280 @@
+use super::Blocks;
+use crate::prelude::*;
+use crate::{
+    format_table, AlertKind, Alignment, ElementContext, Footnote, ImageTags, LinkKind,
+    ListItemInfo, Localizer, MarkdownTransformer, Phrase, StatefulTransformer,
+};
+
+/// Transformer rendering the document as wrapped plain text, like the `text/plain`
+/// alternative of an HTML email
+///
+/// Formatting is dropped, links are written as `text <url>`, quotes keep their `>` markers
+/// and paragraphs are wrapped at 72 columns by default.
+#[derive(Clone)]
+pub struct PlainTextRenderer {
+    width: usize,
+    localizer: Localizer,
+    blocks: Blocks,
+    // Width of the markers of the list elements being transformed
+    indents: Vec<usize>,
+    refs: HashMap<String, String>,
+}
+
+impl Default for PlainTextRenderer {
+    fn default() -> Self {
+        PlainTextRenderer {
+            width: 72,
+            localizer: Localizer::default(),
+            blocks: Blocks::default(),
+            indents: vec![],
+            refs: HashMap::new(),
+        }
+    }
+}
+
+impl PlainTextRenderer {
+    /// Maximum number of columns of the lines of text
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Translation of the titles of the alerts
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = localizer;
+        self
+    }
+
+    // Columns left for a block, once indented under the list elements containing it
+    fn available_width(&self, prefix: usize) -> usize {
+        let indent = self.indents.iter().sum::<usize>() + prefix;
+        self.width.saturating_sub(indent).max(1)
+    }
+}
+
+fn list_marker(info: &ListItemInfo) -> String {
+    let marker = if info.ordered {
+        format!("{} ", info.marker)
+    } else {
+        "- ".to_string()
+    };
+    match info.checked {
+        Some(true) => format!("{marker}[x] "),
+        Some(false) => format!("{marker}[ ] "),
+        None => marker,
+    }
+}
+
+// Greedy word wrapping, line breaks of the text being kept
+fn wrap_text(text: &str, width: usize) -> String {
+    let mut lines = vec![];
+    for segment in text.split('\n') {
+        let mut current = String::new();
+        for word in segment.split_whitespace() {
+            let len = current.chars().count();
+            if len > 0 && len + 1 + word.chars().count() > width {
+                lines.push(core::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        lines.push(current);
+    }
+    lines.join("\n")
+}
+
+fn prefix_lines(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{prefix}{line}").trim_end().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+impl MarkdownTransformer for PlainTextRenderer {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.blocks.context(ctx);
+    }
+
+    fn localize(&mut self, phrase: Phrase) -> String {
+        self.localizer.text(phrase)
+    }
+
+    fn transform_header(&mut self, level: usize, text: String) -> String {
+        let text = wrap_text(&text, self.available_width(0));
+        let underline = match level {
+            1 => Some('='),
+            2 => Some('-'),
+            _ => None,
+        };
+        match underline {
+            Some(c) => {
+                let len = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+                self.blocks
+                    .push(format!("{text}\n{}", c.to_string().repeat(len)))
+            }
+            None => self.blocks.push(text),
+        }
+    }
+
+    fn peek_refurl(&mut self, slug: String, url: String, _title: Option<String>) {
+        self.refs.insert(slug, url);
+    }
+
+    fn transform_reflink(&mut self, text: String, slug: String, _title: Option<String>) -> String {
+        match self.refs.get(&slug) {
+            Some(url) => format!("{text} <{url}>"),
+            None => text,
+        }
+    }
+
+    fn transform_link(&mut self, text: String, url: String, _kind: LinkKind) -> String {
+        if text == url {
+            format!("<{url}>")
+        } else {
+            format!("{text} <{url}>")
+        }
+    }
+
+    fn transform_autolink(&mut self, _text: String, url: String, _kind: LinkKind) -> String {
+        format!("<{url}>")
+    }
+
+    fn transform_footnote_ref(&mut self, label: String, _number: usize) -> String {
+        format!("[{label}]")
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let width = self.available_width(0);
+        let entries = entries
+            .into_iter()
+            .map(|f| wrap_text(&format!("[{}] {}", f.label, f.text), width))
+            .collect::<Vec<String>>();
+        self.blocks.push(entries.join("\n"))
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, _add_tags: ImageTags) -> String {
+        if alt.is_empty() {
+            format!("<{url}>")
+        } else {
+            format!("{alt} <{url}>")
+        }
+    }
+
+    fn transform_comment(&mut self, _text: String) -> String {
+        String::new()
+    }
+
+    fn transform_html_block(&mut self, _tag: String, _html: String) -> String {
+        String::new()
+    }
+
+    // NOTE    Quote lines are soft line breaks, the quote is wrapped as a whole
+    fn transform_quote(&mut self, text: String) -> String {
+        let text = text.lines().collect::<Vec<&str>>().join(" ");
+        let text = wrap_text(&text, self.available_width(2));
+        self.blocks.push(prefix_lines(&text, "> "))
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let label = self.localize(Phrase::Alert(kind)).to_uppercase();
+        self.transform_quote(format!("{label}: {body}"))
+    }
+
+    fn transform_invalid(&mut self, raw: String) -> String {
+        self.blocks.push(raw)
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        _language: Option<String>,
+        _flags: Vec<String>,
+        text: String,
+    ) -> String {
+        self.blocks.push(prefix_lines(&text, "    "))
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        let width = self.available_width(0);
+        self.blocks.push("-".repeat(width))
+    }
+
+    fn enter_list_element(&mut self, info: ListItemInfo) -> String {
+        self.indents.push(list_marker(&info).len());
+        String::new()
+    }
+
+    fn exit_list_element(&mut self, _info: ListItemInfo) -> String {
+        self.indents.pop();
+        String::new()
+    }
+
+    fn transform_list_element(&mut self, element: String, info: ListItemInfo) -> String {
+        let marker = list_marker(&info);
+        let indent = " ".repeat(marker.len());
+        let (text, blocks) = element.split_once("\n\n").unwrap_or((&element, ""));
+        let text = wrap_text(text.trim(), self.available_width(0));
+        let lines = text
+            .lines()
+            .chain(core::iter::once(""))
+            .chain(blocks.lines())
+            .map(|line| match line {
+                "" => String::new(),
+                line => format!("{indent}{line}"),
+            })
+            .collect::<Vec<String>>();
+        format!("{marker}{}", lines.join("\n").trim())
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        self.blocks.push(elements.join("\n"))
+    }
+
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let mut all_rows = vec![header];
+        all_rows.extend(rows);
+        self.blocks.push(format_table(&all_rows, &alignments))
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        "\n".to_string()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        let text = wrap_text(text.trim(), self.available_width(0));
+        self.blocks.push(text)
+    }
+
+    fn finished(&mut self, _peek: bool) -> String {
+        self.blocks.reset();
+        self.indents.clear();
+        String::new()
+    }
+}
+
+impl StatefulTransformer for PlainTextRenderer {
+    type Snapshot = PlainTextRenderer;
+
+    fn reset(&mut self) {
+        self.blocks.reset();
+        self.indents.clear();
+        self.refs.clear();
+    }
+
+    fn snapshot(&self) -> PlainTextRenderer {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: PlainTextRenderer) {
+        *self = snapshot;
+    }
+}
@@ -0,0 +1,256 @@
+use super::Blocks;
+use crate::prelude::*;
+use crate::{
+    AlertKind, Alignment, ElementContext, Footnote, ImageTags, LinkKind, ListItemInfo, Localizer,
+    MarkdownTransformer, Phrase, StatefulTransformer,
+};
+
+/// How code blocks are rendered in speech
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodeBlockSpeech {
+    /// Say that a code sample (in the language of the block) was omitted
+    #[default]
+    Announce,
+    /// Leave code blocks out entirely
+    Skip,
+}
+
+/// Transformer rendering the document as [SSML](https://www.w3.org/TR/speech-synthesis11/),
+/// the input of speech synthesizers
+///
+/// Emphasis is spoken with `<emphasis>`, blocks are separated by pauses and code blocks,
+/// which don't read well, are announced or skipped.
+#[derive(Clone, Default)]
+pub struct SsmlRenderer {
+    code_blocks: CodeBlockSpeech,
+    localizer: Localizer,
+    blocks: Blocks,
+}
+
+impl SsmlRenderer {
+    pub fn code_blocks(mut self, speech: CodeBlockSpeech) -> Self {
+        self.code_blocks = speech;
+        self
+    }
+
+    /// Translation of the announces of quotes, images, footnotes and code blocks
+    pub fn localizer(mut self, localizer: Localizer) -> Self {
+        self.localizer = localizer;
+        self
+    }
+
+    // The first block opens the `<speak>` root element, closed once the document is finished
+    fn block(&mut self, text: String) -> String {
+        if text.is_empty() {
+            return text;
+        }
+        match self.blocks.count() {
+            Some(true) => format!("<speak>{text}"),
+            _ => text,
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn sentence(text: &str) -> String {
+    let text = text.trim();
+    if text.ends_with(['.', '!', '?', ':']) {
+        text.to_string()
+    } else {
+        format!("{text}.")
+    }
+}
+
+impl MarkdownTransformer for SsmlRenderer {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        self.blocks.context(ctx);
+    }
+
+    fn localize(&mut self, phrase: Phrase) -> String {
+        escape(&self.localizer.text(phrase))
+    }
+
+    fn transform_text(&mut self, text: String) -> String {
+        escape(&text)
+    }
+
+    fn transform_header(&mut self, _level: usize, text: String) -> String {
+        self.block(format!(
+            "<p><emphasis level=\"strong\">{text}</emphasis></p><break time=\"500ms\"/>"
+        ))
+    }
+
+    fn transform_bold(&mut self, text: String) -> String {
+        format!("<emphasis level=\"strong\">{text}</emphasis>")
+    }
+
+    fn transform_italic(&mut self, text: String) -> String {
+        format!("<emphasis>{text}</emphasis>")
+    }
+
+    fn transform_link(&mut self, text: String, _url: String, _kind: LinkKind) -> String {
+        text
+    }
+
+    // NOTE    URLs are not worth spelling out, only their presence is
+    fn transform_autolink(&mut self, _text: String, _url: String, _kind: LinkKind) -> String {
+        self.localize(Phrase::Link)
+    }
+
+    fn transform_footnote_ref(&mut self, _label: String, _number: usize) -> String {
+        String::new()
+    }
+
+    fn transform_footnotes_section(&mut self, entries: Vec<Footnote>) -> String {
+        let note = self.localize(Phrase::Footnote);
+        let entries = entries
+            .into_iter()
+            .map(|f| format!("<s>{note} {}: {}</s>", f.number, sentence(&f.text)))
+            .collect::<String>();
+        let title = sentence(&self.localize(Phrase::Footnotes));
+        self.block(format!("<p>{title}</p><p>{entries}</p>"))
+    }
+
+    fn transform_image(&mut self, alt: String, _url: String, _add_tags: ImageTags) -> String {
+        if alt.is_empty() {
+            String::new()
+        } else {
+            format!("{}: {}", self.localize(Phrase::Image), escape(&alt))
+        }
+    }
+
+    fn transform_figure(&mut self, image: String, caption: String) -> String {
+        format!("{}, {}", image, escape(&caption))
+    }
+
+    fn transform_comment(&mut self, _text: String) -> String {
+        String::new()
+    }
+
+    fn transform_html_block(&mut self, _tag: String, _html: String) -> String {
+        String::new()
+    }
+
+    fn transform_quote(&mut self, text: String) -> String {
+        let text = text.lines().collect::<Vec<&str>>().join(" ");
+        let start = sentence(&self.localize(Phrase::QuoteStart));
+        let end = sentence(&self.localize(Phrase::QuoteEnd));
+        self.block(format!(
+            "<p>{start}</p><p>{}</p><p>{end}</p>",
+            sentence(&text)
+        ))
+    }
+
+    fn transform_alert(&mut self, kind: AlertKind, body: String) -> String {
+        let label = self.localize(Phrase::Alert(kind));
+        let body = body.lines().collect::<Vec<&str>>().join(" ");
+        self.block(format!(
+            "<p><emphasis level=\"strong\">{label}:</emphasis> {}</p>",
+            sentence(&body)
+        ))
+    }
+
+    fn transform_invalid(&mut self, raw: String) -> String {
+        self.block(format!("<p>{}</p>", escape(&raw)))
+    }
+
+    fn transform_codeblock(
+        &mut self,
+        language: Option<String>,
+        _flags: Vec<String>,
+        _text: String,
+    ) -> String {
+        let announce = match (self.code_blocks, language) {
+            (CodeBlockSpeech::Skip, _) => return String::new(),
+            (CodeBlockSpeech::Announce, Some(language)) => sentence(
+                &self
+                    .localize(Phrase::CodeOmittedIn)
+                    .replace("{}", &escape(&language)),
+            ),
+            (CodeBlockSpeech::Announce, None) => sentence(&self.localize(Phrase::CodeOmitted)),
+        };
+        self.block(format!("<p>{announce}</p>"))
+    }
+
+    fn transform_horizontal_separator(&mut self) -> String {
+        self.block("<break time=\"1s\"/>".to_string())
+    }
+
+    // NOTE    Blocks nested in the element follow its sentence, a sentence can't contain them
+    fn transform_list_element(&mut self, element: String, _info: ListItemInfo) -> String {
+        let start = [element.find("<p>"), element.find("<s>")]
+            .into_iter()
+            .flatten()
+            .min();
+        let (text, blocks) = element.split_at(start.unwrap_or(element.len()));
+        format!("<s>{}</s>{blocks}", sentence(text))
+    }
+
+    fn transform_list(&mut self, elements: Vec<String>) -> String {
+        self.block(elements.concat())
+    }
+
+    // Rows are read as sentences pairing each cell with its column
+    fn transform_table(
+        &mut self,
+        header: Vec<String>,
+        _alignments: Vec<Alignment>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let cells = header
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| format!("{column}: {cell}"))
+                    .collect::<Vec<String>>();
+                format!("<s>{}</s>", sentence(&cells.join(", ")))
+            })
+            .collect::<String>();
+        self.block(format!("<p>{rows}</p>"))
+    }
+
+    fn transform_vertical_space(&mut self) -> String {
+        "<break/>".to_string()
+    }
+
+    fn transform_paragraph(&mut self, text: String) -> String {
+        if text.trim().is_empty() {
+            return String::new();
+        }
+        self.block(format!("<p>{}</p>", text.trim()))
+    }
+
+    fn finished(&mut self, peek: bool) -> String {
+        let started = self.blocks.is_started();
+        self.blocks.reset();
+        match (peek, started) {
+            (true, _) => String::new(),
+            (false, true) => "</speak>".to_string(),
+            (false, false) => "<speak></speak>".to_string(),
+        }
+    }
+}
+
+impl StatefulTransformer for SsmlRenderer {
+    type Snapshot = SsmlRenderer;
+
+    fn reset(&mut self) {
+        self.blocks.reset();
+    }
+
+    fn snapshot(&self) -> SsmlRenderer {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: SsmlRenderer) {
+        *self = snapshot;
+    }
+}
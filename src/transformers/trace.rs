@@ -0,0 +1,307 @@
+use crate::prelude::*;
+use crate::{
+    AlertKind, Alignment, CodeblockInfo, CrossrefKind, DiagramKind, Directive, ElementContext,
+    Footnote, ImageCandidate, ImageTags, LinkKind, ListItemInfo, MarkdownTransformer,
+    ParagraphInfo, Phrase, StatefulTransformer, TableCell,
+};
+
+/// Call of a transformer hook, recorded by the `TraceTransformer`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookCall {
+    /// Name of the hook, like `transform_link`
+    pub name: &'static str,
+    /// Arguments given to the hook, formatted with `Debug`
+    pub args: Vec<String>,
+    /// Output of the hook, `None` for the hooks returning nothing
+    pub output: Option<String>,
+}
+
+impl core::fmt::Display for HookCall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}({})", self.name, self.args.join(", "))?;
+        if let Some(ref output) = self.output {
+            write!(f, " -> {output:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Transformer recording every hook called on the transformer it wraps, with their
+/// arguments and outputs, to see how a document gets transformed
+///
+/// The calls of both the peek and the transform passes are recorded, `finished` ending each.
+#[derive(Clone)]
+pub struct TraceTransformer<T: MarkdownTransformer> {
+    inner: T,
+    context: bool,
+    calls: Vec<HookCall>,
+}
+
+impl<T: MarkdownTransformer> TraceTransformer<T> {
+    pub fn new(inner: T) -> TraceTransformer<T> {
+        TraceTransformer {
+            inner,
+            context: true,
+            calls: vec![],
+        }
+    }
+
+    /// Whether the calls of `element_context` are recorded, which they are by default
+    pub fn context(mut self, record: bool) -> Self {
+        self.context = record;
+        self
+    }
+
+    pub fn calls(&self) -> &[HookCall] {
+        &self.calls
+    }
+
+    pub fn clear(&mut self) {
+        self.calls.clear();
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// One call per line, indented between the `enter_*` and `exit_*` hooks
+    pub fn pretty(&self) -> String {
+        let mut depth = 0_usize;
+        let mut lines = vec![];
+        for call in self.calls.iter() {
+            if call.name.starts_with("exit_") {
+                depth = depth.saturating_sub(1);
+            }
+            lines.push(format!("{}{call}", "  ".repeat(depth)));
+            if call.name.starts_with("enter_") {
+                depth += 1;
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn record(&mut self, name: &'static str, args: Vec<String>, output: Option<String>) {
+        self.calls.push(HookCall { name, args, output });
+    }
+}
+
+// Hooks recorded before being forwarded to the inner transformer
+macro_rules! peek_hooks {
+    ($($name:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $name(&mut self, $($arg: $ty),*) {
+            self.record(stringify!($name), vec![$(format!("{:?}", $arg)),*], None);
+            self.inner.$name($($arg),*)
+        }
+    )*};
+}
+
+// Hooks forwarded to the inner transformer, recorded with their output
+macro_rules! transform_hooks {
+    ($($name:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $name(&mut self, $($arg: $ty),*) -> String {
+            let args = vec![$(format!("{:?}", $arg)),*];
+            let output = self.inner.$name($($arg),*);
+            self.record(stringify!($name), args, Some(output.clone()));
+            output
+        }
+    )*};
+}
+
+impl<T: MarkdownTransformer> MarkdownTransformer for TraceTransformer<T> {
+    fn element_context(&mut self, ctx: &ElementContext) {
+        if self.context {
+            let span = ctx.span();
+            let args = vec![
+                format!("{:?}", ctx.rule()),
+                format!("{}:{}", span.line, span.column),
+            ];
+            self.record("element_context", args, None);
+        }
+        self.inner.element_context(ctx)
+    }
+
+    // NOTE    Not a hook of the passes, the phrases are not recorded
+    fn localize(&mut self, phrase: Phrase) -> String {
+        self.inner.localize(phrase)
+    }
+
+    fn peek_image(&mut self, alt: String, url: String, add_tags: ImageTags) {
+        let args = vec![
+            format!("{alt:?}"),
+            format!("{url:?}"),
+            format!("{add_tags:?}"),
+        ];
+        self.record("peek_image", args, None);
+        self.inner.peek_image(alt, url, add_tags)
+    }
+
+    fn transform_image(&mut self, alt: String, url: String, add_tags: ImageTags) -> String {
+        let args = vec![
+            format!("{alt:?}"),
+            format!("{url:?}"),
+            format!("{add_tags:?}"),
+        ];
+        let output = self.inner.transform_image(alt, url, add_tags);
+        self.record("transform_image", args, Some(output.clone()));
+        output
+    }
+
+    fn peek_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) {
+        let args = vec![
+            format!("{alt:?}"),
+            format!("{url:?}"),
+            format!("{candidates:?}"),
+            format!("{add_tags:?}"),
+        ];
+        self.record("peek_responsive_image", args, None);
+        self.inner
+            .peek_responsive_image(alt, url, candidates, add_tags)
+    }
+
+    fn transform_responsive_image(
+        &mut self,
+        alt: String,
+        url: String,
+        candidates: Vec<ImageCandidate>,
+        add_tags: ImageTags,
+    ) -> String {
+        let args = vec![
+            format!("{alt:?}"),
+            format!("{url:?}"),
+            format!("{candidates:?}"),
+            format!("{add_tags:?}"),
+        ];
+        let output = self
+            .inner
+            .transform_responsive_image(alt, url, candidates, add_tags);
+        self.record("transform_responsive_image", args, Some(output.clone()));
+        output
+    }
+
+    peek_hooks! {
+        peek_text(text: String);
+        peek_header(level: usize, text: String);
+        peek_bold(text: String);
+        peek_italic(text: String);
+        peek_reflink(text: String, slug: String);
+        peek_refurl(slug: String, url: String, title: Option<String>);
+        peek_link(text: String, url: String, kind: LinkKind);
+        peek_autolink(text: String, url: String, kind: LinkKind);
+        peek_badge(alt: String, img_url: String, target_url: String, kind: LinkKind);
+        peek_footnote_ref(label: String, number: usize);
+        peek_crossref(kind: CrossrefKind, slug: String, resolved: String);
+        peek_citation(keys: Vec<String>);
+        peek_shortcode(name: String, args: Vec<String>);
+        peek_figure(caption: String);
+        peek_comment(text: String);
+        peek_directive(directive: Directive);
+        peek_raw_block(source: String);
+        peek_html_block(tag: String, html: String);
+        peek_strikethrough(text: String);
+        peek_quote(text: String);
+        peek_alert(kind: AlertKind, body: String);
+        peek_details(summary: String, body: String);
+        peek_tab_group(tabs: Vec<(String, String)>);
+        peek_codeblock(language: Option<String>, flags: Vec<String>, text: String);
+        peek_codeblock_with_info(language: Option<String>, text: String, info: CodeblockInfo);
+        peek_diagram(kind: DiagramKind, source: String);
+        peek_inline_code(text: String);
+        peek_horizontal_separator();
+        peek_list(elements: Vec<String>);
+        peek_ordered_list(start: usize, elements: Vec<String>);
+        peek_table(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>);
+        peek_table_with_spans(header: Vec<TableCell>, alignments: Vec<Alignment>, rows: Vec<Vec<TableCell>>);
+        peek_list_element(element: String, info: ListItemInfo);
+        peek_invalid(raw: String);
+        peek_vertical_space();
+        peek_paragraph(text: String);
+        peek_paragraph_with_info(text: String, info: ParagraphInfo);
+    }
+
+    transform_hooks! {
+        transform_text(text: String);
+        transform_header(level: usize, text: String);
+        transform_bold(text: String);
+        transform_italic(text: String);
+        transform_reflink(text: String, slug: String, title: Option<String>);
+        transform_refurl(slug: String, url: String, title: Option<String>);
+        transform_link(text: String, url: String, kind: LinkKind);
+        transform_autolink(text: String, url: String, kind: LinkKind);
+        transform_badge(alt: String, img_url: String, target_url: String, kind: LinkKind);
+        transform_footnote_ref(label: String, number: usize);
+        transform_footnotes_section(entries: Vec<Footnote>);
+        transform_crossref(kind: CrossrefKind, slug: String, resolved: String);
+        transform_citation(keys: Vec<String>);
+        transform_bibliography(keys: Vec<String>);
+        transform_shortcode(name: String, args: Vec<String>);
+        transform_figure(image: String, caption: String);
+        transform_comment(text: String);
+        transform_directive(directive: Directive);
+        transform_raw_block(source: String);
+        transform_html_block(tag: String, html: String);
+        transform_strikethrough(text: String);
+        transform_quote(text: String);
+        transform_alert(kind: AlertKind, body: String);
+        transform_details(summary: String, body: String);
+        transform_tab_group(tabs: Vec<(String, String)>);
+        transform_codeblock(language: Option<String>, flags: Vec<String>, text: String);
+        transform_codeblock_with_info(language: Option<String>, text: String, info: CodeblockInfo);
+        transform_diagram(kind: DiagramKind, source: String);
+        transform_inline_code(text: String);
+        transform_horizontal_separator();
+        transform_list(elements: Vec<String>);
+        transform_ordered_list(start: usize, elements: Vec<String>);
+        transform_table(header: Vec<String>, alignments: Vec<Alignment>, rows: Vec<Vec<String>>);
+        transform_table_with_spans(header: Vec<TableCell>, alignments: Vec<Alignment>, rows: Vec<Vec<TableCell>>);
+        transform_list_element(element: String, info: ListItemInfo);
+        transform_invalid(raw: String);
+        enter_header(level: usize);
+        exit_header(level: usize);
+        enter_paragraph();
+        exit_paragraph();
+        enter_quote();
+        exit_quote();
+        enter_list();
+        exit_list();
+        enter_ordered_list(start: usize);
+        exit_ordered_list(start: usize);
+        enter_list_element(info: ListItemInfo);
+        exit_list_element(info: ListItemInfo);
+        enter_table();
+        exit_table();
+        transform_vertical_space();
+        transform_paragraph(text: String);
+        enter_paragraph_with_info(info: ParagraphInfo);
+        exit_paragraph_with_info(info: ParagraphInfo);
+        transform_paragraph_with_info(text: String, info: ParagraphInfo);
+        finished(peek: bool);
+    }
+}
+
+// NOTE    The calls recorded are kept when restoring a snapshot, they trace what happened
+impl<T: StatefulTransformer> StatefulTransformer for TraceTransformer<T> {
+    type Snapshot = T::Snapshot;
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.calls.clear();
+    }
+
+    fn snapshot(&self) -> T::Snapshot {
+        self.inner.snapshot()
+    }
+
+    fn restore(&mut self, snapshot: T::Snapshot) {
+        self.inner.restore(snapshot);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    context::IdGenerator, crossref::Crossrefs, numbering::Numbering, Document, ElementContext,
+    Errcode, Node,
+};
+
+/// Callbacks of `walk_markdown`, called for every element of the document in source order
+///
+/// Unlike a `MarkdownTransformer`, nothing is rendered: the visitor only gets the location,
+/// rule and source of each element, which is enough for analysis passes (statistics,
+/// link graphs, ...).
+#[allow(unused_variables)]
+pub trait MarkdownVisitor {
+    /// Start of an element, before the elements it contains
+    fn enter(&mut self, ctx: &ElementContext) {}
+    /// End of an element, after the elements it contains
+    fn leave(&mut self, ctx: &ElementContext) {}
+}
+
+// NOTE    IDs are the same as the ones given to a transformer, as they only depend on
+//         the identical elements preceding each one
+pub(crate) fn walk_node<V: MarkdownVisitor>(
+    node: &Node,
+    visitor: &mut V,
+    ids: &mut IdGenerator,
+    numbering: &Numbering,
+    crossrefs: &Crossrefs,
+) {
+    let id = ids.next(node.rule(), node.as_str());
+    let ctx = ElementContext::new(node.rule(), node.span(), node.as_str(), id, node.ordinal())
+        .with_number(numbering.get(node))
+        .with_anchor(crossrefs.anchor(node));
+    visitor.enter(&ctx);
+    for child in node.children() {
+        walk_node(child, visitor, ids, numbering, crossrefs);
+    }
+    visitor.leave(&ctx);
+}
+
+/// Parse the markdown input and call the visitor on each of its elements
+pub fn walk_markdown<V: MarkdownVisitor>(input: &str, visitor: &mut V) -> Result<(), Errcode> {
+    Document::parse(input)?.walk(visitor);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::{prelude::*, ElementKind, Span};
+
+/// Content issue found while transforming a document, that doesn't prevent its transformation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// Reference link or footnote whose label is not defined in the document
+    UnresolvedReference { label: String, span: Span },
+    /// Language of a code block that is not part of `TransformOptions::known_languages`
+    UnknownLanguage { language: String, span: Span },
+    /// Link without any text to display
+    EmptyLinkText { url: String, span: Span },
+    /// Image without alternative text
+    MissingAltText { url: String, span: Span },
+    /// Header or list element without any content (`EmptyElements::Warn`)
+    EmptyElement { kind: ElementKind, span: Span },
+    /// Block that couldn't be parsed, kept as plain text (`ParseMode::Lenient`)
+    InvalidSyntax {
+        expected: Vec<ElementKind>,
+        span: Span,
+    },
+    /// Image tag not allowed by `TransformOptions::image_tag_schema`, `reason` being
+    /// `unknown tag` or the error of the validator of its value
+    InvalidImageTag {
+        key: String,
+        reason: String,
+        span: Span,
+    },
+    /// File included in a code block that couldn't be loaded, or whose line range is invalid
+    IncludeFailed {
+        path: String,
+        error: String,
+        span: Span,
+    },
+    /// Variable `{{ name }}` without a value in `TransformOptions::variables`
+    UnknownVariable { name: String, span: Span },
+    /// `[FOOTNOTES]` or `[BIBLIOGRAPHY]` marker written after the section was already
+    /// emitted, left out of the output
+    DuplicateMarker { kind: ElementKind, span: Span },
+}
+
+impl Warning {
+    /// Location of the element the warning is about
+    pub fn span(&self) -> Span {
+        match self {
+            Warning::UnresolvedReference { span, .. }
+            | Warning::UnknownLanguage { span, .. }
+            | Warning::EmptyLinkText { span, .. }
+            | Warning::MissingAltText { span, .. }
+            | Warning::EmptyElement { span, .. }
+            | Warning::InvalidSyntax { span, .. }
+            | Warning::InvalidImageTag { span, .. }
+            | Warning::IncludeFailed { span, .. }
+            | Warning::UnknownVariable { span, .. }
+            | Warning::DuplicateMarker { span, .. } => *span,
+        }
+    }
+}
+
+impl core::fmt::Display for Warning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let span = self.span();
+        write!(f, "{}:{}: ", span.line, span.column)?;
+        match self {
+            Warning::UnresolvedReference { label, .. } => {
+                write!(f, "reference \"{label}\" is not defined")
+            }
+            Warning::UnknownLanguage { language, .. } => {
+                write!(f, "unknown code block language \"{language}\"")
+            }
+            Warning::EmptyLinkText { url, .. } => write!(f, "link to \"{url}\" has no text"),
+            Warning::MissingAltText { url, .. } => {
+                write!(f, "image \"{url}\" has no alternative text")
+            }
+            Warning::EmptyElement { kind, .. } => match kind {
+                ElementKind::Header(level) => write!(f, "level {level} header has no content"),
+                ElementKind::ListItem => write!(f, "list element has no content"),
+                kind => write!(f, "{kind:?} element has no content"),
+            },
+            Warning::InvalidSyntax { expected, .. } => {
+                write!(
+                    f,
+                    "invalid syntax kept as plain text, expected {expected:?}"
+                )
+            }
+            Warning::InvalidImageTag { key, reason, .. } => {
+                write!(f, "image tag \"{key}\": {reason}")
+            }
+            Warning::IncludeFailed { path, error, .. } => {
+                write!(f, "cannot include \"{path}\": {error}")
+            }
+            Warning::UnknownVariable { name, .. } => write!(f, "variable \"{name}\" has no value"),
+            Warning::DuplicateMarker { kind, .. } => match kind {
+                ElementKind::FootnotesMarker => {
+                    write!(
+                        f,
+                        "[FOOTNOTES] marker ignored, the footnotes are already emitted"
+                    )
+                }
+                _ => write!(
+                    f,
+                    "[BIBLIOGRAPHY] marker ignored, the bibliography is already emitted"
+                ),
+            },
+        }
+    }
+}